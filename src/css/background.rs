@@ -0,0 +1,460 @@
+use super::calc::CalcExpr;
+use super::color::parse_color;
+use super::parser::{parse_angle, parse_length, split_commas, split_components};
+use super::styles::{
+    BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize, BoxArea, Color,
+    ColorStop, GradientDirection, Length, LinearGradient, RadialExtent, RadialGradient, RadialSize,
+    Repeat, Style,
+};
+
+// Propriétés `background-*` à liste : une valeur par couche, séparées par des virgules
+pub fn apply_background(property: &str, value: &str, style: &mut Style) {
+    let layers = split_commas(value);
+    match property {
+        "background" => {
+            if let Some(parsed) = parse_shorthand(&layers) {
+                style.background = parsed.background;
+                style.background_image = parsed.background_image;
+                style.background_position = parsed.background_position;
+                style.background_size = parsed.background_size;
+                style.background_repeat = parsed.background_repeat;
+                style.background_origin = parsed.background_origin;
+                style.background_clip = parsed.background_clip;
+            }
+        }
+        "background-image" => {
+            if let Some(images) = parse_list(&layers, parse_image) {
+                style.background_image = images;
+            }
+        }
+        "background-position" => {
+            if let Some(positions) = parse_list(&layers, |v| parse_position(&split_components(v))) {
+                style.background_position = positions;
+            }
+        }
+        "background-size" => {
+            if let Some(sizes) = parse_list(&layers, |v| parse_size(&split_components(v))) {
+                style.background_size = sizes;
+            }
+        }
+        "background-repeat" => {
+            if let Some(repeats) = parse_list(&layers, |v| parse_repeat(&split_components(v))) {
+                style.background_repeat = repeats;
+            }
+        }
+        "background-origin" => {
+            if let Some(boxes) = parse_list(&layers, parse_box) {
+                style.background_origin = boxes;
+            }
+        }
+        "background-clip" => {
+            if let Some(boxes) = parse_list(&layers, parse_box) {
+                style.background_clip = boxes;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_list<T>(layers: &[String], parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    layers.iter().map(|l| parse(l)).collect()
+}
+
+// `background` réinitialise toutes les propriétés ; la couleur n'est permise que sur la dernière couche
+fn parse_shorthand(layers: &[String]) -> Option<Style> {
+    let mut images = Vec::new();
+    let mut positions = Vec::new();
+    let mut sizes = Vec::new();
+    let mut repeats = Vec::new();
+    let mut origins = Vec::new();
+    let mut clips = Vec::new();
+    let mut color = None;
+    for (i, layer) in layers.iter().enumerate() {
+        let parts = split_slashes(&split_components(layer));
+        let mut image = None;
+        let mut position = None;
+        let mut size = None;
+        let mut repeat = None;
+        let mut boxes = Vec::new();
+        let mut j = 0;
+        while j < parts.len() {
+            let part = parts[j].as_str();
+            if is_position_part(part) && position.is_none() {
+                let end = (j..parts.len())
+                    .find(|&k| !is_position_part(&parts[k]))
+                    .unwrap_or(parts.len());
+                position = Some(parse_position(&parts[j..end])?);
+                j = end;
+                if parts.get(j).map(String::as_str) == Some("/") {
+                    let end = (j + 1..parts.len())
+                        .find(|&k| parse_size(&parts[k..=k]).is_none())
+                        .unwrap_or(parts.len())
+                        .min(j + 3);
+                    size = Some(parse_size(&parts[j + 1..end])?);
+                    j = end;
+                }
+                continue;
+            }
+            if repeat.is_none() && parse_repeat(&parts[j..=j]).is_some() {
+                let two = j + 1 < parts.len() && parse_repeat(&parts[j..j + 2]).is_some();
+                let end = if two { j + 2 } else { j + 1 };
+                repeat = parse_repeat(&parts[j..end]);
+                j = end;
+                continue;
+            }
+            if let Some(area) = parse_box(part)
+                && boxes.len() < 2
+            {
+                boxes.push(area);
+            } else if matches!(part, "scroll" | "fixed" | "local") {
+                // background-attachment : sans effet sur une page imprimée
+            } else if image.is_none()
+                && let Some(img) = parse_image(part)
+            {
+                image = Some(img);
+            } else if i == layers.len() - 1
+                && color.is_none()
+                && let Some(c) = parse_color(part)
+            {
+                color = Some(c);
+            } else {
+                return None;
+            }
+            j += 1;
+        }
+        images.push(image.unwrap_or_default());
+        positions.push(position.unwrap_or_default());
+        sizes.push(size.unwrap_or_default());
+        repeats.push(repeat.unwrap_or_default());
+        // Une seule boîte fixe origin et clip ; deux : origin puis clip
+        origins.push(boxes.first().copied().unwrap_or(BoxArea::PaddingBox));
+        clips.push(boxes.last().copied().unwrap_or_default());
+    }
+    Some(Style {
+        background: Some(color.unwrap_or(Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0.0,
+        })),
+        background_image: images,
+        background_position: positions,
+        background_size: sizes,
+        background_repeat: repeats,
+        background_origin: origins,
+        background_clip: clips,
+        ..Style::default()
+    })
+}
+
+pub fn parse_image(value: &str) -> Option<BackgroundImage> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("none") {
+        return Some(BackgroundImage::None);
+    }
+    let (name, args) = value.split_once('(')?;
+    let args = args.strip_suffix(')')?;
+    match name.trim().to_ascii_lowercase().as_str() {
+        "url" => {
+            let url = args.trim().trim_matches(['"', '\''].as_ref());
+            Some(BackgroundImage::Url(url.to_string()))
+        }
+        "linear-gradient" => parse_linear_gradient(&split_commas(args)),
+        "radial-gradient" => parse_radial_gradient(&split_commas(args)),
+        _ => None,
+    }
+}
+
+fn parse_linear_gradient(args: &[String]) -> Option<BackgroundImage> {
+    let first = args.first()?;
+    let (direction, stops) = match parse_direction(first) {
+        Some(direction) => (direction, &args[1..]),
+        None => (GradientDirection::Angle(180.0), args),
+    };
+    Some(BackgroundImage::LinearGradient(LinearGradient {
+        direction,
+        stops: parse_stops(stops)?,
+    }))
+}
+
+fn parse_direction(value: &str) -> Option<GradientDirection> {
+    let parts = split_components(value);
+    if parts.first().map(String::as_str) != Some("to") {
+        return parse_angle(value).map(GradientDirection::Angle);
+    }
+    let mut horizontal = None;
+    let mut vertical = None;
+    for part in &parts[1..] {
+        match part.as_str() {
+            "left" | "right" if horizontal.is_none() => horizontal = Some(part == "right"),
+            "top" | "bottom" if vertical.is_none() => vertical = Some(part == "bottom"),
+            _ => return None,
+        }
+    }
+    Some(match (horizontal, vertical) {
+        (Some(right), Some(bottom)) => GradientDirection::Corner { right, bottom },
+        (Some(true), None) => GradientDirection::Angle(90.0),
+        (Some(false), None) => GradientDirection::Angle(270.0),
+        (None, Some(true)) => GradientDirection::Angle(180.0),
+        (None, Some(false)) => GradientDirection::Angle(0.0),
+        (None, None) => return None,
+    })
+}
+
+fn parse_radial_gradient(args: &[String]) -> Option<BackgroundImage> {
+    let first = args.first()?;
+    let (shape, stops) = match parse_radial_shape(first) {
+        Some(shape) => (shape, &args[1..]),
+        None => (
+            (
+                false,
+                RadialSize::Extent(RadialExtent::FarthestCorner),
+                center(),
+            ),
+            args,
+        ),
+    };
+    let (circle, size, position) = shape;
+    Some(BackgroundImage::RadialGradient(RadialGradient {
+        circle,
+        size,
+        position,
+        stops: parse_stops(stops)?,
+    }))
+}
+
+fn center() -> BackgroundPosition {
+    BackgroundPosition {
+        x: Length::Percent(50.0),
+        y: Length::Percent(50.0),
+    }
+}
+
+// `[<forme> || <taille>] [at <position>]`
+fn parse_radial_shape(value: &str) -> Option<(bool, RadialSize, BackgroundPosition)> {
+    let parts = split_components(value);
+    let at = parts.iter().position(|p| p == "at");
+    let (shape_parts, position) = match at {
+        Some(i) => (&parts[..i], parse_position(&parts[i + 1..])?),
+        None => (&parts[..], center()),
+    };
+    let mut circle = None;
+    let mut extent = None;
+    let mut lengths = Vec::new();
+    for part in shape_parts {
+        match part.as_str() {
+            "circle" if circle.is_none() => circle = Some(true),
+            "ellipse" if circle.is_none() => circle = Some(false),
+            "closest-side" if extent.is_none() => extent = Some(RadialExtent::ClosestSide),
+            "farthest-side" if extent.is_none() => extent = Some(RadialExtent::FarthestSide),
+            "closest-corner" if extent.is_none() => extent = Some(RadialExtent::ClosestCorner),
+            "farthest-corner" if extent.is_none() => extent = Some(RadialExtent::FarthestCorner),
+            _ => lengths.push(parse_length(part)?),
+        }
+    }
+    if shape_parts.is_empty() && at.is_none() {
+        return None;
+    }
+    // Un cercle n'accepte qu'un rayon, sans pourcentage ; une ellipse en demande deux
+    let size = match (extent, lengths.as_slice()) {
+        (Some(extent), []) => RadialSize::Extent(extent),
+        (None, []) => RadialSize::Extent(RadialExtent::FarthestCorner),
+        (None, [r]) if circle != Some(false) && !r.has_percent() => {
+            circle = Some(true);
+            RadialSize::Explicit(r.clone(), r.clone())
+        }
+        (None, [rx, ry]) if circle != Some(true) => {
+            circle = Some(false);
+            RadialSize::Explicit(rx.clone(), ry.clone())
+        }
+        _ => return None,
+    };
+    Some((circle.unwrap_or(false), size, position))
+}
+
+fn parse_stops(args: &[String]) -> Option<Vec<ColorStop>> {
+    let mut stops = Vec::new();
+    for arg in args {
+        let parts = split_components(arg);
+        let Some(color) = parts.first().and_then(|c| parse_color(c)) else {
+            // Indication de transition seule : l'interpolation reste linéaire
+            if parts.len() == 1 && parse_length(&parts[0]).is_some() && !stops.is_empty() {
+                continue;
+            }
+            return None;
+        };
+        let positions: Vec<Length> = parts[1..]
+            .iter()
+            .map(|p| parse_length(p))
+            .collect::<Option<_>>()?;
+        match positions.len() {
+            0 => stops.push(ColorStop {
+                color,
+                position: None,
+            }),
+            1 | 2 => {
+                for position in positions {
+                    stops.push(ColorStop {
+                        color: color.clone(),
+                        position: Some(position),
+                    });
+                }
+            }
+            _ => return None,
+        }
+    }
+    if stops.len() < 2 {
+        return None;
+    }
+    Some(stops)
+}
+
+fn is_position_part(part: &str) -> bool {
+    matches!(part, "left" | "right" | "top" | "bottom" | "center") || parse_length(part).is_some()
+}
+
+// 1 à 4 valeurs : mots-clés, longueurs, ou mots-clés suivis d'un décalage depuis ce bord
+pub fn parse_position(parts: &[String]) -> Option<BackgroundPosition> {
+    let keyword_axis = |p: &str| match p {
+        "left" | "right" => Some(Axis::Horizontal),
+        "top" | "bottom" => Some(Axis::Vertical),
+        "center" => Some(Axis::Either),
+        _ => None,
+    };
+    let edge = |p: &str| match p {
+        "left" | "top" => Some(Length::Percent(0.0)),
+        "right" | "bottom" => Some(Length::Percent(100.0)),
+        "center" => Some(Length::Percent(50.0)),
+        _ => None,
+    };
+    let value = |p: &str| edge(p).or_else(|| parse_length(p));
+    let (x, y) = match parts {
+        [a] => match keyword_axis(a) {
+            Some(Axis::Vertical) => (Length::Percent(50.0), edge(a)?),
+            _ => (value(a)?, Length::Percent(50.0)),
+        },
+        [a, b] => {
+            let swap = keyword_axis(a) == Some(Axis::Vertical)
+                || keyword_axis(b) == Some(Axis::Horizontal);
+            let (h, v) = if swap { (b, a) } else { (a, b) };
+            if keyword_axis(h) == Some(Axis::Vertical) || keyword_axis(v) == Some(Axis::Horizontal)
+            {
+                return None;
+            }
+            (value(h)?, value(v)?)
+        }
+        [_, _, _] | [_, _, _, _] => {
+            let mut horizontal = None;
+            let mut vertical = None;
+            let mut i = 0;
+            while i < parts.len() {
+                let keyword = parts[i].as_str();
+                let axis = keyword_axis(keyword)?;
+                let offset = parts
+                    .get(i + 1)
+                    .filter(|p| keyword_axis(p).is_none())
+                    .map(|p| parse_length(p))
+                    .map_or(Some(None), |l| l.map(Some))?;
+                i += if offset.is_some() { 2 } else { 1 };
+                let resolved = match offset {
+                    Some(_) if keyword == "center" => return None,
+                    Some(offset) if matches!(keyword, "right" | "bottom") => {
+                        Length::Calc(Box::new(CalcExpr::Difference(
+                            Box::new(CalcExpr::Length(Length::Percent(100.0))),
+                            Box::new(CalcExpr::Length(offset)),
+                        )))
+                    }
+                    Some(offset) => offset,
+                    None => edge(keyword)?,
+                };
+                let slot = match axis {
+                    Axis::Horizontal => &mut horizontal,
+                    Axis::Vertical => &mut vertical,
+                    Axis::Either if horizontal.is_none() => &mut horizontal,
+                    Axis::Either => &mut vertical,
+                };
+                if slot.replace(resolved).is_some() {
+                    return None;
+                }
+            }
+            (
+                horizontal.unwrap_or(Length::Percent(50.0)),
+                vertical.unwrap_or(Length::Percent(50.0)),
+            )
+        }
+        _ => return None,
+    };
+    Some(BackgroundPosition { x, y })
+}
+
+#[derive(PartialEq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+    Either,
+}
+
+fn parse_size(parts: &[String]) -> Option<BackgroundSize> {
+    let dimension = |p: &str| match p {
+        "auto" => Some(None),
+        _ => parse_length(p).map(Some),
+    };
+    match parts {
+        [single] if single == "cover" => Some(BackgroundSize::Cover),
+        [single] if single == "contain" => Some(BackgroundSize::Contain),
+        [w] => Some(BackgroundSize::Explicit(dimension(w)?, None)),
+        [w, h] => Some(BackgroundSize::Explicit(dimension(w)?, dimension(h)?)),
+        _ => None,
+    }
+    .map(|size| match size {
+        BackgroundSize::Explicit(None, None) => BackgroundSize::Auto,
+        size => size,
+    })
+}
+
+fn parse_repeat(parts: &[String]) -> Option<BackgroundRepeat> {
+    let single = |p: &str| match p {
+        "repeat" => Some(Repeat::Repeat),
+        "no-repeat" => Some(Repeat::NoRepeat),
+        "space" => Some(Repeat::Space),
+        "round" => Some(Repeat::Round),
+        _ => None,
+    };
+    let (x, y) = match parts {
+        [p] if p == "repeat-x" => (Repeat::Repeat, Repeat::NoRepeat),
+        [p] if p == "repeat-y" => (Repeat::NoRepeat, Repeat::Repeat),
+        [p] => (single(p)?, single(p)?),
+        [x, y] => (single(x)?, single(y)?),
+        _ => return None,
+    };
+    Some(BackgroundRepeat { x, y })
+}
+
+fn parse_box(value: &str) -> Option<BoxArea> {
+    match value.trim() {
+        "border-box" => Some(BoxArea::BorderBox),
+        "padding-box" => Some(BoxArea::PaddingBox),
+        "content-box" => Some(BoxArea::ContentBox),
+        _ => None,
+    }
+}
+
+// Sépare `center/cover` en trois composants ; les fonctions restent intactes
+fn split_slashes(parts: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    for part in parts {
+        if part.contains('(') || !part.contains('/') {
+            out.push(part.clone());
+            continue;
+        }
+        for (i, piece) in part.split('/').enumerate() {
+            if i > 0 {
+                out.push("/".to_string());
+            }
+            if !piece.is_empty() {
+                out.push(piece.to_string());
+            }
+        }
+    }
+    out
+}
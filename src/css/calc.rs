@@ -0,0 +1,250 @@
+use super::styles::{Length, LengthContext};
+use super::tokenizer::Token;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcExpr {
+    Number(f32),
+    Length(Length),
+    Sum(Box<CalcExpr>, Box<CalcExpr>),
+    Difference(Box<CalcExpr>, Box<CalcExpr>),
+    Product(Box<CalcExpr>, Box<CalcExpr>),
+    Quotient(Box<CalcExpr>, Box<CalcExpr>),
+    Min(Vec<CalcExpr>),
+    Max(Vec<CalcExpr>),
+    Clamp(Box<CalcExpr>, Box<CalcExpr>, Box<CalcExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Number(f32),
+    Length(f32),
+}
+
+impl CalcExpr {
+    // Longueur en points ; un résultat sans unité est lu en px
+    pub fn eval(&self, ctx: &LengthContext) -> f32 {
+        match self.eval_value(ctx) {
+            Some(Value::Length(v)) => v,
+            Some(Value::Number(v)) => Length::Px(v).to_pt(),
+            None => 0.0,
+        }
+    }
+
+    pub fn has_percent(&self) -> bool {
+        match self {
+            CalcExpr::Number(_) => false,
+            CalcExpr::Length(l) => l.has_percent(),
+            CalcExpr::Sum(a, b)
+            | CalcExpr::Difference(a, b)
+            | CalcExpr::Product(a, b)
+            | CalcExpr::Quotient(a, b) => a.has_percent() || b.has_percent(),
+            CalcExpr::Min(args) | CalcExpr::Max(args) => args.iter().any(CalcExpr::has_percent),
+            CalcExpr::Clamp(a, b, c) => a.has_percent() || b.has_percent() || c.has_percent(),
+        }
+    }
+
+    fn eval_value(&self, ctx: &LengthContext) -> Option<Value> {
+        use Value::{Length as L, Number as N};
+        Some(match self {
+            CalcExpr::Number(n) => N(*n),
+            CalcExpr::Length(l) => L(l.resolve(ctx)),
+            CalcExpr::Sum(a, b) | CalcExpr::Difference(a, b) => {
+                let sign = if matches!(self, CalcExpr::Sum(..)) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                match (a.eval_value(ctx)?, b.eval_value(ctx)?) {
+                    (N(x), N(y)) => N(x + sign * y),
+                    (L(x), L(y)) => L(x + sign * y),
+                    _ => return None,
+                }
+            }
+            CalcExpr::Product(a, b) => match (a.eval_value(ctx)?, b.eval_value(ctx)?) {
+                (N(x), N(y)) => N(x * y),
+                (L(x), N(y)) | (N(y), L(x)) => L(x * y),
+                (L(_), L(_)) => return None,
+            },
+            CalcExpr::Quotient(a, b) => match (a.eval_value(ctx)?, b.eval_value(ctx)?) {
+                (_, N(0.0)) => return None,
+                (N(x), N(y)) => N(x / y),
+                (L(x), N(y)) => L(x / y),
+                _ => return None,
+            },
+            CalcExpr::Min(args) | CalcExpr::Max(args) => {
+                let min = matches!(self, CalcExpr::Min(_));
+                let mut values = args.iter().map(|a| a.eval_value(ctx));
+                let mut acc = values.next()??;
+                for v in values {
+                    acc = match (acc, v?) {
+                        (N(x), N(y)) => N(if min { x.min(y) } else { x.max(y) }),
+                        (L(x), L(y)) => L(if min { x.min(y) } else { x.max(y) }),
+                        _ => return None,
+                    };
+                }
+                acc
+            }
+            CalcExpr::Clamp(lo, val, hi) => {
+                match (
+                    lo.eval_value(ctx)?,
+                    val.eval_value(ctx)?,
+                    hi.eval_value(ctx)?,
+                ) {
+                    (N(l), N(v), N(h)) => N(v.min(h).max(l)),
+                    (L(l), L(v), L(h)) => L(v.min(h).max(l)),
+                    _ => return None,
+                }
+            }
+        })
+    }
+}
+
+// `tokens` commence par la fonction (`calc(`, `min(`…) et doit se terminer avec elle
+pub fn parse_math_function(tokens: &[Token]) -> Option<CalcExpr> {
+    let mut parser = CalcParser { tokens, pos: 0 };
+    let expr = parser.function()?;
+    parser.skip_whitespace();
+    if parser.pos != tokens.len() {
+        return None;
+    }
+    Some(expr)
+}
+
+pub fn is_math_function(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "calc" | "min" | "max" | "clamp"
+    )
+}
+
+struct CalcParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl CalcParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek() == Some(&Token::Whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn function(&mut self) -> Option<CalcExpr> {
+        self.skip_whitespace();
+        let name = match self.peek()? {
+            Token::Function(name) if is_math_function(name) => name.to_ascii_lowercase(),
+            _ => return None,
+        };
+        self.pos += 1;
+        let mut args = vec![self.sum()?];
+        while self.expect(Token::Comma).is_some() {
+            args.push(self.sum()?);
+        }
+        self.expect(Token::CloseParen)?;
+        match (name.as_str(), args.len()) {
+            ("calc", 1) => args.pop(),
+            ("min", _) => Some(CalcExpr::Min(args)),
+            ("max", _) => Some(CalcExpr::Max(args)),
+            ("clamp", 3) => {
+                let hi = args.pop()?;
+                let val = args.pop()?;
+                let lo = args.pop()?;
+                Some(CalcExpr::Clamp(Box::new(lo), Box::new(val), Box::new(hi)))
+            }
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Option<CalcExpr> {
+        let mut lhs = self.product()?;
+        loop {
+            // `+` et `-` doivent être entourés d'espaces
+            let save = self.pos;
+            if self.peek() != Some(&Token::Whitespace) {
+                return Some(lhs);
+            }
+            self.skip_whitespace();
+            let op = match self.peek() {
+                Some(Token::Delim('+')) => '+',
+                Some(Token::Delim('-')) => '-',
+                _ => {
+                    self.pos = save;
+                    return Some(lhs);
+                }
+            };
+            self.pos += 1;
+            if self.peek() != Some(&Token::Whitespace) {
+                return None;
+            }
+            let rhs = self.product()?;
+            lhs = if op == '+' {
+                CalcExpr::Sum(Box::new(lhs), Box::new(rhs))
+            } else {
+                CalcExpr::Difference(Box::new(lhs), Box::new(rhs))
+            };
+        }
+    }
+
+    fn product(&mut self) -> Option<CalcExpr> {
+        let mut lhs = self.value()?;
+        loop {
+            let save = self.pos;
+            self.skip_whitespace();
+            let op = match self.peek() {
+                Some(Token::Delim('*')) => '*',
+                Some(Token::Delim('/')) => '/',
+                _ => {
+                    self.pos = save;
+                    return Some(lhs);
+                }
+            };
+            self.pos += 1;
+            let rhs = self.value()?;
+            lhs = if op == '*' {
+                CalcExpr::Product(Box::new(lhs), Box::new(rhs))
+            } else {
+                CalcExpr::Quotient(Box::new(lhs), Box::new(rhs))
+            };
+        }
+    }
+
+    fn value(&mut self) -> Option<CalcExpr> {
+        self.skip_whitespace();
+        match self.peek()?.clone() {
+            Token::Number(n) => {
+                self.pos += 1;
+                Some(CalcExpr::Number(n))
+            }
+            Token::Dimension(v, unit) => {
+                self.pos += 1;
+                Length::from_unit(v, &unit).map(CalcExpr::Length)
+            }
+            Token::Percentage(v) => {
+                self.pos += 1;
+                Some(CalcExpr::Length(Length::Percent(v)))
+            }
+            Token::OpenParen => {
+                self.pos += 1;
+                let inner = self.sum()?;
+                self.expect(Token::CloseParen)?;
+                Some(inner)
+            }
+            Token::Function(_) => self.function(),
+            _ => None,
+        }
+    }
+}
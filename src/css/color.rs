@@ -0,0 +1,429 @@
+use super::styles::Color;
+use super::tokenizer::{Token, tokenize};
+
+// Les 148 couleurs nommées de CSS Color Level 4
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+pub fn named_color(name: &str) -> Option<Color> {
+    let name = name.to_ascii_lowercase();
+    if name == "transparent" {
+        return Some(Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0.0,
+        });
+    }
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+        .ok()
+        .map(|i| {
+            let rgb = NAMED_COLORS[i].1;
+            Color {
+                r: (rgb >> 16) as u8,
+                g: (rgb >> 8) as u8,
+                b: rgb as u8,
+                a: 1.0,
+            }
+        })
+}
+
+pub fn is_current_color(value: &str) -> bool {
+    tokenize(value)
+        .iter()
+        .any(|t| matches!(&t.token, Token::Ident(id) if id.eq_ignore_ascii_case("currentcolor")))
+}
+
+pub fn replace_current_color(value: &str, color: &Color) -> String {
+    let mut out = String::new();
+    let mut copied = 0;
+    for t in tokenize(value) {
+        if matches!(&t.token, Token::Ident(id) if id.eq_ignore_ascii_case("currentcolor")) {
+            out.push_str(&value[copied..t.span.start]);
+            out.push_str(&format!(
+                "rgb({} {} {} / {})",
+                color.r, color.g, color.b, color.a
+            ));
+            copied = t.span.end;
+        }
+    }
+    out.push_str(&value[copied..]);
+    out
+}
+
+// None si la valeur n'est pas une couleur (currentColor est résolu en amont)
+pub fn parse_color(value: &str) -> Option<Color> {
+    let tokens: Vec<Token> = tokenize(value.trim())
+        .into_iter()
+        .map(|t| t.token)
+        .filter(|t| *t != Token::Whitespace)
+        .collect();
+    match tokens.first()? {
+        Token::Hash(hex) if tokens.len() == 1 => parse_hex(hex),
+        Token::Ident(name) if tokens.len() == 1 => named_color(name),
+        Token::Function(name) if tokens.last() == Some(&Token::CloseParen) => {
+            parse_function(&name.to_ascii_lowercase(), &tokens[1..tokens.len() - 1])
+        }
+        _ => None,
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0)?, digit(1)?, digit(2)?, 255),
+        4 => (digit(0)?, digit(1)?, digit(2)?, digit(3)?),
+        6 => (pair(0)?, pair(2)?, pair(4)?, 255),
+        8 => (pair(0)?, pair(2)?, pair(4)?, pair(6)?),
+        _ => return None,
+    };
+    Some(Color {
+        r,
+        g,
+        b,
+        a: a as f32 / 255.0,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Component {
+    Number(f32),
+    Percent(f32),
+    Angle(f32), // en degrés
+    None,
+}
+
+impl Component {
+    // `percent_ref` : valeur correspondant à 100%
+    fn number(self, percent_ref: f32) -> Option<f32> {
+        match self {
+            Component::Number(v) => Some(v),
+            Component::Percent(p) => Some(p / 100.0 * percent_ref),
+            Component::None => Some(0.0),
+            Component::Angle(_) => None,
+        }
+    }
+
+    fn hue(self) -> Option<f32> {
+        match self {
+            Component::Number(v) | Component::Angle(v) => Some(v.rem_euclid(360.0)),
+            Component::None => Some(0.0),
+            Component::Percent(_) => None,
+        }
+    }
+}
+
+fn component(token: &Token, legacy: bool) -> Option<Component> {
+    Some(match token {
+        Token::Number(v) => Component::Number(*v),
+        Token::Percentage(v) => Component::Percent(*v),
+        Token::Dimension(v, unit) => Component::Angle(match unit.to_ascii_lowercase().as_str() {
+            "deg" => *v,
+            "rad" => v.to_degrees(),
+            "grad" => v * 0.9,
+            "turn" => v * 360.0,
+            _ => return None,
+        }),
+        Token::Ident(id) if !legacy && id.eq_ignore_ascii_case("none") => Component::None,
+        _ => return None,
+    })
+}
+
+// Sépare « a b c / alpha » ou la syntaxe historique « a, b, c, alpha »
+fn components(tokens: &[Token]) -> Option<(Vec<Component>, Option<Component>)> {
+    let mut values = Vec::new();
+    let mut alpha = None;
+    if tokens.contains(&Token::Comma) {
+        for chunk in tokens.split(|t| *t == Token::Comma) {
+            let [t] = chunk else { return None };
+            values.push(component(t, true)?);
+        }
+        if values.len() == 4 {
+            alpha = values.pop();
+        }
+    } else {
+        let mut parts = tokens.split(|t| *t == Token::Delim('/'));
+        for t in parts.next()? {
+            values.push(component(t, false)?);
+        }
+        if let Some(chunk) = parts.next() {
+            let [t] = chunk else { return None };
+            alpha = Some(component(t, false)?);
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+    }
+    (values.len() == 3).then_some((values, alpha))
+}
+
+fn parse_function(name: &str, args: &[Token]) -> Option<Color> {
+    let (c, alpha) = components(args)?;
+    let alpha = match alpha {
+        Some(a) => a.number(1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    let rgb = match name {
+        "rgb" | "rgba" => [
+            c[0].number(255.0)?,
+            c[1].number(255.0)?,
+            c[2].number(255.0)?,
+        ]
+        .map(|v| v / 255.0),
+        "hsl" | "hsla" => hsl_to_rgb(
+            c[0].hue()?,
+            c[1].number(100.0)? / 100.0,
+            c[2].number(100.0)? / 100.0,
+        ),
+        "hwb" => hwb_to_rgb(
+            c[0].hue()?,
+            c[1].number(100.0)? / 100.0,
+            c[2].number(100.0)? / 100.0,
+        ),
+        "lab" => lab_to_rgb(
+            c[0].number(100.0)?,
+            c[1].number(125.0)?,
+            c[2].number(125.0)?,
+        ),
+        "lch" => {
+            let (a, b) = polar(c[1].number(150.0)?, c[2].hue()?);
+            lab_to_rgb(c[0].number(100.0)?, a, b)
+        }
+        "oklab" => oklab_to_rgb(c[0].number(1.0)?, c[1].number(0.4)?, c[2].number(0.4)?),
+        "oklch" => {
+            let (a, b) = polar(c[1].number(0.4)?, c[2].hue()?);
+            oklab_to_rgb(c[0].number(1.0)?, a, b)
+        }
+        _ => return None,
+    };
+    let [r, g, b] = rgb.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+    Some(Color { r, g, b, a: alpha })
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [f32; 3] {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let f = |n: f32| {
+        let k = (n + h / 30.0) % 12.0;
+        let a = s * l.min(1.0 - l);
+        l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+fn hwb_to_rgb(h: f32, w: f32, b: f32) -> [f32; 3] {
+    if w + b >= 1.0 {
+        let gray = w / (w + b);
+        return [gray; 3];
+    }
+    hsl_to_rgb(h, 1.0, 0.5).map(|v| v * (1.0 - w - b) + w)
+}
+
+fn polar(chroma: f32, hue: f32) -> (f32, f32) {
+    let rad = hue.to_radians();
+    (chroma.max(0.0) * rad.cos(), chroma.max(0.0) * rad.sin())
+}
+
+fn lab_to_rgb(l: f32, a: f32, b: f32) -> [f32; 3] {
+    // CIE Lab (D50) -> XYZ D50 -> XYZ D65 (Bradford) -> sRGB linéaire
+    const EPSILON: f32 = 216.0 / 24389.0;
+    const KAPPA: f32 = 24389.0 / 27.0;
+    let fy = (l + 16.0) / 116.0;
+    let fx = a / 500.0 + fy;
+    let fz = fy - b / 200.0;
+    let inv = |f: f32| {
+        if f.powi(3) > EPSILON {
+            f.powi(3)
+        } else {
+            (116.0 * f - 16.0) / KAPPA
+        }
+    };
+    let y = if l > KAPPA * EPSILON {
+        fy.powi(3)
+    } else {
+        l / KAPPA
+    };
+    let (x, z) = (inv(fx) * 0.96422, inv(fz) * 0.82521);
+    let x65 = 0.955_473_4 * x - 0.023_098_538 * y + 0.063_259_31 * z;
+    let y65 = -0.028_369_706 * x + 1.009_995_5 * y + 0.021_041_399 * z;
+    let z65 = 0.012_314_002 * x - 0.020_507_697 * y + 1.330_366 * z;
+    let linear = [
+        3.240_97 * x65 - 1.537_383_2 * y65 - 0.498_610_76 * z65,
+        -0.969_243_6 * x65 + 1.875_967_5 * y65 + 0.041_555_06 * z65,
+        0.055_630_08 * x65 - 0.203_976_96 * y65 + 1.056_971_5 * z65,
+    ];
+    linear.map(gamma)
+}
+
+fn oklab_to_rgb(l: f32, a: f32, b: f32) -> [f32; 3] {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    let linear = [
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ];
+    linear.map(gamma)
+}
+
+// sRGB linéaire -> sRGB encodé
+fn gamma(v: f32) -> f32 {
+    if v.abs() <= 0.003_130_8 {
+        12.92 * v
+    } else {
+        v.signum() * (1.055 * v.abs().powf(1.0 / 2.4) - 0.055)
+    }
+}
@@ -0,0 +1,28 @@
+use super::background::parse_position;
+use super::parser::split_components;
+use super::styles::{BackgroundPosition, ImageOrientation, ObjectFit};
+
+pub fn parse_object_fit(value: &str) -> Option<ObjectFit> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "fill" => ObjectFit::Fill,
+        "contain" => ObjectFit::Contain,
+        "cover" => ObjectFit::Cover,
+        "none" => ObjectFit::None,
+        "scale-down" => ObjectFit::ScaleDown,
+        _ => return None,
+    })
+}
+
+// Mêmes valeurs que `background-position`
+pub fn parse_object_position(value: &str) -> Option<BackgroundPosition> {
+    parse_position(&split_components(value))
+}
+
+// Les angles de l'ancienne syntaxe ne sont pas pris en charge
+pub fn parse_image_orientation(value: &str) -> Option<ImageOrientation> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "from-image" => ImageOrientation::FromImage,
+        "none" => ImageOrientation::None,
+        _ => return None,
+    })
+}
@@ -1,2 +1,12 @@
+pub mod background;
+pub mod calc;
+pub mod color;
+pub mod image;
 pub mod parser;
-pub mod styles;
\ No newline at end of file
+pub mod rules;
+pub mod shadow;
+pub mod styles;
+pub mod text;
+pub mod tokenizer;
+pub mod transform;
+pub mod variables;
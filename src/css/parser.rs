@@ -1,5 +1,26 @@
-use super::styles::{Color, Display, Position, Sides, Style, Stylesheet};
+use super::background::apply_background;
+use super::calc::{is_math_function, parse_math_function};
+use super::color::{is_current_color, parse_color, replace_current_color};
+use super::image::{parse_image_orientation, parse_object_fit, parse_object_position};
+use super::rules::{Declaration, Rule, parse_declarations, parse_rules};
+use super::shadow::parse_shadows;
+use super::styles::{
+    BorderStyle, Color, Corners, DecorationLine, Direction, Display, FontStyle,
+    FontWeight, Length, Overflow, Position, Radius, Sides, Style, Stylesheet, UnicodeBidi,
+    VerticalAlign,
+};
+use super::text::{
+    apply_text_decoration, parse_direction, parse_feature_settings, parse_font_face,
+    parse_font_family, parse_font_kerning, parse_font_style, parse_font_weight, parse_hyphens,
+    parse_line_height, parse_spacing, parse_text_align, parse_unicode_bidi,
+    parse_variant_ligatures, parse_vertical_align,
+};
+use super::tokenizer::{Token, tokenize};
+use super::transform::{parse_transform, parse_transform_origin};
+use super::variables::{contains_var, substitute_vars};
 use crate::html::dom::{Node, NodeType};
+use crate::resources::data_url::DataUrls;
+use crate::resources::loader::{ResourceLoader, resolve_url};
 
 pub fn parse_css(
     node: &Node,
@@ -8,20 +29,29 @@ pub fn parse_css(
     parent_style: Option<&Style>,
 ) -> Style {
     let mut style = Style::default();
+    // Une langue vide (inconnue) masque celle des ancêtres
+    if let Some(lang) = node.get_attribute("lang") {
+        style.lang = Some(lang.trim().to_string());
+    }
 
     if let NodeType::Element(tag) = &node.node_type {
-        if let Some(sheet) = sheet {
+        if let Some(sheet) = sheet.map(|s| &s.rules) {
             if let Some(s) = sheet.get(tag) {
                 merge_styles(&mut style, s);
             }
-            if let Some(parent_node) = parent_node {
-                if let NodeType::Element(p_tag) = &parent_node.node_type {
-                    let selector = format!("{} > {}", p_tag, tag);
-                    if let Some(s) = sheet.get(&selector) {
-                        merge_styles(&mut style, s);
-                    }
+            if let Some(parent_node) = parent_node
+                && let NodeType::Element(p_tag) = &parent_node.node_type
+            {
+                let selector = format!("{} > {}", p_tag, tag);
+                if let Some(s) = sheet.get(&selector) {
+                    merge_styles(&mut style, s);
                 }
             }
+            if tag == "html"
+                && let Some(s) = sheet.get(":root")
+            {
+                merge_styles(&mut style, s);
+            }
             if let Some(class_attr) = node.get_attribute("class") {
                 for c in class_attr.split_whitespace() {
                     if let Some(s) = sheet.get(&format!(".{}", c)) {
@@ -29,98 +59,99 @@ pub fn parse_css(
                     }
                 }
             }
-            if let Some(id) = node.get_attribute("id") {
-                if let Some(s) = sheet.get(&format!("#{}", id)) {
-                    merge_styles(&mut style, s);
-                }
+            if let Some(id) = node.get_attribute("id")
+                && let Some(s) = sheet.get(&format!("#{}", id))
+            {
+                merge_styles(&mut style, s);
             }
         }
 
         match tag.as_str() {
             "img" | "span" | "a" | "strong" | "em" | "b" | "i" | "u" | "small" | "abbr"
-            | "code" | "kbd" | "mark" | "s" | "sub" | "sup" | "var" | "time" | "cite" | "q" => {
+            | "code" | "kbd" | "mark" | "s" | "sub" | "sup" | "var" | "time" | "cite" | "q"
+            | "bdi" | "bdo" | "svg" => {
                 style.display = Display::InlineBlock;
             }
             "p" | "div" | "section" | "article" | "aside" | "main" | "nav" | "header"
             | "footer" | "address" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 10.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(10.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             "h1" => {
                 style.display = Display::Block;
-                style.font_size = 32.0;
+                style.font_size = Length::Px(32.0);
                 style.margin = Sides {
-                    top: 20.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(20.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             "h2" => {
                 style.display = Display::Block;
-                style.font_size = 28.0;
+                style.font_size = Length::Px(28.0);
                 style.margin = Sides {
-                    top: 18.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(18.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             "h3" => {
                 style.display = Display::Block;
-                style.font_size = 24.0;
+                style.font_size = Length::Px(24.0);
             }
             "h4" => {
                 style.display = Display::Block;
-                style.font_size = 20.0;
+                style.font_size = Length::Px(20.0);
             }
             "h5" => {
                 style.display = Display::Block;
-                style.font_size = 18.0;
+                style.font_size = Length::Px(18.0);
             }
             "h6" => {
                 style.display = Display::Block;
-                style.font_size = 16.0;
+                style.font_size = Length::Px(16.0);
             }
             "form" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 10.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(10.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             "ul" | "ol" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 10.0,
-                    bottom: 10.0,
-                    left: 20.0,
-                    right: 0.0,
+                    top: Length::Px(10.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(20.0),
+                    right: Length::Px(0.0),
                 };
             }
             "li" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 4.0,
-                    bottom: 4.0,
-                    left: 10.0,
-                    right: 0.0,
+                    top: Length::Px(4.0),
+                    bottom: Length::Px(4.0),
+                    left: Length::Px(10.0),
+                    right: Length::Px(0.0),
                 };
             }
             "iframe" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 10.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(10.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             "table" => {
@@ -133,77 +164,326 @@ pub fn parse_css(
             "td" | "th" => {
                 style.display = Display::InlineBlock;
                 style.padding = Sides {
-                    top: 4.0,
-                    bottom: 4.0,
-                    left: 6.0,
-                    right: 6.0,
+                    top: Length::Px(4.0),
+                    bottom: Length::Px(4.0),
+                    left: Length::Px(6.0),
+                    right: Length::Px(6.0),
                 };
                 style.border_width = Sides {
-                    top: 1.0,
-                    bottom: 1.0,
-                    left: 1.0,
-                    right: 1.0,
+                    top: Length::Px(1.0),
+                    bottom: Length::Px(1.0),
+                    left: Length::Px(1.0),
+                    right: Length::Px(1.0),
                 };
+                style.border_style = Sides::all(BorderStyle::Solid);
             }
             "input" | "label" | "textarea" | "select" | "option" | "button" => {
                 style.display = Display::InlineBlock;
                 style.margin = Sides {
-                    top: 4.0,
-                    bottom: 4.0,
-                    left: 2.0,
-                    right: 2.0,
+                    top: Length::Px(4.0),
+                    bottom: Length::Px(4.0),
+                    left: Length::Px(2.0),
+                    right: Length::Px(2.0),
                 };
             }
             "video" | "audio" | "canvas" => {
                 style.display = Display::Block;
                 style.margin = Sides {
-                    top: 10.0,
-                    bottom: 10.0,
-                    left: 0.0,
-                    right: 0.0,
+                    top: Length::Px(10.0),
+                    bottom: Length::Px(10.0),
+                    left: Length::Px(0.0),
+                    right: Length::Px(0.0),
                 };
             }
             _ => {}
         }
+
+        // Styles de police et décorations par défaut, sauf déclaration de l'auteur
+        match tag.as_str() {
+            "strong" | "b" => {
+                style.font_weight.get_or_insert(FontWeight::Bolder);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => {
+                style.font_weight.get_or_insert(FontWeight::Weight(700));
+            }
+            "em" | "i" | "cite" | "var" | "dfn" | "address" => {
+                style.font_style.get_or_insert(FontStyle::Italic);
+            }
+            "u" | "ins" => {
+                style.text_decoration.line.get_or_insert(DecorationLine {
+                    underline: true,
+                    ..Default::default()
+                });
+            }
+            "s" | "strike" | "del" => {
+                style.text_decoration.line.get_or_insert(DecorationLine {
+                    line_through: true,
+                    ..Default::default()
+                });
+            }
+            "sub" | "sup" => {
+                style.vertical_align.get_or_insert(if tag == "sub" {
+                    VerticalAlign::Sub
+                } else {
+                    VerticalAlign::Super
+                });
+                if style.font_size.is_zero() {
+                    style.font_size = Length::Em(0.833);
+                }
+            }
+            "bdo" => {
+                style.unicode_bidi.get_or_insert(UnicodeBidi::BidiOverride);
+            }
+            _ => {}
+        }
+
+        // Attribut `dir`, comme une règle de la feuille par défaut
+        let dir = node.get_attribute("dir").map(|d| d.trim().to_ascii_lowercase());
+        match dir.as_deref() {
+            Some("ltr") => {
+                style.direction.get_or_insert(Direction::Ltr);
+                style.unicode_bidi.get_or_insert(UnicodeBidi::Isolate);
+            }
+            Some("rtl") => {
+                style.direction.get_or_insert(Direction::Rtl);
+                style.unicode_bidi.get_or_insert(UnicodeBidi::Isolate);
+            }
+            // Le sens est déduit du premier caractère fort du contenu ; `bdi` par défaut
+            Some("auto") => {
+                style.unicode_bidi.get_or_insert(UnicodeBidi::Plaintext);
+            }
+            None if tag == "bdi" => {
+                style.unicode_bidi.get_or_insert(UnicodeBidi::Plaintext);
+            }
+            _ => {}
+        }
     }
 
+    // Les déclarations `!important` des règles puis de l'attribut style l'emportent sur
+    // toutes les autres
+    let mut important = std::mem::take(&mut style.important_declarations);
     if let Some(css) = node.get_attribute("style") {
-        apply_declarations(css, &mut style);
+        let (inline_important, normal): (Vec<_>, Vec<_>) =
+            parse_declarations(css).into_iter().partition(|d| d.important);
+        apply_declaration_list(&normal, &mut style);
+        important.extend(inline_important);
+    }
+    for decl in &important {
+        apply_cascaded(decl, &mut style);
     }
 
     if let Some(parent) = parent_style {
-        if style.font_size == 0.0 {
-            style.font_size = parent.font_size;
+        for (name, value) in &parent.custom_properties {
+            style
+                .custom_properties
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+    let mut uses_current_color: Vec<(String, String)> = Vec::new();
+    for decl in std::mem::take(&mut style.deferred_declarations) {
+        // Invalide au moment du calcul : la propriété reste non définie
+        if let Some(value) = substitute_vars(&decl.value, &style.custom_properties) {
+            // Ce qui suit un currentColor qu'il précise attend aussi la couleur
+            let after_current = uses_current_color.iter().any(|(n, _)| covers(n, &decl.name));
+            if (decl.name != "color" && is_current_color(&value)) || after_current {
+                uses_current_color.push((decl.name, value));
+            } else {
+                apply_declaration(&decl.name, &value, &mut style);
+            }
         }
-        if style.color == Color::default() {
+    }
+
+    if let Some(parent) = parent_style {
+        if style.font_size.is_zero() {
+            style.font_size = parent.font_size.clone();
+        }
+        if style.color.is_none() {
             style.color = parent.color.clone();
         }
         if style.font_family.is_none() {
             style.font_family = parent.font_family.clone();
         }
+        if style.text_shadow.is_none() {
+            style.text_shadow = parent.text_shadow.clone();
+        }
+        if style.font_style.is_none() {
+            style.font_style = parent.font_style;
+        }
+        if style.font_kerning.is_none() {
+            style.font_kerning = parent.font_kerning;
+        }
+        if style.font_variant_ligatures.is_none() {
+            style.font_variant_ligatures = parent.font_variant_ligatures;
+        }
+        if style.font_feature_settings.is_none() {
+            style.font_feature_settings = parent.font_feature_settings.clone();
+        }
+        if style.text_align.is_none() {
+            style.text_align = parent.text_align;
+        }
+        if style.text_indent.is_none() {
+            style.text_indent = parent.text_indent.clone();
+        }
+        if style.letter_spacing.is_none() {
+            style.letter_spacing = parent.letter_spacing.clone();
+        }
+        if style.word_spacing.is_none() {
+            style.word_spacing = parent.word_spacing.clone();
+        }
+        if style.line_height.is_none() {
+            style.line_height = parent.line_height.clone();
+        }
+        if style.hyphens.is_none() {
+            style.hyphens = parent.hyphens;
+        }
+        if style.image_orientation.is_none() {
+            style.image_orientation = parent.image_orientation;
+        }
+        if style.direction.is_none() {
+            style.direction = parent.direction;
+        }
+        if style.lang.is_none() {
+            style.lang = parent.lang.clone();
+        }
+        // Les décorations ne s'héritent pas mais s'étendent au texte des descendants
+        if style.position != Position::Absolute {
+            style.text_decorations = parent.text_decorations.clone();
+        }
+    }
+    let parent_weight = parent_style
+        .and_then(|p| p.font_weight)
+        .map_or(400, FontWeight::value);
+    let weight = style.font_weight.map_or(parent_weight, |w| w.relative_to(parent_weight));
+    style.font_weight = Some(FontWeight::Weight(weight));
+
+    let current = style.color.clone().unwrap_or_default();
+    for (name, value) in uses_current_color {
+        apply_declaration(&name, &replace_current_color(&value, &current), &mut style);
+    }
+    if let Some(line) = style.text_decoration.line.filter(|l| !l.is_none()) {
+        let mut decoration = style.text_decoration.clone();
+        decoration.line = Some(line);
+        decoration.style.get_or_insert_default();
+        decoration.color.get_or_insert(current);
+        style.text_decorations.push(decoration);
     }
 
     style
 }
 
 pub fn parse_stylesheet(css: &str) -> Stylesheet {
-    let mut sheet = Stylesheet::new();
-    for rule in css.split('}') {
-        let rule = rule.trim();
-        if rule.is_empty() {
-            continue;
+    parse_linked_stylesheet(css, "")
+}
+
+// Feuille chargée depuis `base` : ses `url()` sont relatives à elle, non au document
+fn parse_linked_stylesheet(css: &str, base: &str) -> Stylesheet {
+    let mut sheet = Stylesheet::default();
+    add_rules(&parse_rules(css), &mut sheet, base);
+    sheet
+}
+
+fn add_rules(rules: &[Rule], sheet: &mut Stylesheet, base: &str) {
+    let rebase = |declarations: Vec<Declaration>| -> Vec<Declaration> {
+        declarations
+            .into_iter()
+            .map(|decl| Declaration {
+                value: rebase_urls(&decl.value, base),
+                ..decl
+            })
+            .collect()
+    };
+    for rule in rules {
+        match rule {
+            Rule::Style(rule) => {
+                let declarations = rebase(rule.declarations.clone());
+                for selector in &rule.selectors {
+                    let style = sheet.rules.entry(selector.clone()).or_default();
+                    apply_declaration_list(&declarations, style);
+                }
+            }
+            Rule::At(at) if at.name == "font-face" => {
+                if let Some(block) = &at.block
+                    && let Some(face) = parse_font_face(&rebase(parse_declarations(block)))
+                {
+                    sheet.font_faces.push(face);
+                }
+            }
+            Rule::At(at) => {
+                let applies = match at.name.as_str() {
+                    "media" => media_matches(&at.prelude),
+                    "supports" => !at.prelude.trim_start().starts_with("not"),
+                    _ => false,
+                };
+                if applies && let Some(block) = &at.block {
+                    add_rules(&parse_rules(block), sheet, base);
+                }
+            }
         }
-        if let Some((selector, body)) = rule.split_once('{') {
-            let mut style = Style::default();
-            apply_declarations(body, &mut style);
-            sheet.insert(selector.trim().to_string(), style);
+    }
+}
+
+// Arguments des `url()` résolus depuis la feuille `base`
+fn rebase_urls(value: &str, base: &str) -> String {
+    if base.is_empty() {
+        return value.to_string();
+    }
+    let tokens = tokenize(value);
+    let mut out = String::new();
+    let mut pos = 0;
+    for (i, spanned) in tokens.iter().enumerate() {
+        let url = match &spanned.token {
+            Token::Url(url) => url,
+            // url("...") : fonction suivie d'une chaîne
+            Token::String(url)
+                if tokens[..i]
+                    .iter()
+                    .rev()
+                    .find(|t| t.token != Token::Whitespace)
+                    .is_some_and(|t| {
+                        matches!(&t.token, Token::Function(f) if f.eq_ignore_ascii_case("url"))
+                    }) =>
+            {
+                url
+            }
+            _ => continue,
+        };
+        let resolved = resolve_url(base, url);
+        let quote = if resolved.contains('"') { '\'' } else { '"' };
+        out.push_str(&value[pos..spanned.span.start]);
+        match spanned.token {
+            Token::Url(_) => out.push_str(&format!("url({}{}{})", quote, resolved, quote)),
+            _ => out.push_str(&format!("{}{}{}", quote, resolved, quote)),
         }
+        pos = spanned.span.end;
     }
-    sheet
+    out.push_str(&value[pos..]);
+    out
 }
 
-pub fn collect_stylesheets(node: &Node) -> Stylesheet {
-    fn collect(node: &Node, sheet: &mut Stylesheet) {
+// On produit un document imprimé : seuls `print` et `all` s'appliquent
+fn media_matches(query_list: &str) -> bool {
+    if query_list.trim().is_empty() {
+        return true;
+    }
+    query_list.split(',').any(|query| {
+        let query = query.trim().to_ascii_lowercase();
+        let (negated, query) = match query.strip_prefix("not ") {
+            Some(rest) => (true, rest.trim_start()),
+            None => (
+                false,
+                query.strip_prefix("only ").unwrap_or(&query).trim_start(),
+            ),
+        };
+        let media_type = query.split_whitespace().next().unwrap_or("all");
+        let matches = media_type.starts_with('(') || matches!(media_type, "all" | "print");
+        matches != negated
+    })
+}
+
+// Feuilles `<style>` et `<link rel=stylesheet>` dans l'ordre du document
+pub fn collect_stylesheets(node: &Node, loader: &dyn ResourceLoader) -> Stylesheet {
+    fn collect(node: &Node, sheet: &mut Stylesheet, loader: &dyn ResourceLoader) {
         if let NodeType::Element(tag) = &node.node_type {
             if tag == "style" {
                 let mut css_text = String::new();
@@ -212,28 +492,26 @@ pub fn collect_stylesheets(node: &Node) -> Stylesheet {
                         css_text.push_str(t);
                     }
                 }
-                for (sel, st) in parse_stylesheet(&css_text) {
-                    sheet.insert(sel, st);
-                }
-            } else if tag == "link" {
-                if node.get_attribute("rel") == Some("stylesheet") {
-                    if let Some(href) = node.get_attribute("href") {
-                        if let Ok(content) = std::fs::read_to_string(href) {
-                            for (sel, st) in parse_stylesheet(&content) {
-                                sheet.insert(sel, st);
-                            }
-                        }
-                    }
-                }
+                let parsed = parse_stylesheet(&css_text);
+                sheet.rules.extend(parsed.rules);
+                sheet.font_faces.extend(parsed.font_faces);
+            } else if tag == "link"
+                && node.get_attribute("rel") == Some("stylesheet")
+                && let Some(href) = node.get_attribute("href")
+                && let Ok(content) = loader.load(href)
+            {
+                let parsed = parse_linked_stylesheet(&String::from_utf8_lossy(&content), href);
+                sheet.rules.extend(parsed.rules);
+                sheet.font_faces.extend(parsed.font_faces);
             }
         }
         for child in &node.children {
-            collect(&child.borrow(), sheet);
+            collect(&child.borrow(), sheet, loader);
         }
     }
 
-    let mut sheet = Stylesheet::new();
-    collect(node, &mut sheet);
+    let mut sheet = Stylesheet::default();
+    collect(node, &mut sheet, &DataUrls(loader));
     sheet
 }
 
@@ -241,151 +519,743 @@ fn merge_styles(base: &mut Style, other: &Style) {
     base.display = other.display.clone();
     base.margin = other.margin.clone();
     base.padding = other.padding.clone();
-    base.border_width = other.border_width.clone();
-    if other.font_size != 0.0 {
-        base.font_size = other.font_size;
+    if other.border_width != Sides::default() {
+        base.border_width = other.border_width.clone();
+    }
+    if other.border_style != Sides::default() {
+        base.border_style = other.border_style.clone();
+    }
+    if other.border_color != Sides::default() {
+        base.border_color = other.border_color.clone();
     }
-    if other.color != Color::default() {
+    if other.border_radius != Corners::default() {
+        base.border_radius = other.border_radius.clone();
+    }
+    if !other.font_size.is_zero() {
+        base.font_size = other.font_size.clone();
+    }
+    if other.color.is_some() {
         base.color = other.color.clone();
     }
     if let Some(bg) = &other.background {
         base.background = Some(bg.clone());
     }
+    if !other.background_image.is_empty() {
+        base.background_image = other.background_image.clone();
+    }
+    if !other.background_position.is_empty() {
+        base.background_position = other.background_position.clone();
+    }
+    if !other.background_size.is_empty() {
+        base.background_size = other.background_size.clone();
+    }
+    if !other.background_repeat.is_empty() {
+        base.background_repeat = other.background_repeat.clone();
+    }
+    if !other.background_origin.is_empty() {
+        base.background_origin = other.background_origin.clone();
+    }
+    if !other.background_clip.is_empty() {
+        base.background_clip = other.background_clip.clone();
+    }
+    if other.box_shadow.is_some() {
+        base.box_shadow = other.box_shadow.clone();
+    }
+    if other.text_shadow.is_some() {
+        base.text_shadow = other.text_shadow.clone();
+    }
+    if other.opacity.is_some() {
+        base.opacity = other.opacity;
+    }
+    if other.transform.is_some() {
+        base.transform = other.transform.clone();
+    }
+    if other.transform_origin.is_some() {
+        base.transform_origin = other.transform_origin.clone();
+    }
+    if other.overflow_x != Overflow::Visible {
+        base.overflow_x = other.overflow_x;
+    }
+    if other.object_fit.is_some() {
+        base.object_fit = other.object_fit;
+    }
+    if other.object_position.is_some() {
+        base.object_position = other.object_position.clone();
+    }
+    if other.image_orientation.is_some() {
+        base.image_orientation = other.image_orientation;
+    }
+    if other.overflow_y != Overflow::Visible {
+        base.overflow_y = other.overflow_y;
+    }
     if other.width.is_some() {
-        base.width = other.width;
+        base.width = other.width.clone();
     }
     if other.height.is_some() {
-        base.height = other.height;
+        base.height = other.height.clone();
     }
     if other.font_family.is_some() {
         base.font_family = other.font_family.clone();
     }
+    if other.font_weight.is_some() {
+        base.font_weight = other.font_weight;
+    }
+    if other.font_style.is_some() {
+        base.font_style = other.font_style;
+    }
+    if other.font_kerning.is_some() {
+        base.font_kerning = other.font_kerning;
+    }
+    if other.font_variant_ligatures.is_some() {
+        base.font_variant_ligatures = other.font_variant_ligatures;
+    }
+    if other.font_feature_settings.is_some() {
+        base.font_feature_settings = other.font_feature_settings.clone();
+    }
+    if other.text_align.is_some() {
+        base.text_align = other.text_align;
+    }
+    if other.text_indent.is_some() {
+        base.text_indent = other.text_indent.clone();
+    }
+    if other.letter_spacing.is_some() {
+        base.letter_spacing = other.letter_spacing.clone();
+    }
+    if other.word_spacing.is_some() {
+        base.word_spacing = other.word_spacing.clone();
+    }
+    if other.line_height.is_some() {
+        base.line_height = other.line_height.clone();
+    }
+    if other.vertical_align.is_some() {
+        base.vertical_align = other.vertical_align.clone();
+    }
+    if other.hyphens.is_some() {
+        base.hyphens = other.hyphens;
+    }
+    if other.direction.is_some() {
+        base.direction = other.direction;
+    }
+    if other.unicode_bidi.is_some() {
+        base.unicode_bidi = other.unicode_bidi;
+    }
+    let decoration = &other.text_decoration;
+    if decoration.line.is_some() {
+        base.text_decoration.line = decoration.line;
+    }
+    if decoration.style.is_some() {
+        base.text_decoration.style = decoration.style;
+    }
+    if decoration.color.is_some() {
+        base.text_decoration.color = decoration.color.clone();
+    }
+    if decoration.thickness.is_some() {
+        base.text_decoration.thickness = decoration.thickness.clone();
+    }
     base.position = other.position.clone();
-    base.top = other.top;
-    base.left = other.left;
+    base.top = other.top.clone();
+    base.left = other.left.clone();
+    for (name, value) in &other.custom_properties {
+        base.custom_properties.insert(name.clone(), value.clone());
+    }
+    base.important_declarations
+        .extend(other.important_declarations.iter().cloned());
+    for decl in &other.concrete_declarations {
+        override_deferred(base, decl);
+    }
+    for decl in &other.deferred_declarations {
+        base.deferred_declarations.retain(|d| !covers(&decl.name, &d.name));
+        base.deferred_declarations.push(decl.clone());
+    }
 }
 
-fn apply_declarations(css: &str, style: &mut Style) {
-    for decl in css.split(';').filter(|s| !s.trim().is_empty()) {
-        let parts: Vec<&str> = decl.split(':').map(|s| s.trim()).collect();
-        if parts.len() != 2 {
-            continue;
-        }
-        let property = parts[0];
-        let value = parts[1];
-        match property {
-            "display" => {
-                style.display = match value {
-                    "inline" => Display::Inline,
-                    "inline-block" => Display::InlineBlock,
-                    "none" => Display::None,
-                    _ => Display::Block,
-                };
+// Une déclaration concrète écarte les déclarations différées antérieures de la même propriété
+// (ou de ses composantes) ; composante d'un raccourci différé, elle est rejouée après lui
+fn override_deferred(style: &mut Style, decl: &Declaration) {
+    style.deferred_declarations.retain(|d| !covers(&decl.name, &d.name));
+    if style.deferred_declarations.iter().any(|d| covers(&d.name, &decl.name)) {
+        style.deferred_declarations.push(decl.clone());
+    }
+}
+
+// `margin` couvre `margin-top`, `border` `border-top-color`, `border-color` `border-top-color`
+fn covers(shorthand: &str, property: &str) -> bool {
+    let prefixed = property
+        .strip_prefix(shorthand)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'));
+    prefixed
+        || shorthand.split_once('-').is_some_and(|(head, tail)| {
+            property.starts_with(&format!("{}-", head))
+                && property.ends_with(&format!("-{}", tail))
+                && property.len() > shorthand.len()
+        })
+}
+
+fn apply_declaration_list(declarations: &[Declaration], style: &mut Style) {
+    let normal = declarations.iter().filter(|d| !d.important);
+    let important = declarations.iter().filter(|d| d.important);
+    for decl in normal.chain(important.clone()) {
+        apply_cascaded(decl, style);
+    }
+    style.important_declarations.extend(important.cloned());
+}
+
+fn apply_cascaded(decl: &Declaration, style: &mut Style) {
+    if decl.name.starts_with("--") {
+        style
+            .custom_properties
+            .insert(decl.name.clone(), decl.value.clone());
+        return;
+    }
+    // var() et currentColor ne peuvent être résolus qu'une fois l'élément connu
+    if contains_var(&decl.value) || (decl.name != "color" && is_current_color(&decl.value)) {
+        style.deferred_declarations.retain(|d| !covers(&decl.name, &d.name));
+        style.deferred_declarations.push(decl.clone());
+    } else {
+        apply_declaration(&decl.name, &decl.value, style);
+        override_deferred(style, decl);
+        style.concrete_declarations.push(decl.clone());
+    }
+}
+
+fn apply_declaration(property: &str, value: &str, style: &mut Style) {
+    match property {
+        "display" => {
+            style.display = match value {
+                "inline" => Display::Inline,
+                "inline-block" => Display::InlineBlock,
+                "none" => Display::None,
+                _ => Display::Block,
+            };
+        }
+        "margin" => style.margin = parse_sides(value),
+        "padding" => style.padding = parse_sides(value),
+        _ if property.starts_with("border") => apply_border(property, value, style),
+        "font-size" => {
+            if let Some(size) = parse_font_size(value) {
+                style.font_size = size;
+            }
+        }
+        "font-family" => {
+            if let Some(families) = parse_font_family(value) {
+                style.font_family = Some(families);
+            }
+        }
+        "font-weight" => {
+            if let Some(weight) = parse_font_weight(value) {
+                style.font_weight = Some(weight);
+            }
+        }
+        "font-style" => {
+            if let Some(font_style) = parse_font_style(value) {
+                style.font_style = Some(font_style);
+            }
+        }
+        "font-kerning" => {
+            if let Some(kerning) = parse_font_kerning(value) {
+                style.font_kerning = Some(kerning);
+            }
+        }
+        "font-variant-ligatures" => {
+            if let Some(ligatures) = parse_variant_ligatures(value) {
+                style.font_variant_ligatures = Some(ligatures);
+            }
+        }
+        "font-feature-settings" => {
+            if let Some(settings) = parse_feature_settings(value) {
+                style.font_feature_settings = Some(settings);
+            }
+        }
+        _ if property.starts_with("text-decoration") => {
+            apply_text_decoration(property, value, style)
+        }
+        "text-align" => {
+            if let Some(align) = parse_text_align(value) {
+                style.text_align = Some(align);
+            }
+        }
+        "text-indent" => {
+            if let Some(indent) = parse_length(value) {
+                style.text_indent = Some(indent);
+            }
+        }
+        "letter-spacing" => {
+            if let Some(spacing) = parse_spacing(value) {
+                style.letter_spacing = Some(spacing);
+            }
+        }
+        "word-spacing" => {
+            if let Some(spacing) = parse_spacing(value) {
+                style.word_spacing = Some(spacing);
+            }
+        }
+        "line-height" => {
+            if let Some(height) = parse_line_height(value) {
+                style.line_height = Some(height);
+            }
+        }
+        "vertical-align" => {
+            if let Some(align) = parse_vertical_align(value) {
+                style.vertical_align = Some(align);
+            }
+        }
+        "direction" => {
+            if let Some(direction) = parse_direction(value) {
+                style.direction = Some(direction);
+            }
+        }
+        "unicode-bidi" => {
+            if let Some(bidi) = parse_unicode_bidi(value) {
+                style.unicode_bidi = Some(bidi);
+            }
+        }
+        "hyphens" | "-webkit-hyphens" => {
+            if let Some(hyphens) = parse_hyphens(value) {
+                style.hyphens = Some(hyphens);
+            }
+        }
+        // currentColor et inherit : la couleur du parent est reprise
+        "color" => {
+            if let Some(color) = parse_color(value) {
+                style.color = Some(color);
             }
-            "margin" => style.margin = parse_sides(value),
-            "padding" => style.padding = parse_sides(value),
-            "border" | "border-width" => style.border_width = parse_sides(value),
-            "font-size" => {
-                if let Some(size) = parse_unit(value) {
-                    style.font_size = size;
+        }
+        "background-color" => {
+            if let Some(color) = parse_color(value) {
+                style.background = Some(color);
+            }
+        }
+        _ if property.starts_with("background") => apply_background(property, value, style),
+        "box-shadow" => {
+            if let Some(shadows) = parse_shadows(value, false) {
+                style.box_shadow = Some(shadows);
+            }
+        }
+        "text-shadow" => {
+            if let Some(shadows) = parse_shadows(value, true) {
+                style.text_shadow = Some(shadows);
+            }
+        }
+        "opacity" => {
+            if let Some(opacity) = parse_number(value) {
+                style.opacity = Some(opacity.clamp(0.0, 1.0));
+            }
+        }
+        "transform" => {
+            if let Some(functions) = parse_transform(value) {
+                style.transform = Some(functions);
+            }
+        }
+        "transform-origin" => {
+            if let Some(origin) = parse_transform_origin(value) {
+                style.transform_origin = Some(origin);
+            }
+        }
+        "overflow" => {
+            let values: Option<Vec<Overflow>> =
+                split_components(value).iter().map(|v| parse_overflow(v)).collect();
+            match values.as_deref() {
+                Some([both]) => {
+                    style.overflow_x = *both;
+                    style.overflow_y = *both;
                 }
+                Some([x, y]) => {
+                    style.overflow_x = *x;
+                    style.overflow_y = *y;
+                }
+                _ => {}
             }
-            "font-family" => {
-                let clean = value.trim_matches(['"', '\''].as_ref());
-                style.font_family = Some(clean.to_string());
-            }
-            "color" => style.color = parse_color(value),
-            "background" | "background-color" => style.background = Some(parse_color(value)),
-            "width" => style.width = parse_unit(value),
-            "height" => style.height = parse_unit(value),
-            "position" => {
-                style.position = match value {
-                    "relative" => Position::Relative,
-                    "absolute" => Position::Absolute,
-                    _ => Position::Static,
-                };
+        }
+        "overflow-x" => {
+            if let Some(overflow) = parse_overflow(value) {
+                style.overflow_x = overflow;
             }
-            "top" => style.top = parse_unit(value),
-            "left" => style.left = parse_unit(value),
-            _ => {}
         }
+        "overflow-y" => {
+            if let Some(overflow) = parse_overflow(value) {
+                style.overflow_y = overflow;
+            }
+        }
+        "object-fit" => {
+            if let Some(fit) = parse_object_fit(value) {
+                style.object_fit = Some(fit);
+            }
+        }
+        "object-position" => {
+            if let Some(position) = parse_object_position(value) {
+                style.object_position = Some(position);
+            }
+        }
+        "image-orientation" => {
+            if let Some(orientation) = parse_image_orientation(value) {
+                style.image_orientation = Some(orientation);
+            }
+        }
+        "width" => style.width = parse_length(value),
+        "height" => style.height = parse_length(value),
+        "position" => {
+            style.position = match value {
+                "relative" => Position::Relative,
+                "absolute" => Position::Absolute,
+                _ => Position::Static,
+            };
+        }
+        "top" => style.top = parse_length(value),
+        "left" => style.left = parse_length(value),
+        _ => {}
     }
 }
 
+fn parse_overflow(value: &str) -> Option<Overflow> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "visible" => Overflow::Visible,
+        "hidden" => Overflow::Hidden,
+        "clip" => Overflow::Clip,
+        "scroll" => Overflow::Scroll,
+        "auto" => Overflow::Auto,
+        _ => return None,
+    })
+}
+
 fn parse_sides(value: &str) -> Sides {
-    let values: Vec<f32> = value.split_whitespace().filter_map(parse_unit).collect();
-    match values.len() {
-        1 => Sides {
-            top: values[0],
-            right: values[0],
-            bottom: values[0],
-            left: values[0],
-        },
+    parse_side_values(value, |v| {
+        if v == "auto" {
+            Some(Length::default())
+        } else {
+            parse_length(v)
+        }
+    })
+    .unwrap_or_default()
+}
+
+// Développe 1 à 4 valeurs en haut / droite / bas / gauche
+fn parse_side_values<T: Clone>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Sides<T>> {
+    let values: Vec<T> = split_components(value)
+        .iter()
+        .map(|v| parse(v))
+        .collect::<Option<_>>()?;
+    let v = |i: usize| values[i].clone();
+    Some(match values.len() {
+        1 => Sides::all(v(0)),
         2 => Sides {
-            top: values[0],
-            right: values[1],
-            bottom: values[0],
-            left: values[1],
+            top: v(0),
+            right: v(1),
+            bottom: v(0),
+            left: v(1),
+        },
+        3 => Sides {
+            top: v(0),
+            right: v(1),
+            bottom: v(2),
+            left: v(1),
         },
         4 => Sides {
-            top: values[0],
-            right: values[1],
-            bottom: values[2],
-            left: values[3],
+            top: v(0),
+            right: v(1),
+            bottom: v(2),
+            left: v(3),
         },
-        _ => Sides::default(),
+        _ => return None,
+    })
+}
+
+fn apply_border(property: &str, value: &str, style: &mut Style) {
+    let rest = property
+        .trim_start_matches("border")
+        .trim_start_matches('-');
+    let (side, part) = match rest.split_once('-') {
+        Some((side @ ("top" | "right" | "bottom" | "left"), part)) => (Some(side), part),
+        _ if matches!(rest, "top" | "right" | "bottom" | "left") => (Some(rest), ""),
+        _ => (None, rest),
+    };
+    if part.ends_with("radius") {
+        apply_border_radius(property, value, style);
+        return;
+    }
+    match (side, part) {
+        (None, "") => {
+            if let Some((width, border_style, color)) = parse_border_shorthand(value) {
+                style.border_width = Sides::all(width);
+                style.border_style = Sides::all(border_style);
+                style.border_color = Sides::all(color);
+            }
+        }
+        (Some(side), "") => {
+            if let Some((width, border_style, color)) = parse_border_shorthand(value) {
+                *side_mut(&mut style.border_width, side) = width;
+                *side_mut(&mut style.border_style, side) = border_style;
+                *side_mut(&mut style.border_color, side) = color;
+            }
+        }
+        (None, "width") => {
+            if let Some(sides) = parse_side_values(value, parse_border_width) {
+                style.border_width = sides;
+            }
+        }
+        (None, "style") => {
+            if let Some(sides) = parse_side_values(value, parse_border_style) {
+                style.border_style = sides;
+            }
+        }
+        (None, "color") => {
+            if let Some(sides) = parse_side_values(value, |v| parse_color(v).map(Some)) {
+                style.border_color = sides;
+            }
+        }
+        (Some(side), "width") => {
+            if let Some(width) = parse_border_width(value) {
+                *side_mut(&mut style.border_width, side) = width;
+            }
+        }
+        (Some(side), "style") => {
+            if let Some(border_style) = parse_border_style(value) {
+                *side_mut(&mut style.border_style, side) = border_style;
+            }
+        }
+        (Some(side), "color") => {
+            if let Some(color) = parse_color(value) {
+                *side_mut(&mut style.border_color, side) = Some(color);
+            }
+        }
+        _ => {}
     }
 }
 
-fn parse_unit(value: &str) -> Option<f32> {
-    if value.ends_with("px") {
-        value.trim_end_matches("px").parse().ok()
-    } else if value.ends_with("pt") {
-        value.trim_end_matches("pt").parse().ok()
-    } else if value.ends_with('%') {
-        value.trim_end_matches('%').parse().ok()
-    } else if value.ends_with("em") {
-        value.trim_end_matches("em").parse().ok()
-    } else if value.ends_with("rem") {
-        value.trim_end_matches("rem").parse().ok()
-    } else {
-        value.parse().ok()
+fn side_mut<'a, T>(sides: &'a mut Sides<T>, side: &str) -> &'a mut T {
+    match side {
+        "top" => &mut sides.top,
+        "right" => &mut sides.right,
+        "bottom" => &mut sides.bottom,
+        _ => &mut sides.left,
+    }
+}
+
+fn parse_border_width(value: &str) -> Option<Length> {
+    match value {
+        "thin" => Some(Length::Px(1.0)),
+        "medium" => Some(Length::Px(3.0)),
+        "thick" => Some(Length::Px(5.0)),
+        _ => parse_length(value),
+    }
+}
+
+fn parse_border_style(value: &str) -> Option<BorderStyle> {
+    Some(match value.to_ascii_lowercase().as_str() {
+        "none" => BorderStyle::None,
+        "hidden" => BorderStyle::Hidden,
+        "solid" => BorderStyle::Solid,
+        "dashed" => BorderStyle::Dashed,
+        "dotted" => BorderStyle::Dotted,
+        "double" => BorderStyle::Double,
+        "groove" => BorderStyle::Groove,
+        "ridge" => BorderStyle::Ridge,
+        "inset" => BorderStyle::Inset,
+        "outset" => BorderStyle::Outset,
+        _ => return None,
+    })
+}
+
+// `border: <largeur> || <style> || <couleur>`, les valeurs omises reprennent leur valeur initiale
+fn parse_border_shorthand(value: &str) -> Option<(Length, BorderStyle, Option<Color>)> {
+    let mut width = None;
+    let mut border_style = None;
+    let mut color = None;
+    for part in split_components(value) {
+        if width.is_none()
+            && let Some(w) = parse_border_width(&part)
+        {
+            width = Some(w);
+        } else if border_style.is_none()
+            && let Some(s) = parse_border_style(&part)
+        {
+            border_style = Some(s);
+        } else if color.is_none()
+            && let Some(c) = parse_color(&part)
+        {
+            color = Some(c);
+        } else {
+            return None;
+        }
+    }
+    Some((
+        width.unwrap_or(Length::Px(3.0)),
+        border_style.unwrap_or_default(),
+        color,
+    ))
+}
+
+fn apply_border_radius(property: &str, value: &str, style: &mut Style) {
+    let radius = &mut style.border_radius;
+    let corner = match property {
+        "border-radius" => {
+            if let Some(corners) = parse_border_radius(value) {
+                *radius = corners;
+            }
+            return;
+        }
+        "border-top-left-radius" => &mut radius.top_left,
+        "border-top-right-radius" => &mut radius.top_right,
+        "border-bottom-right-radius" => &mut radius.bottom_right,
+        "border-bottom-left-radius" => &mut radius.bottom_left,
+        _ => return,
+    };
+    let parts: Option<Vec<Length>> = split_components(value)
+        .iter()
+        .map(|v| parse_length(v))
+        .collect();
+    match parts.as_deref() {
+        Some([r]) => {
+            *corner = Radius {
+                horizontal: r.clone(),
+                vertical: r.clone(),
+            }
+        }
+        Some([h, v]) => {
+            *corner = Radius {
+                horizontal: h.clone(),
+                vertical: v.clone(),
+            }
+        }
+        _ => {}
     }
 }
 
-fn parse_color(value: &str) -> Color {
-    if value.starts_with('#') {
-        if value.len() == 7 {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&value[1..3], 16),
-                u8::from_str_radix(&value[3..5], 16),
-                u8::from_str_radix(&value[5..7], 16),
-            ) {
-                return Color { r, g, b, a: 1.0 };
-            }
-        } else if value.len() == 9 {
-            if let (Ok(r), Ok(g), Ok(b), Ok(a)) = (
-                u8::from_str_radix(&value[1..3], 16),
-                u8::from_str_radix(&value[3..5], 16),
-                u8::from_str_radix(&value[5..7], 16),
-                u8::from_str_radix(&value[7..9], 16),
-            ) {
-                return Color { r, g, b, a: a as f32 / 255.0 };
-            }
-        }
-    } else if value.starts_with("rgba(") && value.ends_with(')') {
-        let inner = &value[5..value.len() - 1];
-        let parts: Vec<&str> = inner.split(',').map(|s| s.trim()).collect();
-        if parts.len() == 4 {
-            if let (Ok(r), Ok(g), Ok(b), Ok(a)) = (
-                parts[0].parse::<u8>(),
-                parts[1].parse::<u8>(),
-                parts[2].parse::<u8>(),
-                parts[3].parse::<f32>(),
-            ) {
-                return Color { r, g, b, a };
-            }
-        }
-    }
-    Color::default()
-}
\ No newline at end of file
+// `border-radius: 1-4 valeurs [ / 1-4 valeurs ]`, dans l'ordre des coins depuis haut-gauche
+fn parse_border_radius(value: &str) -> Option<Corners> {
+    let (h, v) = match value.split_once('/') {
+        Some((h, v)) => (h, v),
+        None => (value, value),
+    };
+    let h = parse_side_values(h, parse_length)?;
+    let v = parse_side_values(v, parse_length)?;
+    let radius = |horizontal: Length, vertical: Length| Radius {
+        horizontal,
+        vertical,
+    };
+    // Sides : top = haut-gauche, right = haut-droit, bottom = bas-droit, left = bas-gauche
+    Some(Corners {
+        top_left: radius(h.top, v.top),
+        top_right: radius(h.right, v.right),
+        bottom_right: radius(h.bottom, v.bottom),
+        bottom_left: radius(h.left, v.left),
+    })
+}
+
+// Découpe une valeur sur les espaces de premier niveau (hors parenthèses)
+pub fn split_components(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    for c in value.trim().chars() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+// Découpe sur les virgules de premier niveau (hors parenthèses et chaînes)
+pub fn split_commas(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    for c in value.chars() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(std::mem::take(&mut current).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current.trim().to_string());
+    parts
+}
+
+pub fn parse_length(value: &str) -> Option<Length> {
+    let tokens: Vec<Token> = tokenize(value.trim())
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    match tokens.as_slice() {
+        [Token::Dimension(v, unit)] => Length::from_unit(*v, unit),
+        [Token::Percentage(v)] => Some(Length::Percent(*v)),
+        // Nombre sans unité toléré comme en mode quirks
+        [Token::Number(v)] => Some(Length::Px(*v)),
+        [Token::Function(name), ..] if is_math_function(name) => {
+            parse_math_function(&tokens).map(|expr| Length::Calc(Box::new(expr)))
+        }
+        _ => None,
+    }
+}
+
+// Nombre ou pourcentage (`scale(150%)`)
+pub fn parse_number(value: &str) -> Option<f32> {
+    match tokenize(value.trim()).as_slice() {
+        [t] => match t.token {
+            Token::Number(v) => Some(v),
+            Token::Percentage(v) => Some(v / 100.0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Angle en degrés ; `0` sans unité est toléré
+pub fn parse_angle(value: &str) -> Option<f32> {
+    let tokens: Vec<Token> = tokenize(value.trim())
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    match tokens.as_slice() {
+        [Token::Dimension(v, unit)] => Some(match unit.to_ascii_lowercase().as_str() {
+            "deg" => *v,
+            "rad" => v.to_degrees(),
+            "grad" => v * 0.9,
+            "turn" => v * 360.0,
+            _ => return None,
+        }),
+        [Token::Number(v)] if *v == 0.0 => Some(0.0),
+        _ => None,
+    }
+}
+
+fn parse_font_size(value: &str) -> Option<Length> {
+    let px = match value.trim().to_ascii_lowercase().as_str() {
+        "xx-small" => 9.0,
+        "x-small" => 10.0,
+        "small" => 13.0,
+        "medium" => 16.0,
+        "large" => 18.0,
+        "x-large" => 24.0,
+        "xx-large" => 32.0,
+        "xxx-large" => 48.0,
+        "smaller" => return Some(Length::Em(0.833)),
+        "larger" => return Some(Length::Em(1.2)),
+        _ => return parse_length(value),
+    };
+    Some(Length::Px(px))
+}
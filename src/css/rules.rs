@@ -0,0 +1,334 @@
+use super::tokenizer::{Spanned, Token, tokenize};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    Style(StyleRule),
+    At(AtRule),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    pub selectors: Vec<String>,
+    pub declarations: Vec<Declaration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtRule {
+    pub name: String,
+    pub prelude: String,
+    pub block: Option<String>, // Contenu brut entre { }, analysé selon la règle
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub name: String,
+    pub value: String,
+    pub important: bool,
+}
+
+pub fn parse_rules(css: &str) -> Vec<Rule> {
+    let tokens = tokenize(css);
+    let mut rules = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::Whitespace | Token::Cdo | Token::Cdc => i += 1,
+            Token::AtKeyword(_) => {
+                let (rule, next) = consume_at_rule(css, &tokens, i);
+                rules.push(Rule::At(rule));
+                i = next;
+            }
+            _ => {
+                let (rule, next) = consume_qualified_rule(css, &tokens, i);
+                if let Some(rule) = rule {
+                    rules.push(Rule::Style(rule));
+                }
+                i = next;
+            }
+        }
+    }
+    rules
+}
+
+pub fn parse_declarations(css: &str) -> Vec<Declaration> {
+    let tokens = tokenize(css);
+    let mut declarations = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::Whitespace | Token::Semicolon => i += 1,
+            Token::AtKeyword(_) => {
+                // Les at-rules imbriquées dans un bloc de déclarations sont ignorées
+                i = consume_at_rule(css, &tokens, i).1;
+            }
+            Token::Ident(name) => {
+                let end = declaration_end(&tokens, i, name.starts_with("--"));
+                if let Some(decl) = consume_declaration(css, &tokens[i..end]) {
+                    declarations.push(decl);
+                }
+                i = end;
+            }
+            _ => i = declaration_end(&tokens, i, false),
+        }
+    }
+    declarations
+}
+
+fn consume_at_rule(css: &str, tokens: &[Spanned], start: usize) -> (AtRule, usize) {
+    let name = match &tokens[start].token {
+        Token::AtKeyword(n) => n.to_ascii_lowercase(),
+        _ => String::new(),
+    };
+    let prelude_start = tokens[start].span.end;
+    let mut i = start + 1;
+    while i < tokens.len() {
+        match tokens[i].token {
+            Token::Semicolon => {
+                let prelude = css[prelude_start..tokens[i].span.start].trim().to_string();
+                return (
+                    AtRule {
+                        name,
+                        prelude,
+                        block: None,
+                    },
+                    i + 1,
+                );
+            }
+            Token::OpenCurly => {
+                let prelude = css[prelude_start..tokens[i].span.start].trim().to_string();
+                let close = skip_component(tokens, i);
+                let block = block_text(css, tokens, i, close);
+                return (
+                    AtRule {
+                        name,
+                        prelude,
+                        block: Some(block),
+                    },
+                    close,
+                );
+            }
+            _ => i = skip_component(tokens, i),
+        }
+    }
+    let prelude = css[prelude_start..].trim().to_string();
+    (
+        AtRule {
+            name,
+            prelude,
+            block: None,
+        },
+        i,
+    )
+}
+
+fn consume_qualified_rule(
+    css: &str,
+    tokens: &[Spanned],
+    start: usize,
+) -> (Option<StyleRule>, usize) {
+    let mut i = start;
+    let mut valid = true;
+    while i < tokens.len() {
+        match tokens[i].token {
+            Token::OpenCurly => {
+                let close = skip_component(tokens, i);
+                if !valid {
+                    return (None, close);
+                }
+                let selectors = split_selectors(css, &tokens[start..i]);
+                if selectors.is_empty() {
+                    return (None, close);
+                }
+                let declarations = parse_declarations(&block_text(css, tokens, i, close));
+                return (
+                    Some(StyleRule {
+                        selectors,
+                        declarations,
+                    }),
+                    close,
+                );
+            }
+            Token::CloseCurly | Token::BadString | Token::BadUrl => {
+                valid = false;
+                i += 1;
+            }
+            _ => i = skip_component(tokens, i),
+        }
+    }
+    // Fin du fichier avant le bloc : règle ignorée
+    (None, i)
+}
+
+fn consume_declaration(css: &str, tokens: &[Spanned]) -> Option<Declaration> {
+    let name = match &tokens.first()?.token {
+        Token::Ident(n) if n.starts_with("--") => n.clone(),
+        Token::Ident(n) => n.to_ascii_lowercase(),
+        _ => return None,
+    };
+    let mut i = 1;
+    while tokens.get(i).is_some_and(|t| t.token == Token::Whitespace) {
+        i += 1;
+    }
+    if tokens.get(i)?.token != Token::Colon {
+        return None;
+    }
+    let mut value: Vec<&Spanned> = tokens[i + 1..].iter().collect();
+    while value.last().is_some_and(|t| t.token == Token::Whitespace) {
+        value.pop();
+    }
+
+    let mut important = false;
+    if let Some(last) = value.last()
+        && matches!(&last.token, Token::Ident(id) if id.eq_ignore_ascii_case("important"))
+    {
+        let mut j = value.len() - 1;
+        while j > 0 && value[j - 1].token == Token::Whitespace {
+            j -= 1;
+        }
+        if j > 0 && value[j - 1].token == Token::Delim('!') {
+            important = true;
+            value.truncate(j - 1);
+            while value.last().is_some_and(|t| t.token == Token::Whitespace) {
+                value.pop();
+            }
+        }
+    }
+
+    let mut depth = 0i32;
+    for t in &value {
+        match t.token {
+            Token::BadString | Token::BadUrl => return None,
+            // Règle imbriquée (`a { b:hover { } }`), non prise en charge
+            Token::OpenCurly if depth == 0 && !name.starts_with("--") => return None,
+            Token::OpenParen | Token::OpenSquare | Token::OpenCurly | Token::Function(_) => {
+                depth += 1
+            }
+            Token::CloseParen | Token::CloseSquare | Token::CloseCurly => {
+                depth -= 1;
+                if depth < 0 {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let value = match (value.first(), value.last()) {
+        (Some(first), Some(last)) => css[first.span.start..last.span.end].trim().to_string(),
+        _ => String::new(),
+    };
+    if value.is_empty() && !name.starts_with("--") {
+        return None;
+    }
+    Some(Declaration {
+        name,
+        value,
+        important,
+    })
+}
+
+// Index du `;` terminant la déclaration (ou de la fin d'un bloc imbriqué)
+fn declaration_end(tokens: &[Spanned], start: usize, custom: bool) -> usize {
+    let mut i = start;
+    while i < tokens.len() {
+        match tokens[i].token {
+            Token::Semicolon => return i,
+            Token::OpenCurly if !custom => return skip_component(tokens, i),
+            _ => i = skip_component(tokens, i),
+        }
+    }
+    i
+}
+
+// Renvoie l'index qui suit la valeur composante commençant en `start`
+fn skip_component(tokens: &[Spanned], start: usize) -> usize {
+    let closing = match tokens[start].token {
+        Token::OpenParen | Token::Function(_) => Token::CloseParen,
+        Token::OpenSquare => Token::CloseSquare,
+        Token::OpenCurly => Token::CloseCurly,
+        _ => return start + 1,
+    };
+    let mut i = start + 1;
+    while i < tokens.len() {
+        if tokens[i].token == closing {
+            return i + 1;
+        }
+        i = skip_component(tokens, i);
+    }
+    i
+}
+
+fn block_text(css: &str, tokens: &[Spanned], open: usize, after_close: usize) -> String {
+    let start = tokens[open].span.end;
+    let end = if after_close > open + 1
+        && tokens
+            .get(after_close - 1)
+            .is_some_and(|t| t.token == Token::CloseCurly)
+    {
+        tokens[after_close - 1].span.start
+    } else {
+        css.len()
+    };
+    css[start..end.max(start)].to_string()
+}
+
+fn split_selectors(css: &str, prelude: &[Spanned]) -> Vec<String> {
+    let mut selectors = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+    while i < prelude.len() {
+        if prelude[i].token == Token::Comma {
+            selectors.push(normalize_selector(&current));
+            current.clear();
+            i += 1;
+            continue;
+        }
+        let next = skip_component(prelude, i);
+        current.push_str(&css[prelude[i].span.start..prelude[next - 1].span.end]);
+        i = next;
+    }
+    selectors.push(normalize_selector(&current));
+    if selectors.iter().any(|s| s.is_empty()) {
+        // Un sélecteur invalide invalide toute la liste
+        return Vec::new();
+    }
+    selectors
+}
+
+fn normalize_selector(selector: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut pending_space = false;
+    for c in selector.trim().chars() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ => {}
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if depth == 0 && matches!(c, '>' | '+' | '~') {
+            out.truncate(out.trim_end().len());
+            out.push_str(&format!(" {} ", c));
+            pending_space = false;
+            continue;
+        }
+        if pending_space && !out.is_empty() && !out.ends_with(' ') {
+            out.push(' ');
+        }
+        pending_space = false;
+        out.push(c);
+    }
+    out.trim().to_string()
+}
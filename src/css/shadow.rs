@@ -0,0 +1,63 @@
+use super::color::parse_color;
+use super::parser::{parse_length, split_commas, split_components};
+use super::styles::{LengthContext, Shadow};
+
+// `none` ou liste : `inset? <x> <y> <flou>? <étalement>? <couleur>?` dans un ordre libre
+pub fn parse_shadows(value: &str, text: bool) -> Option<Vec<Shadow>> {
+    if value.trim().eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+    split_commas(value)
+        .iter()
+        .map(|shadow| parse_shadow(shadow, text))
+        .collect()
+}
+
+fn parse_shadow(value: &str, text: bool) -> Option<Shadow> {
+    let mut lengths = Vec::new();
+    let mut color = None;
+    let mut inset = false;
+    // Les longueurs doivent se suivre
+    let mut lengths_done = false;
+    for part in split_components(value) {
+        if part.eq_ignore_ascii_case("inset") && !text && !inset {
+            inset = true;
+        } else if let Some(length) = parse_length(&part).filter(|_| !lengths_done) {
+            lengths.push(length);
+            continue;
+        } else if color.is_none()
+            && let Some(c) = parse_color(&part)
+        {
+            color = Some(c);
+        } else {
+            return None;
+        }
+        lengths_done = !lengths.is_empty();
+    }
+    let max = if text { 3 } else { 4 };
+    if lengths.len() < 2 || lengths.len() > max {
+        return None;
+    }
+    let mut lengths = lengths.into_iter();
+    let shadow = Shadow {
+        offset_x: lengths.next()?,
+        offset_y: lengths.next()?,
+        blur: lengths.next().unwrap_or_default(),
+        spread: lengths.next().unwrap_or_default(),
+        color,
+        inset,
+    };
+    // Un flou négatif est invalide (toutes les unités ont un signe indépendant du contexte)
+    let unit = LengthContext {
+        font_size: 1.0,
+        root_font_size: 1.0,
+        containing_width: 1.0,
+        containing_height: None,
+        page_width: 1.0,
+        page_height: 1.0,
+    };
+    if shadow.blur.resolve(&unit) < 0.0 {
+        return None;
+    }
+    Some(shadow)
+}
@@ -1,3 +1,5 @@
+use super::calc::CalcExpr;
+use super::rules::Declaration;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -14,37 +16,757 @@ pub struct Style {
     pub margin: Sides,
     pub padding: Sides,
     pub border_width: Sides,
-    pub font_size: f32,
-    pub color: Color,
+    pub border_style: Sides<BorderStyle>,
+    pub border_color: Sides<Option<Color>>, // None : currentColor
+    pub border_radius: Corners,
+    pub font_size: Length,
+    pub color: Option<Color>,
     pub background: Option<Color>,
-    pub width: Option<f32>,
-    pub height: Option<f32>,
-    pub font_family: Option<String>,
+    pub background_image: Vec<BackgroundImage>, // une entrée par couche, la première au-dessus
+    pub background_position: Vec<BackgroundPosition>,
+    pub background_size: Vec<BackgroundSize>,
+    pub background_repeat: Vec<BackgroundRepeat>,
+    pub background_origin: Vec<BoxArea>,
+    pub background_clip: Vec<BoxArea>,
+    pub box_shadow: Option<Vec<Shadow>>, // Some(vec![]) : `none`
+    pub text_shadow: Option<Vec<Shadow>>,
+    pub opacity: Option<f32>,
+    pub transform: Option<Vec<TransformFunction>>, // Some(vec![]) : `none`
+    pub transform_origin: Option<BackgroundPosition>, // None : centre de la boîte
+    pub overflow_x: Overflow,
+    pub overflow_y: Overflow,
+    pub object_fit: Option<ObjectFit>,
+    pub object_position: Option<BackgroundPosition>, // None : centre de la boîte
+    pub image_orientation: Option<ImageOrientation>,
+    pub width: Option<Length>,
+    pub height: Option<Length>,
+    pub font_family: Option<Vec<FontFamily>>, // par ordre de préférence
+    pub font_weight: Option<FontWeight>,
+    pub font_style: Option<FontStyle>,
+    pub font_kerning: Option<FontKerning>,
+    pub font_variant_ligatures: Option<VariantLigatures>,
+    pub font_feature_settings: Option<Vec<FeatureSetting>>, // Some(vec![]) : `normal`
+    pub text_decoration: TextDecoration,
+    pub text_decorations: Vec<TextDecoration>, // propagées par les ancêtres, couleur résolue
+    pub text_align: Option<TextAlign>,
+    pub text_indent: Option<Length>,
+    pub letter_spacing: Option<Length>,
+    pub word_spacing: Option<Length>,
+    pub line_height: Option<LineHeight>,
+    pub vertical_align: Option<VerticalAlign>,
+    pub hyphens: Option<Hyphens>,
+    pub direction: Option<Direction>,
+    pub unicode_bidi: Option<UnicodeBidi>,
+    pub lang: Option<String>, // attribut `lang` de l'élément ou d'un ancêtre
     pub position: Position,
-    pub top: Option<f32>,
-    pub left: Option<f32>,
+    pub top: Option<Length>,
+    pub left: Option<Length>,
+    pub custom_properties: HashMap<String, String>,
+    pub deferred_declarations: Vec<Declaration>, // var() ou currentColor à résoudre
+    pub concrete_declarations: Vec<Declaration>, // sans var() : écartent les différées antérieures
+    pub important_declarations: Vec<Declaration>, // rejouées après l'attribut style
 }
 
-#[derive(Debug, Clone, PartialEq)] // Ajout de PartialEq
+#[derive(Debug, Clone, PartialEq, Default)] // Ajout de PartialEq
 pub enum Display {
+    #[default]
     Block,
     Inline,
     InlineBlock,
     None,
 }
 
-impl Default for Display {
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sides<T = Length> {
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+    pub left: T,
+}
+
+impl<T: Clone> Sides<T> {
+    pub fn all(value: T) -> Self {
+        Sides {
+            top: value.clone(),
+            right: value.clone(),
+            bottom: value.clone(),
+            left: value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BorderStyle {
+    #[default]
+    None,
+    Hidden,
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+    Groove,
+    Ridge,
+    Inset,
+    Outset,
+}
+
+impl BorderStyle {
+    pub fn is_visible(&self) -> bool {
+        !matches!(self, BorderStyle::None | BorderStyle::Hidden)
+    }
+}
+
+// Rayon elliptique d'un coin : horizontal puis vertical
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Radius {
+    pub horizontal: Length,
+    pub vertical: Length,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Corners {
+    pub top_left: Radius,
+    pub top_right: Radius,
+    pub bottom_right: Radius,
+    pub bottom_left: Radius,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BackgroundImage {
+    #[default]
+    None,
+    Url(String),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    pub position: Option<Length>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientDirection {
+    Angle(f32), // degrés, 0 vers le haut, sens horaire
+    Corner { right: bool, bottom: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    pub direction: GradientDirection,
+    pub stops: Vec<ColorStop>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RadialExtent {
+    ClosestSide,
+    FarthestSide,
+    ClosestCorner,
+    FarthestCorner,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadialSize {
+    Extent(RadialExtent),
+    Explicit(Length, Length), // rayons horizontal et vertical (égaux pour un cercle)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    pub circle: bool,
+    pub size: RadialSize,
+    pub position: BackgroundPosition,
+    pub stops: Vec<ColorStop>,
+}
+
+// Les pourcentages portent sur (zone - image), comme en CSS
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackgroundPosition {
+    pub x: Length,
+    pub y: Length,
+}
+
+impl Default for BackgroundPosition {
     fn default() -> Self {
-        Display::Block
+        BackgroundPosition {
+            x: Length::Percent(0.0),
+            y: Length::Percent(0.0),
+        }
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct Sides {
-    pub top: f32,
-    pub right: f32,
-    pub bottom: f32,
-    pub left: f32,
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BackgroundSize {
+    #[default]
+    Auto,
+    Cover,
+    Contain,
+    Explicit(Option<Length>, Option<Length>), // None : auto
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Repeat {
+    #[default]
+    Repeat,
+    NoRepeat,
+    Space,
+    Round,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BackgroundRepeat {
+    pub x: Repeat,
+    pub y: Repeat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BoxArea {
+    #[default]
+    BorderBox,
+    PaddingBox,
+    ContentBox,
+}
+
+// `box-shadow` / `text-shadow` ; le texte n'a ni `spread` ni `inset`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Shadow {
+    pub offset_x: Length,
+    pub offset_y: Length,
+    pub blur: Length,
+    pub spread: Length,
+    pub color: Option<Color>, // None : currentColor
+    pub inset: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformFunction {
+    Translate(Length, Length),
+    Scale(f32, f32),
+    Rotate(f32),      // degrés, sens horaire
+    Skew(f32, f32),   // degrés
+    Matrix([f32; 6]), // translation en px
+}
+
+// À l'impression, tout ce qui n'est pas `visible` découpe le contenu
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    Hidden,
+    Clip,
+    Scroll,
+    Auto,
+}
+
+// Ajustement d'une image à sa boîte
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ObjectFit {
+    #[default]
+    Fill,
+    Contain,
+    Cover,
+    None,
+    ScaleDown,
+}
+
+// `from-image` : orientation EXIF appliquée
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ImageOrientation {
+    #[default]
+    FromImage,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontWeight {
+    Weight(u16),
+    Bolder, // relatives au parent, résolues à l'héritage
+    Lighter,
+}
+
+impl FontWeight {
+    pub fn value(self) -> u16 {
+        match self {
+            FontWeight::Weight(w) => w,
+            FontWeight::Bolder => 700,
+            FontWeight::Lighter => 100,
+        }
+    }
+
+    pub fn is_bold(self) -> bool {
+        self.value() >= 600
+    }
+
+    // Table des poids relatifs de CSS Fonts 4
+    pub fn relative_to(self, parent: u16) -> u16 {
+        match self {
+            FontWeight::Weight(w) => w,
+            FontWeight::Bolder if parent < 350 => 400,
+            FontWeight::Bolder if parent < 550 => 700,
+            FontWeight::Bolder => 900,
+            FontWeight::Lighter if parent < 550 => 100,
+            FontWeight::Lighter if parent < 750 => 400,
+            FontWeight::Lighter => 700,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+    Oblique,
+}
+
+// Les familles génériques ne sont reconnues que sans guillemets
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontFamily {
+    Named(String),
+    Serif,
+    SansSerif,
+    Monospace,
+    Cursive,
+    Fantasy,
+    SystemUi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FontKerning {
+    #[default]
+    Auto,
+    Normal,
+    None,
+}
+
+// Ligatures actives ; `normal` : usuelles et contextuelles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VariantLigatures {
+    pub common: bool,
+    pub discretionary: bool,
+    pub historical: bool,
+    pub contextual: bool,
+}
+
+impl Default for VariantLigatures {
+    fn default() -> Self {
+        VariantLigatures {
+            common: true,
+            discretionary: false,
+            historical: false,
+            contextual: true,
+        }
+    }
+}
+
+// Fonctionnalité OpenType de `font-feature-settings`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeatureSetting {
+    pub tag: [u8; 4],
+    pub value: u32,
+}
+
+// Règle `@font-face` ; graisses en intervalle, plages Unicode vides : tous les caractères
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFace {
+    pub family: String,
+    pub sources: Vec<FontSource>,
+    pub weight: (u16, u16),
+    pub italic: bool,
+    pub unicode_range: Vec<(u32, u32)>,
+}
+
+// Sources dans l'ordre d'essai ; les URL relatives partent du dossier de la feuille
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontSource {
+    Local(String),
+    Url(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+}
+
+impl DecorationLine {
+    pub fn is_none(&self) -> bool {
+        *self == DecorationLine::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DecorationStyle {
+    #[default]
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
+// `start` et `end` dépendent du sens du paragraphe
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextAlign {
+    #[default]
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnicodeBidi {
+    #[default]
+    Normal,
+    Embed,
+    Isolate,
+    BidiOverride,
+    IsolateOverride,
+    Plaintext,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Hyphens {
+    None,
+    #[default]
+    Manual, // seulement aux traits d'union conditionnels (`&shy;`)
+    Auto,
+}
+
+// Un nombre s'hérite tel quel et porte sur la taille de police de chaque descendant
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum LineHeight {
+    #[default]
+    Normal,
+    Number(f32),
+    Length(Length),
+}
+
+impl LineHeight {
+    // Hauteur en points pour une taille de police donnée (longueur déjà résolue)
+    pub fn to_pt(&self, font_size: f32) -> f32 {
+        match self {
+            LineHeight::Normal => font_size * 1.2,
+            LineHeight::Number(n) => font_size * n,
+            LineHeight::Length(l) => l.to_pt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Baseline,
+    Sub,
+    Super,
+    TextTop,
+    TextBottom,
+    Middle,
+    Top,
+    Bottom,
+    Length(Length), // décalage vers le haut ; % de la hauteur de ligne
+}
+
+// Chaque sous-propriété reste à None tant qu'elle n'est pas déclarée
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextDecoration {
+    pub line: Option<DecorationLine>,
+    pub style: Option<DecorationStyle>,
+    pub color: Option<Color>,      // None : currentColor
+    pub thickness: Option<Length>, // None : auto, d'après la police
+}
+
+// Toutes les longueurs sont converties en points PDF (1/72 in) à la résolution
+#[derive(Debug, Clone, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Pt(f32),
+    Pc(f32),
+    In(f32),
+    Cm(f32),
+    Mm(f32),
+    Q(f32),
+    Em(f32),
+    Rem(f32),
+    Percent(f32),
+    Vw(f32),
+    Vh(f32),
+    Vmin(f32),
+    Vmax(f32),
+    Calc(Box<CalcExpr>), // calc(), min(), max(), clamp()
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Length::Px(0.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LengthContext {
+    pub font_size: f32,
+    pub root_font_size: f32,
+    pub containing_width: f32,
+    pub containing_height: Option<f32>,
+    pub page_width: f32,
+    pub page_height: f32,
+}
+
+pub const DEFAULT_FONT_SIZE: f32 = 12.0; // `medium` = 16px
+
+impl Length {
+    pub fn from_unit(value: f32, unit: &str) -> Option<Length> {
+        Some(match unit.to_ascii_lowercase().as_str() {
+            "px" => Length::Px(value),
+            "pt" => Length::Pt(value),
+            "pc" => Length::Pc(value),
+            "in" => Length::In(value),
+            "cm" => Length::Cm(value),
+            "mm" => Length::Mm(value),
+            "q" => Length::Q(value),
+            "em" => Length::Em(value),
+            "rem" => Length::Rem(value),
+            "vw" => Length::Vw(value),
+            "vh" => Length::Vh(value),
+            "vmin" => Length::Vmin(value),
+            "vmax" => Length::Vmax(value),
+            _ => return None,
+        })
+    }
+
+    pub fn resolve(&self, ctx: &LengthContext) -> f32 {
+        match *self {
+            Length::Calc(ref expr) => expr.eval(ctx),
+            Length::Em(v) => v * ctx.font_size,
+            Length::Rem(v) => v * ctx.root_font_size,
+            Length::Percent(v) => v / 100.0 * ctx.containing_width,
+            Length::Vw(v) => v / 100.0 * ctx.page_width,
+            Length::Vh(v) => v / 100.0 * ctx.page_height,
+            Length::Vmin(v) => v / 100.0 * ctx.page_width.min(ctx.page_height),
+            Length::Vmax(v) => v / 100.0 * ctx.page_width.max(ctx.page_height),
+            _ => self.to_pt(),
+        }
+    }
+
+    // Valeur en points des unités absolues ; 0 pour les unités relatives
+    pub fn to_pt(&self) -> f32 {
+        match *self {
+            Length::Calc(ref expr) => expr.eval(&LengthContext {
+                font_size: 0.0,
+                root_font_size: 0.0,
+                containing_width: 0.0,
+                containing_height: None,
+                page_width: 0.0,
+                page_height: 0.0,
+            }),
+            Length::Px(v) => v * 0.75,
+            Length::Pt(v) => v,
+            Length::Pc(v) => v * 12.0,
+            Length::In(v) => v * 72.0,
+            Length::Cm(v) => v * 72.0 / 2.54,
+            Length::Mm(v) => v * 72.0 / 25.4,
+            Length::Q(v) => v * 72.0 / 101.6,
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match *self {
+            Length::Px(v)
+            | Length::Pt(v)
+            | Length::Pc(v)
+            | Length::In(v)
+            | Length::Cm(v)
+            | Length::Mm(v)
+            | Length::Q(v)
+            | Length::Em(v)
+            | Length::Rem(v)
+            | Length::Percent(v)
+            | Length::Vw(v)
+            | Length::Vh(v)
+            | Length::Vmin(v)
+            | Length::Vmax(v) => v == 0.0,
+            Length::Calc(_) => false,
+        }
+    }
+
+    pub fn has_percent(&self) -> bool {
+        match self {
+            Length::Percent(_) => true,
+            Length::Calc(expr) => expr.has_percent(),
+            _ => false,
+        }
+    }
+}
+
+impl Style {
+    // Calcule toutes les longueurs en points ; `ctx.font_size` est celle du parent
+    pub fn resolve_lengths(&mut self, ctx: &LengthContext) {
+        let font_size = if self.font_size.is_zero() {
+            ctx.font_size
+        } else {
+            let parent = LengthContext {
+                containing_width: ctx.font_size,
+                ..ctx.clone()
+            };
+            self.font_size.resolve(&parent)
+        };
+        self.font_size = Length::Pt(font_size);
+        let ctx = LengthContext {
+            font_size,
+            ..ctx.clone()
+        };
+        let abs = |l: &Length| Length::Pt(l.resolve(&ctx));
+        for sides in [&mut self.margin, &mut self.padding, &mut self.border_width] {
+            sides.top = abs(&sides.top);
+            sides.right = abs(&sides.right);
+            sides.bottom = abs(&sides.bottom);
+            sides.left = abs(&sides.left);
+        }
+        // Une bordure sans style n'a pas d'épaisseur
+        let width = &mut self.border_width;
+        for (w, style) in [
+            (&mut width.top, self.border_style.top),
+            (&mut width.right, self.border_style.right),
+            (&mut width.bottom, self.border_style.bottom),
+            (&mut width.left, self.border_style.left),
+        ] {
+            if !style.is_visible() {
+                *w = Length::Pt(0.0);
+            }
+        }
+        // Les rayons en % portent sur la boîte elle-même : résolus au rendu
+        let radius = &mut self.border_radius;
+        for r in [
+            &mut radius.top_left,
+            &mut radius.top_right,
+            &mut radius.bottom_right,
+            &mut radius.bottom_left,
+        ] {
+            for l in [&mut r.horizontal, &mut r.vertical] {
+                if !l.has_percent() {
+                    *l = abs(l);
+                }
+            }
+        }
+        // Même chose pour les fonds, dont les pourcentages dépendent de la zone de peinture
+        for l in self.background_lengths_mut() {
+            if !l.has_percent() {
+                *l = abs(l);
+            }
+        }
+        for shadows in [&mut self.box_shadow, &mut self.text_shadow] {
+            for shadow in shadows.iter_mut().flatten() {
+                for l in [
+                    &mut shadow.offset_x,
+                    &mut shadow.offset_y,
+                    &mut shadow.blur,
+                    &mut shadow.spread,
+                ] {
+                    *l = abs(l);
+                }
+            }
+        }
+        // Les translations en % portent sur la boîte elle-même, la position d'une image
+        // sur l'espace libre autour d'elle : résolues au rendu
+        let translations = self.transform.iter_mut().flatten().filter_map(|f| match f {
+            TransformFunction::Translate(x, y) => Some([x, y]),
+            _ => None,
+        });
+        let origins = self.transform_origin.iter_mut().chain(&mut self.object_position);
+        for l in translations
+            .flatten()
+            .chain(origins.flat_map(|o| [&mut o.x, &mut o.y]))
+        {
+            if !l.has_percent() {
+                *l = abs(l);
+            }
+        }
+        for l in [
+            &mut self.text_indent,
+            &mut self.letter_spacing,
+            &mut self.word_spacing,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *l = abs(l);
+        }
+        if let Some(LineHeight::Length(l)) = &mut self.line_height {
+            *l = abs(l);
+        }
+        // Un pourcentage porte sur la hauteur de ligne de l'élément
+        let line_height = self.line_height.clone().unwrap_or_default().to_pt(font_size);
+        if let Some(VerticalAlign::Length(l)) = &mut self.vertical_align {
+            *l = Length::Pt(l.resolve(&LengthContext {
+                containing_width: line_height,
+                ..ctx.clone()
+            }));
+        }
+        // Une épaisseur en % porte sur la taille de police
+        let em = LengthContext {
+            containing_width: font_size,
+            ..ctx.clone()
+        };
+        let decorations = self.text_decorations.iter_mut();
+        for thickness in decorations.filter_map(|d| d.thickness.as_mut()) {
+            *thickness = Length::Pt(thickness.resolve(&em));
+        }
+        self.width = self.width.as_ref().map(abs);
+        // Les pourcentages verticaux portent sur la hauteur du bloc conteneur
+        let vertical = |l: &Length| match ctx.containing_height {
+            Some(h) => Some(Length::Pt(l.resolve(&LengthContext {
+                containing_width: h,
+                ..ctx.clone()
+            }))),
+            None if l.has_percent() => None,
+            None => Some(abs(l)),
+        };
+        self.height = self.height.as_ref().and_then(vertical);
+        self.top = self.top.as_ref().map(|l| vertical(l).unwrap_or_default());
+        self.left = self.left.as_ref().map(abs);
+    }
+}
+
+impl Style {
+    fn background_lengths_mut(&mut self) -> Vec<&mut Length> {
+        let mut lengths = Vec::new();
+        for position in &mut self.background_position {
+            lengths.extend([&mut position.x, &mut position.y]);
+        }
+        for size in &mut self.background_size {
+            if let BackgroundSize::Explicit(w, h) = size {
+                lengths.extend(w.iter_mut().chain(h.iter_mut()));
+            }
+        }
+        for image in &mut self.background_image {
+            let stops = match image {
+                BackgroundImage::LinearGradient(g) => &mut g.stops,
+                BackgroundImage::RadialGradient(g) => {
+                    lengths.extend([&mut g.position.x, &mut g.position.y]);
+                    if let RadialSize::Explicit(rx, ry) = &mut g.size {
+                        lengths.extend([rx, ry]);
+                    }
+                    &mut g.stops
+                }
+                _ => continue,
+            };
+            lengths.extend(stops.iter_mut().filter_map(|s| s.position.as_mut()));
+        }
+        lengths
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -57,8 +779,18 @@ pub struct Color {
 
 impl Default for Color {
     fn default() -> Self {
-        Color { r: 0, g: 0, b: 0, a: 1.0 }
+        Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 1.0,
+        }
     }
 }
 
-pub type Stylesheet = HashMap<String, Style>;
\ No newline at end of file
+// Styles par sélecteur et polices déclarées par `@font-face`
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    pub rules: HashMap<String, Style>,
+    pub font_faces: Vec<FontFace>,
+}
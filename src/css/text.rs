@@ -0,0 +1,451 @@
+use super::color::parse_color;
+use super::parser::{parse_length, parse_number, split_commas, split_components};
+use super::rules::Declaration;
+use super::styles::{
+    DecorationLine, DecorationStyle, Direction, FeatureSetting, FontFace, FontFamily, FontKerning,
+    FontSource, FontStyle, FontWeight, Hyphens, Length, LineHeight, Style, TextAlign,
+    TextDecoration, UnicodeBidi, VariantLigatures, VerticalAlign,
+};
+
+// `text-decoration` et ses sous-propriétés
+pub fn apply_text_decoration(property: &str, value: &str, style: &mut Style) {
+    let decoration = &mut style.text_decoration;
+    match property {
+        "text-decoration" => {
+            if let Some(parsed) = parse_shorthand(value) {
+                *decoration = parsed;
+            }
+        }
+        "text-decoration-line" => {
+            if let Some(line) = parse_line(&split_components(value)) {
+                decoration.line = Some(line);
+            }
+        }
+        "text-decoration-style" => {
+            if let Some(s) = parse_decoration_style(value) {
+                decoration.style = Some(s);
+            }
+        }
+        "text-decoration-color" => {
+            if let Some(color) = parse_color(value) {
+                decoration.color = Some(color);
+            }
+        }
+        "text-decoration-thickness" => {
+            if let Some(thickness) = parse_thickness(value) {
+                decoration.thickness = thickness;
+            }
+        }
+        _ => {}
+    }
+}
+
+// Noms entre guillemets, ou suites d'identifiants réunies par une espace
+pub fn parse_font_family(value: &str) -> Option<Vec<FontFamily>> {
+    let mut families = Vec::new();
+    for item in split_commas(value) {
+        let item = item.trim();
+        let quoted = ['"', '\'']
+            .iter()
+            .find_map(|q| item.strip_prefix(*q).and_then(|rest| rest.strip_suffix(*q)));
+        if let Some(name) = quoted {
+            families.push(FontFamily::Named(name.to_string()));
+            continue;
+        }
+        let words = split_components(item);
+        if words.is_empty() || words.iter().any(|w| w.contains(['"', '\'', '(', ')'])) {
+            return None;
+        }
+        let family = match words.as_slice() {
+            [word] => match word.to_ascii_lowercase().as_str() {
+                "serif" => FontFamily::Serif,
+                "sans-serif" => FontFamily::SansSerif,
+                "monospace" => FontFamily::Monospace,
+                "cursive" => FontFamily::Cursive,
+                "fantasy" => FontFamily::Fantasy,
+                "system-ui" => FontFamily::SystemUi,
+                _ => FontFamily::Named(word.clone()),
+            },
+            _ => FontFamily::Named(words.join(" ")),
+        };
+        families.push(family);
+    }
+    Some(families)
+}
+
+pub fn parse_font_weight(value: &str) -> Option<FontWeight> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "normal" => FontWeight::Weight(400),
+        "bold" => FontWeight::Weight(700),
+        "bolder" => FontWeight::Bolder,
+        "lighter" => FontWeight::Lighter,
+        v => {
+            let weight = parse_number(v).filter(|_| !v.ends_with('%'))?;
+            if !(1.0..=1000.0).contains(&weight) {
+                return None;
+            }
+            FontWeight::Weight(weight.round() as u16)
+        }
+    })
+}
+
+// L'angle de `oblique <angle>` est ignoré
+pub fn parse_font_style(value: &str) -> Option<FontStyle> {
+    let parts = split_components(&value.to_ascii_lowercase());
+    match parts.first().map(|s| s.as_str()) {
+        Some("normal") if parts.len() == 1 => Some(FontStyle::Normal),
+        Some("italic") if parts.len() == 1 => Some(FontStyle::Italic),
+        Some("oblique") if parts.len() <= 2 => Some(FontStyle::Oblique),
+        _ => None,
+    }
+}
+
+pub fn parse_font_kerning(value: &str) -> Option<FontKerning> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "auto" => FontKerning::Auto,
+        "normal" => FontKerning::Normal,
+        "none" => FontKerning::None,
+        _ => return None,
+    })
+}
+
+// `normal`, `none` ou des mots-clés distincts, chacun au plus une fois
+pub fn parse_variant_ligatures(value: &str) -> Option<VariantLigatures> {
+    let value = value.trim().to_ascii_lowercase();
+    match value.as_str() {
+        "normal" => return Some(VariantLigatures::default()),
+        "none" => {
+            return Some(VariantLigatures {
+                common: false,
+                discretionary: false,
+                historical: false,
+                contextual: false,
+            });
+        }
+        _ => {}
+    }
+    let mut ligatures = VariantLigatures::default();
+    let mut seen = Vec::new();
+    for part in split_components(&value) {
+        let keyword = part.strip_prefix("no-").unwrap_or(&part).to_string();
+        let slot = match keyword.as_str() {
+            "common-ligatures" => &mut ligatures.common,
+            "discretionary-ligatures" => &mut ligatures.discretionary,
+            "historical-ligatures" => &mut ligatures.historical,
+            "contextual" => &mut ligatures.contextual,
+            _ => return None,
+        };
+        *slot = !part.starts_with("no-");
+        if seen.contains(&keyword) {
+            return None;
+        }
+        seen.push(keyword);
+    }
+    Some(ligatures)
+}
+
+// `normal` ou une liste `"liga" 0, "smcp", "ss01" on` ; la valeur vaut 1 par défaut
+pub fn parse_feature_settings(value: &str) -> Option<Vec<FeatureSetting>> {
+    if value.trim().eq_ignore_ascii_case("normal") {
+        return Some(Vec::new());
+    }
+    let mut settings = Vec::new();
+    for setting in split_commas(value) {
+        let parts = split_components(&setting);
+        let tag = parts.first()?;
+        let tag = tag
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+            .or_else(|| tag.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))?;
+        let tag: [u8; 4] = tag.as_bytes().try_into().ok()?;
+        if !tag.iter().all(|b| (0x20..=0x7E).contains(b)) {
+            return None;
+        }
+        let value = match parts.get(1).map(|v| v.to_ascii_lowercase()).as_deref() {
+            None | Some("on") => 1,
+            Some("off") => 0,
+            Some(v) => v.parse().ok()?,
+        };
+        if parts.len() > 2 {
+            return None;
+        }
+        settings.push(FeatureSetting { tag, value });
+    }
+    Some(settings)
+}
+
+// Descripteurs d'une règle `@font-face` ; sans famille ni source utilisable, la règle
+// est ignorée
+pub fn parse_font_face(declarations: &[Declaration]) -> Option<FontFace> {
+    let mut face = FontFace {
+        family: String::new(),
+        sources: Vec::new(),
+        weight: (400, 400),
+        italic: false,
+        unicode_range: Vec::new(),
+    };
+    for declaration in declarations {
+        let value = declaration.value.as_str();
+        match declaration.name.as_str() {
+            "font-family" => {
+                if let Some([FontFamily::Named(name)]) = parse_font_family(value).as_deref() {
+                    face.family = name.clone();
+                }
+            }
+            "src" => face.sources = parse_font_sources(value),
+            "font-weight" => {
+                let weights: Option<Vec<u16>> = split_components(value)
+                    .iter()
+                    .map(|w| match parse_font_weight(w) {
+                        Some(FontWeight::Weight(w)) => Some(w),
+                        _ => None,
+                    })
+                    .collect();
+                match weights.as_deref() {
+                    Some([w]) => face.weight = (*w, *w),
+                    Some([a, b]) => face.weight = (*a.min(b), *a.max(b)),
+                    _ => {}
+                }
+            }
+            "font-style" => {
+                // Une plage d'angles obliques compte comme un seul style
+                let first = split_components(value)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default();
+                if let Some(style) = parse_font_style(&first) {
+                    face.italic = style != FontStyle::Normal;
+                }
+            }
+            "unicode-range" => {
+                if let Some(ranges) = parse_unicode_range(value) {
+                    face.unicode_range = ranges;
+                }
+            }
+            _ => {}
+        }
+    }
+    (!face.family.is_empty() && !face.sources.is_empty()).then_some(face)
+}
+
+// `url(...) format(...)` ou `local(...)` ; les formats que l'on ne sait pas lire sont écartés
+fn parse_font_sources(value: &str) -> Vec<FontSource> {
+    let mut sources = Vec::new();
+    for item in split_commas(value) {
+        let parts = split_components(&item);
+        let Some((name, args)) = parts.first().and_then(|p| p.split_once('(')) else {
+            continue;
+        };
+        let Some(args) = args.strip_suffix(')') else {
+            continue;
+        };
+        let arg = args.trim().trim_matches(['"', '\''].as_ref()).to_string();
+        let supported = parts[1..].iter().all(|hint| {
+            let hint = hint.to_ascii_lowercase();
+            match hint
+                .strip_prefix("format(")
+                .and_then(|h| h.strip_suffix(')'))
+            {
+                Some(format) => matches!(
+                    format.trim().trim_matches(['"', '\''].as_ref()),
+                    "truetype" | "opentype" | "woff" | "woff2" | "collection"
+                ),
+                None => true,
+            }
+        });
+        match name.trim().to_ascii_lowercase().as_str() {
+            "url" if supported && !arg.is_empty() => sources.push(FontSource::Url(arg)),
+            "local" if !arg.is_empty() => sources.push(FontSource::Local(arg)),
+            _ => {}
+        }
+    }
+    sources
+}
+
+// `U+0-7F`, `U+0025-00FF`, `U+4??` ; bornes incluses
+pub fn parse_unicode_range(value: &str) -> Option<Vec<(u32, u32)>> {
+    let mut ranges = Vec::new();
+    for item in split_commas(value) {
+        let item = item.strip_prefix(['U', 'u'])?.strip_prefix('+')?;
+        let (start, end) = match item.split_once('-') {
+            Some((a, b)) => (
+                u32::from_str_radix(a, 16).ok()?,
+                u32::from_str_radix(b, 16).ok()?,
+            ),
+            None if item.contains('?') => (
+                u32::from_str_radix(&item.replace('?', "0"), 16).ok()?,
+                u32::from_str_radix(&item.replace('?', "F"), 16).ok()?,
+            ),
+            None => {
+                let code = u32::from_str_radix(item, 16).ok()?;
+                (code, code)
+            }
+        };
+        if start > end || end > 0x10FFFF {
+            return None;
+        }
+        ranges.push((start, end));
+    }
+    Some(ranges)
+}
+
+// Sous-propriétés dans un ordre libre ; celles qui manquent reprennent leur valeur initiale
+fn parse_shorthand(value: &str) -> Option<TextDecoration> {
+    let mut lines = Vec::new();
+    let mut decoration_style = None;
+    let mut color = None;
+    let mut thickness = None;
+    for part in split_components(value) {
+        if parse_line(std::slice::from_ref(&part)).is_some() {
+            lines.push(part);
+        } else if decoration_style.is_none()
+            && let Some(s) = parse_decoration_style(&part)
+        {
+            decoration_style = Some(s);
+        } else if thickness.is_none()
+            && let Some(t) = parse_thickness(&part)
+        {
+            thickness = Some(t);
+        } else if color.is_none()
+            && let Some(c) = parse_color(&part)
+        {
+            color = Some(c);
+        } else {
+            return None;
+        }
+    }
+    let line = if lines.is_empty() {
+        DecorationLine::default()
+    } else {
+        parse_line(&lines)?
+    };
+    Some(TextDecoration {
+        line: Some(line),
+        style: Some(decoration_style.unwrap_or_default()),
+        color,
+        thickness: thickness.flatten(),
+    })
+}
+
+fn parse_line(parts: &[String]) -> Option<DecorationLine> {
+    let mut line = DecorationLine::default();
+    if let [part] = parts
+        && part.eq_ignore_ascii_case("none")
+    {
+        return Some(line);
+    }
+    for part in parts {
+        let flag = match part.to_ascii_lowercase().as_str() {
+            "underline" => &mut line.underline,
+            "overline" => &mut line.overline,
+            "line-through" => &mut line.line_through,
+            // Le clignotement n'a pas de sens sur papier
+            "blink" => continue,
+            _ => return None,
+        };
+        if *flag {
+            return None;
+        }
+        *flag = true;
+    }
+    (!parts.is_empty()).then_some(line)
+}
+
+fn parse_decoration_style(value: &str) -> Option<DecorationStyle> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "solid" => DecorationStyle::Solid,
+        "double" => DecorationStyle::Double,
+        "dotted" => DecorationStyle::Dotted,
+        "dashed" => DecorationStyle::Dashed,
+        "wavy" => DecorationStyle::Wavy,
+        _ => return None,
+    })
+}
+
+// Some(None) : `auto` ou `from-font`
+fn parse_thickness(value: &str) -> Option<Option<Length>> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "auto" | "from-font" => Some(None),
+        _ => parse_length(value).map(Some),
+    }
+}
+
+pub fn parse_text_align(value: &str) -> Option<TextAlign> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "start" => TextAlign::Start,
+        "end" => TextAlign::End,
+        "left" => TextAlign::Left,
+        "right" => TextAlign::Right,
+        "center" => TextAlign::Center,
+        "justify" => TextAlign::Justify,
+        _ => return None,
+    })
+}
+
+// `letter-spacing` et `word-spacing` ; `normal` ne change rien
+pub fn parse_spacing(value: &str) -> Option<Length> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "normal" => Some(Length::Px(0.0)),
+        _ => parse_length(value),
+    }
+}
+
+// Un pourcentage devient un multiple de la taille de police, calculé sur l'élément
+pub fn parse_line_height(value: &str) -> Option<LineHeight> {
+    let value = value.trim().to_ascii_lowercase();
+    if value == "normal" {
+        return Some(LineHeight::Normal);
+    }
+    if let Some(percent) = value.strip_suffix('%') {
+        let p = parse_number(percent)?;
+        return (p >= 0.0).then_some(LineHeight::Length(Length::Em(p / 100.0)));
+    }
+    match parse_number(&value) {
+        Some(n) if n >= 0.0 => Some(LineHeight::Number(n)),
+        Some(_) => None,
+        None => parse_length(&value).map(LineHeight::Length),
+    }
+}
+
+pub fn parse_vertical_align(value: &str) -> Option<VerticalAlign> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "baseline" => VerticalAlign::Baseline,
+        "sub" => VerticalAlign::Sub,
+        "super" => VerticalAlign::Super,
+        "text-top" => VerticalAlign::TextTop,
+        "text-bottom" => VerticalAlign::TextBottom,
+        "middle" => VerticalAlign::Middle,
+        "top" => VerticalAlign::Top,
+        "bottom" => VerticalAlign::Bottom,
+        _ => VerticalAlign::Length(parse_length(value)?),
+    })
+}
+
+pub fn parse_hyphens(value: &str) -> Option<Hyphens> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "none" => Hyphens::None,
+        "manual" => Hyphens::Manual,
+        "auto" => Hyphens::Auto,
+        _ => return None,
+    })
+}
+
+pub fn parse_direction(value: &str) -> Option<Direction> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "ltr" => Some(Direction::Ltr),
+        "rtl" => Some(Direction::Rtl),
+        _ => None,
+    }
+}
+
+pub fn parse_unicode_bidi(value: &str) -> Option<UnicodeBidi> {
+    Some(match value.trim().to_ascii_lowercase().as_str() {
+        "normal" => UnicodeBidi::Normal,
+        "embed" => UnicodeBidi::Embed,
+        "isolate" => UnicodeBidi::Isolate,
+        "bidi-override" => UnicodeBidi::BidiOverride,
+        "isolate-override" => UnicodeBidi::IsolateOverride,
+        "plaintext" => UnicodeBidi::Plaintext,
+        _ => return None,
+    })
+}
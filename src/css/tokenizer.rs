@@ -0,0 +1,376 @@
+use std::ops::Range;
+
+// Tokens définis par CSS Syntax Level 3 (§4)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Ident(String),
+    Function(String),
+    AtKeyword(String),
+    Hash(String),
+    String(String),
+    BadString,
+    Url(String),
+    BadUrl,
+    Delim(char),
+    Number(f32),
+    Percentage(f32),
+    Dimension(f32, String),
+    Whitespace,
+    Cdo,
+    Cdc,
+    Colon,
+    Semicolon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenParen,
+    CloseParen,
+    OpenCurly,
+    CloseCurly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub span: Range<usize>,
+}
+
+pub fn tokenize(css: &str) -> Vec<Spanned> {
+    let mut tokenizer = Tokenizer { input: css, pos: 0 };
+    let mut tokens = Vec::new();
+    while let Some(t) = tokenizer.next_token() {
+        tokens.push(t);
+    }
+    tokens
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Tokenizer<'_> {
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.input[self.pos..].chars().nth(n)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn next_token(&mut self) -> Option<Spanned> {
+        loop {
+            if self.input[self.pos..].starts_with("/*") {
+                match self.input[self.pos + 2..].find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => self.pos = self.input.len(),
+                }
+                continue;
+            }
+            break;
+        }
+
+        let start = self.pos;
+        let c = self.bump()?;
+        let token = match c {
+            c if is_whitespace(c) => {
+                while self.peek().is_some_and(is_whitespace) {
+                    self.bump();
+                }
+                Token::Whitespace
+            }
+            '"' | '\'' => self.consume_string(c),
+            '#' => {
+                if self.peek().is_some_and(is_name_char) || self.starts_escape(0) {
+                    Token::Hash(self.consume_name())
+                } else {
+                    Token::Delim('#')
+                }
+            }
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '[' => Token::OpenSquare,
+            ']' => Token::CloseSquare,
+            '{' => Token::OpenCurly,
+            '}' => Token::CloseCurly,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            '+' | '.' => {
+                self.pos = start;
+                if self.number_starts_at(0) {
+                    self.consume_numeric()
+                } else {
+                    self.bump();
+                    Token::Delim(c)
+                }
+            }
+            '-' => {
+                self.pos = start;
+                if self.number_starts_at(0) {
+                    self.consume_numeric()
+                } else if self.input[self.pos..].starts_with("-->") {
+                    self.pos += 3;
+                    Token::Cdc
+                } else if self.ident_starts_at(0) {
+                    self.consume_ident_like()
+                } else {
+                    self.bump();
+                    Token::Delim('-')
+                }
+            }
+            '<' => {
+                if self.input[self.pos..].starts_with("!--") {
+                    self.pos += 3;
+                    Token::Cdo
+                } else {
+                    Token::Delim('<')
+                }
+            }
+            '@' => {
+                if self.ident_starts_at(0) {
+                    Token::AtKeyword(self.consume_name())
+                } else {
+                    Token::Delim('@')
+                }
+            }
+            '\\' => {
+                self.pos = start;
+                if self.starts_escape(0) {
+                    self.consume_ident_like()
+                } else {
+                    self.bump();
+                    Token::Delim('\\')
+                }
+            }
+            c if c.is_ascii_digit() => {
+                self.pos = start;
+                self.consume_numeric()
+            }
+            c if is_name_start(c) => {
+                self.pos = start;
+                self.consume_ident_like()
+            }
+            c => Token::Delim(c),
+        };
+        Some(Spanned {
+            token,
+            span: start..self.pos,
+        })
+    }
+
+    fn starts_escape(&self, offset: usize) -> bool {
+        self.peek_at(offset) == Some('\\') && self.peek_at(offset + 1).is_some_and(|c| c != '\n')
+    }
+
+    fn number_starts_at(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some('+') | Some('-') => match self.peek_at(offset + 1) {
+                Some(c) if c.is_ascii_digit() => true,
+                Some('.') => self.peek_at(offset + 2).is_some_and(|c| c.is_ascii_digit()),
+                _ => false,
+            },
+            Some('.') => self.peek_at(offset + 1).is_some_and(|c| c.is_ascii_digit()),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    fn ident_starts_at(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some('-') => match self.peek_at(offset + 1) {
+                Some(c) if is_name_start(c) || c == '-' => true,
+                Some('\\') => self.starts_escape(offset + 1),
+                _ => false,
+            },
+            Some('\\') => self.starts_escape(offset),
+            Some(c) => is_name_start(c),
+            None => false,
+        }
+    }
+
+    fn consume_escape(&mut self) -> char {
+        let mut hex = String::new();
+        while hex.len() < 6 && self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            hex.push(self.bump().unwrap_or_default());
+        }
+        if hex.is_empty() {
+            return self.bump().unwrap_or('\u{FFFD}');
+        }
+        if self.peek().is_some_and(is_whitespace) {
+            self.bump();
+        }
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .filter(|&v| v != 0)
+            .and_then(char::from_u32)
+            .unwrap_or('\u{FFFD}')
+    }
+
+    fn consume_name(&mut self) -> String {
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some(c) if is_name_char(c) => {
+                    name.push(c);
+                    self.bump();
+                }
+                Some('\\') if self.starts_escape(0) => {
+                    self.bump();
+                    name.push(self.consume_escape());
+                }
+                _ => return name,
+            }
+        }
+    }
+
+    fn consume_string(&mut self, quote: char) -> Token {
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Token::String(value),
+                Some(c) if c == quote => {
+                    self.bump();
+                    return Token::String(value);
+                }
+                Some('\n') => return Token::BadString,
+                Some('\\') => {
+                    self.bump();
+                    match self.peek() {
+                        None => {}
+                        Some('\n') => {
+                            self.bump();
+                        }
+                        Some(_) => value.push(self.consume_escape()),
+                    }
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn consume_number(&mut self) -> f32 {
+        let start = self.pos;
+        if matches!(self.peek(), Some('+') | Some('-')) {
+            self.bump();
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        if self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+        if matches!(self.peek(), Some('e') | Some('E')) {
+            let digit_at = if matches!(self.peek_at(1), Some('+') | Some('-')) {
+                2
+            } else {
+                1
+            };
+            if self.peek_at(digit_at).is_some_and(|c| c.is_ascii_digit()) {
+                for _ in 0..digit_at {
+                    self.bump();
+                }
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.bump();
+                }
+            }
+        }
+        self.input[start..self.pos].parse().unwrap_or(0.0)
+    }
+
+    fn consume_numeric(&mut self) -> Token {
+        let value = self.consume_number();
+        if self.peek() == Some('%') {
+            self.bump();
+            return Token::Percentage(value);
+        }
+        if self.ident_starts_at(0) {
+            return Token::Dimension(value, self.consume_name());
+        }
+        Token::Number(value)
+    }
+
+    fn consume_ident_like(&mut self) -> Token {
+        let name = self.consume_name();
+        if self.peek() != Some('(') {
+            return Token::Ident(name);
+        }
+        self.bump();
+        if !name.eq_ignore_ascii_case("url") {
+            return Token::Function(name);
+        }
+        let save = self.pos;
+        while self.peek().is_some_and(is_whitespace) {
+            self.bump();
+        }
+        if matches!(self.peek(), Some('"') | Some('\'')) {
+            // url("...") reste une fonction dont l'argument est une chaîne
+            self.pos = save;
+            return Token::Function(name);
+        }
+        self.consume_url()
+    }
+
+    fn consume_url(&mut self) -> Token {
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None | Some(')') => return Token::Url(value),
+                Some(c) if is_whitespace(c) => {
+                    while self.peek().is_some_and(is_whitespace) {
+                        self.bump();
+                    }
+                    return match self.bump() {
+                        None | Some(')') => Token::Url(value),
+                        Some(_) => self.consume_bad_url(),
+                    };
+                }
+                Some('"') | Some('\'') | Some('(') => return self.consume_bad_url(),
+                Some('\\') => {
+                    if self.peek().is_some_and(|c| c != '\n') {
+                        value.push(self.consume_escape());
+                    } else {
+                        return self.consume_bad_url();
+                    }
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn consume_bad_url(&mut self) -> Token {
+        loop {
+            match self.bump() {
+                None | Some(')') => return Token::BadUrl,
+                Some('\\') => {
+                    self.bump();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
@@ -0,0 +1,56 @@
+use super::background::parse_position;
+use super::parser::{parse_angle, parse_length, parse_number, split_commas, split_components};
+use super::styles::{BackgroundPosition, Length, TransformFunction};
+
+// `none` ou liste de fonctions 2D, appliquées de droite à gauche
+pub fn parse_transform(value: &str) -> Option<Vec<TransformFunction>> {
+    if value.trim().eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+    split_components(value)
+        .iter()
+        .map(|f| parse_function(f))
+        .collect()
+}
+
+fn parse_function(value: &str) -> Option<TransformFunction> {
+    let (name, args) = value.split_once('(')?;
+    let args = split_commas(args.strip_suffix(')')?);
+    let length = |i: usize| args.get(i).and_then(|a| parse_length(a));
+    let angle = |i: usize| args.get(i).and_then(|a| parse_angle(a));
+    let number = |i: usize| args.get(i).and_then(|a| parse_number(a));
+    let zero = Length::Px(0.0);
+    let function = match (name.trim().to_ascii_lowercase().as_str(), args.len()) {
+        ("translate", 1) => TransformFunction::Translate(length(0)?, zero),
+        ("translate", 2) => TransformFunction::Translate(length(0)?, length(1)?),
+        ("translatex", 1) => TransformFunction::Translate(length(0)?, zero),
+        ("translatey", 1) => TransformFunction::Translate(zero, length(0)?),
+        ("scale", 1) => TransformFunction::Scale(number(0)?, number(0)?),
+        ("scale", 2) => TransformFunction::Scale(number(0)?, number(1)?),
+        ("scalex", 1) => TransformFunction::Scale(number(0)?, 1.0),
+        ("scaley", 1) => TransformFunction::Scale(1.0, number(0)?),
+        ("rotate", 1) => TransformFunction::Rotate(angle(0)?),
+        ("skew", 1) => TransformFunction::Skew(angle(0)?, 0.0),
+        ("skew", 2) => TransformFunction::Skew(angle(0)?, angle(1)?),
+        ("skewx", 1) => TransformFunction::Skew(angle(0)?, 0.0),
+        ("skewy", 1) => TransformFunction::Skew(0.0, angle(0)?),
+        ("matrix", 6) => {
+            let mut m = [0.0; 6];
+            for (i, v) in m.iter_mut().enumerate() {
+                *v = number(i)?;
+            }
+            TransformFunction::Matrix(m)
+        }
+        _ => return None,
+    };
+    Some(function)
+}
+
+// `transform-origin: x y [z]` ; la profondeur est ignorée
+pub fn parse_transform_origin(value: &str) -> Option<BackgroundPosition> {
+    let parts = split_components(value);
+    match parts.as_slice() {
+        [x, y, z] if parse_length(z).is_some() => parse_position(&[x.clone(), y.clone()]),
+        _ => parse_position(&parts),
+    }
+}
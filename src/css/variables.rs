@@ -0,0 +1,95 @@
+use super::tokenizer::{Spanned, Token, tokenize};
+use std::collections::HashMap;
+
+// Profondeur maximale de substitution, au-delà on suppose un cycle
+const MAX_DEPTH: usize = 16;
+// Longueur maximale d'une valeur substituée : des références doublées à chaque niveau
+// grandiraient sinon de façon exponentielle
+const MAX_LENGTH: usize = 1 << 16;
+
+pub fn contains_var(value: &str) -> bool {
+    tokenize(value)
+        .iter()
+        .any(|t| matches!(&t.token, Token::Function(name) if name.eq_ignore_ascii_case("var")))
+}
+
+// Remplace chaque var(--nom, repli) ; None si une variable est indéfinie et sans repli
+pub fn substitute_vars(value: &str, properties: &HashMap<String, String>) -> Option<String> {
+    substitute(value, properties, 0)
+}
+
+fn substitute(value: &str, properties: &HashMap<String, String>, depth: usize) -> Option<String> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let tokens = tokenize(value);
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_var =
+            matches!(&tokens[i].token, Token::Function(n) if n.eq_ignore_ascii_case("var"));
+        if !is_var {
+            i += 1;
+            continue;
+        }
+        let close = matching_paren(&tokens, i)?;
+        out.push_str(&value[copied..tokens[i].span.start]);
+        let args = &tokens[i + 1..close];
+        out.push_str(&substitute_one(value, args, properties, depth)?);
+        if out.len() > MAX_LENGTH {
+            return None;
+        }
+        copied = tokens.get(close).map_or(value.len(), |t| t.span.end);
+        i = close + 1;
+    }
+    out.push_str(&value[copied..]);
+    (out.len() <= MAX_LENGTH).then_some(out)
+}
+
+fn substitute_one(
+    source: &str,
+    args: &[Spanned],
+    properties: &HashMap<String, String>,
+    depth: usize,
+) -> Option<String> {
+    let mut it = args.iter().filter(|t| t.token != Token::Whitespace);
+    let name = match &it.next()?.token {
+        Token::Ident(name) if name.starts_with("--") => name.clone(),
+        _ => return None,
+    };
+    let fallback = match it.next() {
+        None => None,
+        Some(comma) if comma.token == Token::Comma => {
+            let end = args.last().map_or(comma.span.end, |t| t.span.end);
+            Some(source[comma.span.end..end].trim().to_string())
+        }
+        Some(_) => return None,
+    };
+    match properties.get(&name) {
+        Some(value) => substitute(value, properties, depth + 1).or_else(|| {
+            fallback
+                .as_deref()
+                .and_then(|f| substitute(f, properties, depth + 1))
+        }),
+        None => substitute(&fallback?, properties, depth + 1),
+    }
+}
+
+// Index du `)` fermant la fonction ouverte en `open` (ou fin des tokens)
+fn matching_paren(tokens: &[Spanned], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate().skip(open) {
+        match t.token {
+            Token::Function(_) | Token::OpenParen => depth += 1,
+            Token::CloseParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(tokens.len())
+}
@@ -49,7 +49,7 @@ pub fn parse_html(html: &str) -> NodeRef {
                 if chars.peek() == Some(&'/') {
                     chars.next();
                     let mut tag = String::new();
-                    while let Some(ch) = chars.next() {
+                    for ch in chars.by_ref() {
                         if ch == '>' {
                             break;
                         }
@@ -81,16 +81,17 @@ pub fn parse_html(html: &str) -> NodeRef {
     root
 }
 
+// Les blancs sont conservés : la mise en ligne les fusionne
 fn flush_text_buffer(buffer: &mut String, parent: Option<&NodeRef>) {
-    let text = decode_entities(buffer.trim());
-    if !text.is_empty() {
-        if let Some(p) = parent {
-            p.borrow_mut().children.push(Rc::new(RefCell::new(Node {
-                node_type: NodeType::Text(text),
-                attributes: vec![],
-                children: vec![],
-            })));
-        }
+    let text = decode_entities(buffer);
+    if !text.is_empty()
+        && let Some(p) = parent
+    {
+        p.borrow_mut().children.push(Rc::new(RefCell::new(Node {
+            node_type: NodeType::Text(text),
+            attributes: vec![],
+            children: vec![],
+        })));
     }
     buffer.clear();
 }
@@ -167,7 +168,7 @@ where
                     if let Some(&quote) = chars.peek() {
                         if quote == '"' || quote == '\'' {
                             chars.next();
-                            while let Some(ch) = chars.next() {
+                            for ch in chars.by_ref() {
                                 if ch == quote {
                                     break;
                                 }
@@ -225,6 +226,7 @@ fn decode_entities(text: &str) -> String {
                 "quot" => "\"".to_string(),
                 "apos" => "'".to_string(),
                 "nbsp" => "\u{00A0}".to_string(),
+                "shy" => "\u{00AD}".to_string(),
                 "copy" => "©".to_string(),
                 "reg" => "®".to_string(),
                 "euro" => "€".to_string(),
@@ -1,19 +1,265 @@
-use image::ImageReader;
+use crate::resources::loader::ResourceLoader;
+use crate::svg::parser::svg_size;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use std::io::Cursor;
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Image {
     pub width: u32,
     pub height: u32,
-    pub data: Vec<u8>, // Données brutes (RGB)
+    pub color_space: ColorSpace,
+    pub bits_per_component: u8,
+    pub data: Vec<u8>, // échantillons entrelacés, poids fort d'abord en 16 bits ; JPEG tel quel
+    pub jpeg: bool,    // flux DCT recopié sans décodage
+    pub inverted: bool, // CMYK Adobe : composantes inversées
+    pub alpha: Option<Vec<u8>>, // opacité, même profondeur que les couleurs
+    pub orientation: u8, // EXIF, de 1 (telle quelle) à 8
+    pub icc: Option<Vec<u8>>, // profil ICC incorporé, de même espace que les couleurs
 }
 
-pub fn decode_image(path: &str) -> Option<Image> {
-    let reader = ImageReader::open(path).ok()?;
-    let img = reader.decode().ok()?;
-    let rgb = img.to_rgb8();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+}
+
+impl ColorSpace {
+    pub fn components(self) -> usize {
+        match self {
+            ColorSpace::Gray => 1,
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        }
+    }
+}
+
+pub fn decode_image(src: &str, loader: &dyn ResourceLoader) -> Option<Image> {
+    decode_image_data(&loader.load(src).ok()?)
+}
+
+// JPEG et TIFF CMYK gardent leurs encres ; le reste passe par `image` (PNG, GIF à sa
+// première image, WebP, BMP, TIFF), en gris ou RGB sur 8 ou 16 bits
+pub fn decode_image_data(data: &[u8]) -> Option<Image> {
+    let mut image = match jpeg(data).or_else(|| cmyk_tiff(data)) {
+        Some(image) => image,
+        None => from_dynamic(image::load_from_memory(data).ok()?),
+    };
+    image.orientation = exif_orientation(data);
+    image.icc = icc_profile(data).filter(|icc| {
+        let signature: &[u8] = match image.color_space {
+            ColorSpace::Gray => b"GRAY",
+            ColorSpace::Rgb => b"RGB ",
+            ColorSpace::Cmyk => b"CMYK",
+        };
+        icc.get(16..20) == Some(signature)
+    });
+    Some(image)
+}
+
+// Orientation EXIF (JPEG, PNG, WebP, TIFF) ; 1 sans métadonnées
+fn exif_orientation(data: &[u8]) -> u8 {
+    ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok())
+        .map_or(1, |orientation| orientation.to_exif())
+}
+
+// Profil ICC (iCCP d'un PNG, segments APP2 d'un JPEG, WebP, TIFF)
+fn icc_profile(data: &[u8]) -> Option<Vec<u8>> {
+    ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?
+        .icc_profile()
+        .ok()
+        .flatten()
+}
+
+pub(crate) fn from_dynamic(img: DynamicImage) -> Image {
+    let (width, height) = (img.width(), img.height());
+    let sixteen =
+        |samples: &[u16]| -> Vec<u8> { samples.iter().flat_map(|s| s.to_be_bytes()).collect() };
+    let (color_space, bits_per_component, data, alpha) = match img {
+        DynamicImage::ImageLuma8(buffer) => (ColorSpace::Gray, 8, buffer.into_raw(), None),
+        DynamicImage::ImageLumaA8(buffer) => {
+            let (gray, alpha) = split_alpha(buffer.as_raw(), 2, 1);
+            (ColorSpace::Gray, 8, gray, alpha)
+        }
+        DynamicImage::ImageRgb8(buffer) => (ColorSpace::Rgb, 8, buffer.into_raw(), None),
+        DynamicImage::ImageLuma16(buffer) => (ColorSpace::Gray, 16, sixteen(buffer.as_raw()), None),
+        DynamicImage::ImageLumaA16(buffer) => {
+            let (gray, alpha) = split_alpha(&sixteen(buffer.as_raw()), 4, 2);
+            (ColorSpace::Gray, 16, gray, alpha)
+        }
+        DynamicImage::ImageRgb16(buffer) => (ColorSpace::Rgb, 16, sixteen(buffer.as_raw()), None),
+        DynamicImage::ImageRgba16(buffer) => {
+            let (rgb, alpha) = split_alpha(&sixteen(buffer.as_raw()), 8, 2);
+            (ColorSpace::Rgb, 16, rgb, alpha)
+        }
+        // RGBA 8 bits et valeurs flottantes
+        img => {
+            let (rgb, alpha) = split_alpha(img.to_rgba8().as_raw(), 4, 1);
+            (ColorSpace::Rgb, 8, rgb, alpha)
+        }
+    };
+    Image {
+        width,
+        height,
+        color_space,
+        bits_per_component,
+        data,
+        jpeg: false,
+        inverted: false,
+        alpha,
+        orientation: 1,
+        icc: None,
+    }
+}
+
+// Sépare l'opacité (derniers `size` octets de chaque pixel) ; aucune si tout est opaque
+fn split_alpha(pixels: &[u8], pixel: usize, size: usize) -> (Vec<u8>, Option<Vec<u8>>) {
+    let mut colors = Vec::with_capacity(pixels.len() / pixel * (pixel - size));
+    let mut alpha = Vec::with_capacity(pixels.len() / pixel * size);
+    for chunk in pixels.chunks_exact(pixel) {
+        colors.extend(&chunk[..pixel - size]);
+        alpha.extend(&chunk[pixel - size..]);
+    }
+    let opaque = alpha.iter().all(|&a| a == 0xFF);
+    (colors, (!opaque).then_some(alpha))
+}
+
+// JPEG de base ou progressif sur 8 bits, recopié pour DCTDecode
+fn jpeg(data: &[u8]) -> Option<Image> {
+    let info = jpeg_info(data)?;
+    let color_space = match info.components {
+        1 => ColorSpace::Gray,
+        3 => ColorSpace::Rgb,
+        4 => ColorSpace::Cmyk,
+        _ => return None,
+    };
+    if !info.dct || info.precision != 8 || info.width == 0 || info.height == 0 {
+        return None;
+    }
     Some(Image {
-        width: rgb.width(),
-        height: rgb.height(),
-        data: rgb.into_raw(),
+        width: info.width,
+        height: info.height,
+        color_space,
+        bits_per_component: 8,
+        data: data.to_vec(),
+        jpeg: true,
+        // Photoshop et les logiciels Adobe écrivent le CMYK inversé
+        inverted: color_space == ColorSpace::Cmyk && info.adobe,
+        alpha: None,
+        orientation: 1,
+        icc: None,
     })
-}
\ No newline at end of file
+}
+
+struct JpegInfo {
+    width: u32,
+    height: u32,
+    components: u8,
+    precision: u8,
+    dct: bool, // codage pris en charge par DCTDecode
+    adobe: bool,
+}
+
+// Segments lus jusqu'à l'en-tête de trame (SOFn)
+fn jpeg_info(data: &[u8]) -> Option<JpegInfo> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut adobe = false;
+    let mut pos = 2;
+    loop {
+        while *data.get(pos)? != 0xFF {
+            pos += 1;
+        }
+        while *data.get(pos)? == 0xFF {
+            pos += 1;
+        }
+        let marker = *data.get(pos)?;
+        pos += 1;
+        if matches!(marker, 0x01 | 0xD0..=0xD7) {
+            continue;
+        }
+        let length = u16::from_be_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize;
+        let segment = data.get(pos + 2..pos + length)?;
+        match marker {
+            0xEE if segment.starts_with(b"Adobe") => adobe = true,
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return Some(JpegInfo {
+                    precision: *segment.first()?,
+                    height: u16::from_be_bytes([*segment.get(1)?, *segment.get(2)?]) as u32,
+                    width: u16::from_be_bytes([*segment.get(3)?, *segment.get(4)?]) as u32,
+                    components: *segment.get(5)?,
+                    dct: matches!(marker, 0xC0..=0xC2),
+                    adobe,
+                });
+            }
+            0xD9 | 0xDA => return None,
+            _ => {}
+        }
+        pos += length;
+    }
+}
+
+// TIFF séparé en CMYK (toutes bandes confondues) ; `image` le convertirait en RGB
+fn cmyk_tiff(data: &[u8]) -> Option<Image> {
+    if !data.starts_with(b"II*\0") && !data.starts_with(b"MM\0*") {
+        return None;
+    }
+    let mut decoder = TiffDecoder::new(Cursor::new(data)).ok()?;
+    if !matches!(decoder.colortype().ok()?, tiff::ColorType::CMYK(8 | 16)) {
+        return None;
+    }
+    let (width, height) = decoder.dimensions().ok()?;
+    let (bits_per_component, data) = match decoder.read_image().ok()? {
+        DecodingResult::U8(samples) => (8, samples),
+        DecodingResult::U16(samples) => {
+            (16, samples.iter().flat_map(|s| s.to_be_bytes()).collect())
+        }
+        _ => return None,
+    };
+    Some(Image {
+        width,
+        height,
+        color_space: ColorSpace::Cmyk,
+        bits_per_component,
+        data,
+        jpeg: false,
+        inverted: false,
+        alpha: None,
+        orientation: 1,
+        icc: None,
+    })
+}
+
+// Taille en pixels CSS, lue dans l'en-tête et tournée selon l'EXIF si `oriented` ;
+// un fichier SVG donne la sienne
+pub fn image_dimensions(
+    src: &str,
+    loader: &dyn ResourceLoader,
+    oriented: bool,
+) -> Option<(f32, f32)> {
+    let data = loader.load(src).ok()?;
+    let raster = match jpeg_info(&data) {
+        Some(info) if info.width > 0 && info.height > 0 => Ok((info.width, info.height)),
+        _ => ImageReader::new(Cursor::new(&data))
+            .with_guessed_format()
+            .ok()?
+            .into_dimensions(),
+    };
+    match raster {
+        Ok((width, height)) if oriented && exif_orientation(&data) >= 5 => {
+            Some((height as f32, width as f32))
+        }
+        Ok((width, height)) => Some((width as f32, height as f32)),
+        Err(_) => svg_size(&data),
+    }
+}
@@ -0,0 +1,182 @@
+use crate::image::decoder::{ColorSpace, Image, from_dynamic};
+use crate::pdf::images::deflate;
+use crate::pdf::options::ImageOptions;
+use image::ExtendedColorType;
+use image::codecs::jpeg::JpegEncoder;
+
+// Image allégée selon les options ; `drawn` est sa plus grande taille de dessin en points,
+// dans le sens des pixels (aucune si elle n'est pas connue). None : l'image reste telle quelle
+pub fn optimize_image(
+    image: &Image,
+    drawn: Option<(f32, f32)>,
+    options: &ImageOptions,
+) -> Option<Image> {
+    let target = match (options.max_dpi, drawn) {
+        (Some(dpi), Some((width, height))) => (
+            pixels(width, dpi).min(image.width),
+            pixels(height, dpi).min(image.height),
+        ),
+        _ => (image.width, image.height),
+    };
+    let resize = target != (image.width, image.height);
+    let gray = options.grayscale && image.color_space != ColorSpace::Gray;
+    if !resize && !gray && options.jpeg_quality.is_none() {
+        return None;
+    }
+    // `image` rend un JPEG CMYK en RGB : ses encres ne sont décodées que pour le gris
+    if image.jpeg && image.color_space == ColorSpace::Cmyk && !gray {
+        return None;
+    }
+    let mut raw = match image.jpeg {
+        true => Image {
+            orientation: image.orientation,
+            icc: image.icc.clone(),
+            ..from_dynamic(image::load_from_memory(&image.data).ok()?)
+        },
+        false => image.clone(),
+    };
+    if gray {
+        to_gray(&mut raw);
+    }
+    if resize {
+        resample(&mut raw, target);
+    }
+    // Comparé au flux compressé qui serait écrit à sa place
+    let mut encoded = false;
+    if let Some(quality) = options.jpeg_quality
+        && let Some(jpeg) = encode_jpeg(&raw, quality)
+        && jpeg.data.len() < deflate(&raw.data).len()
+    {
+        raw = jpeg;
+        encoded = true;
+    }
+    // Le profil ne vaut plus pour d'autres composantes
+    if raw.color_space != image.color_space {
+        raw.icc = None;
+    }
+    // Sans ré-encodage qui allège, l'image d'origine est gardée
+    let lighter = encoded && (!image.jpeg || raw.data.len() < image.data.len());
+    if !resize && !gray && !lighter {
+        return None;
+    }
+    Some(raw)
+}
+
+// Pixels nécessaires pour `points` à `dpi` ; une taille infinie n'impose rien
+fn pixels(points: f32, dpi: f32) -> u32 {
+    (points / 72.0 * dpi).ceil().max(1.0) as u32
+}
+
+fn samples(data: &[u8], bits: u8) -> Vec<u16> {
+    match bits {
+        16 => data
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect(),
+        _ => data.iter().map(|&s| s as u16).collect(),
+    }
+}
+
+fn to_bytes(samples: &[u16], bits: u8) -> Vec<u8> {
+    match bits {
+        16 => samples.iter().flat_map(|s| s.to_be_bytes()).collect(),
+        _ => samples.iter().map(|&s| s as u8).collect(),
+    }
+}
+
+// Luminance Rec. 601 ; le CMYK est d'abord ramené à des encres additionnées
+fn to_gray(image: &mut Image) {
+    let max = if image.bits_per_component == 16 {
+        0xFFFF
+    } else {
+        0xFF
+    };
+    let components = image.color_space.components();
+    let gray: Vec<u16> = samples(&image.data, image.bits_per_component)
+        .chunks_exact(components)
+        .map(|p| {
+            let [a, b, c] = [p[0], p[1], p[2]].map(|s| s as u32);
+            match image.color_space {
+                ColorSpace::Cmyk => max - ((30 * a + 59 * b + 11 * c) / 100 + p[3] as u32).min(max),
+                _ => (299 * a + 587 * b + 114 * c) / 1000,
+            }
+        })
+        .map(|s| s as u16)
+        .collect();
+    image.data = to_bytes(&gray, image.bits_per_component);
+    image.color_space = ColorSpace::Gray;
+}
+
+// Moyenne des pixels source couverts par chaque pixel cible
+fn resample(image: &mut Image, (width, height): (u32, u32)) {
+    let size = (image.width as usize, image.height as usize);
+    let target = (width as usize, height as usize);
+    let bits = image.bits_per_component;
+    let color = samples(&image.data, bits);
+    image.data = to_bytes(
+        &average(&color, size, image.color_space.components(), target),
+        bits,
+    );
+    if let Some(alpha) = &image.alpha {
+        image.alpha = Some(to_bytes(
+            &average(&samples(alpha, bits), size, 1, target),
+            bits,
+        ));
+    }
+    image.width = width;
+    image.height = height;
+}
+
+fn average(
+    samples: &[u16],
+    (width, height): (usize, usize),
+    components: usize,
+    (target_width, target_height): (usize, usize),
+) -> Vec<u16> {
+    let span = |i: usize, from: usize, to: usize| {
+        (i * from / to, ((i + 1) * from / to).max(i * from / to + 1))
+    };
+    let mut out = Vec::with_capacity(target_width * target_height * components);
+    for ty in 0..target_height {
+        let (y0, y1) = span(ty, height, target_height);
+        for tx in 0..target_width {
+            let (x0, x1) = span(tx, width, target_width);
+            let count = ((y1 - y0) * (x1 - x0)) as u64;
+            for c in 0..components {
+                let mut sum = 0u64;
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        sum += samples[(y * width + x) * components + c] as u64;
+                    }
+                }
+                out.push(((sum + count / 2) / count) as u16);
+            }
+        }
+    }
+    out
+}
+
+// JPEG en gris ou RGB sur 8 bits ; l'opacité reste un masque à part
+fn encode_jpeg(image: &Image, quality: u8) -> Option<Image> {
+    let color_type = match image.color_space {
+        ColorSpace::Gray => ExtendedColorType::L8,
+        ColorSpace::Rgb => ExtendedColorType::Rgb8,
+        ColorSpace::Cmyk => return None,
+    };
+    let eight = |data: &[u8]| match image.bits_per_component {
+        16 => data.iter().step_by(2).copied().collect(),
+        _ => data.to_vec(),
+    };
+    let mut data = Vec::new();
+    JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100))
+        .encode(&eight(&image.data), image.width, image.height, color_type)
+        .ok()?;
+    Some(Image {
+        bits_per_component: 8,
+        data,
+        jpeg: true,
+        inverted: false,
+        alpha: image.alpha.as_deref().map(eight),
+        ..image.clone()
+    })
+}
@@ -0,0 +1,112 @@
+use crate::css::styles::{Direction, Style, UnicodeBidi};
+use unicode_bidi::{BidiInfo, Level};
+
+// Texte bidirectionnel (UAX #9) : niveaux d'imbrication et ordre d'affichage
+
+// Caractères de contrôle équivalents à `unicode-bidi` et `direction` d'un élément en ligne
+pub fn controls(style: &Style) -> (&'static str, &'static str) {
+    let rtl = style.direction == Some(Direction::Rtl);
+    match (style.unicode_bidi.unwrap_or_default(), rtl) {
+        (UnicodeBidi::Normal, _) => ("", ""),
+        (UnicodeBidi::Embed, false) => ("\u{202A}", "\u{202C}"),
+        (UnicodeBidi::Embed, true) => ("\u{202B}", "\u{202C}"),
+        (UnicodeBidi::Isolate, false) => ("\u{2066}", "\u{2069}"),
+        (UnicodeBidi::Isolate, true) => ("\u{2067}", "\u{2069}"),
+        (UnicodeBidi::BidiOverride, false) => ("\u{202D}", "\u{202C}"),
+        (UnicodeBidi::BidiOverride, true) => ("\u{202E}", "\u{202C}"),
+        (UnicodeBidi::IsolateOverride, false) => ("\u{2066}\u{202D}", "\u{202C}\u{2069}"),
+        (UnicodeBidi::IsolateOverride, true) => ("\u{2067}\u{202E}", "\u{202C}\u{2069}"),
+        (UnicodeBidi::Plaintext, _) => ("\u{2068}", "\u{2069}"),
+    }
+}
+
+// Forçage du sens d'un bloc sur son contenu en ligne
+pub fn override_control(direction: Option<Direction>) -> &'static str {
+    match direction.unwrap_or_default() {
+        Direction::Ltr => "\u{202D}",
+        Direction::Rtl => "\u{202E}",
+    }
+}
+
+// Niveau de chaque octet du paragraphe et sens du paragraphe ;
+// sans sens imposé, il est déduit du premier caractère fort
+pub fn paragraph_levels(text: &str, base: Option<Direction>) -> (Vec<u8>, bool) {
+    let base = base.map(|d| match d {
+        Direction::Ltr => Level::ltr(),
+        Direction::Rtl => Level::rtl(),
+    });
+    let info = BidiInfo::new(text, base);
+    let rtl = match info.paragraphs.first() {
+        Some(paragraph) => paragraph.level.is_rtl(),
+        None => base.is_some_and(|l| l.is_rtl()),
+    };
+    (info.levels.iter().map(|l| l.number()).collect(), rtl)
+}
+
+// Ordre d'affichage des morceaux d'une ligne (règle L2) : de haut en bas,
+// chaque suite de niveau au moins égal à un niveau impair est retournée
+pub fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let Some(lowest_odd) = levels.iter().copied().filter(|l| l % 2 == 1).min() else {
+        return order;
+    };
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && levels[order[i]] >= level {
+                i += 1;
+            }
+            order[start..i].reverse();
+        }
+    }
+    order
+}
+
+// Texte de droite à gauche dans l'ordre d'affichage : les signes diacritiques restent
+// après leur lettre, les caractères en miroir sont retournés (règle L4)
+pub fn reverse_run(text: &str) -> String {
+    let mut clusters: Vec<String> = Vec::new();
+    for c in text.chars() {
+        match clusters.last_mut() {
+            Some(cluster) if is_mark(c) => cluster.push(c),
+            _ => clusters.push(mirror(c).to_string()),
+        }
+    }
+    clusters.into_iter().rev().collect()
+}
+
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        '≤' => '≥',
+        '≥' => '≤',
+        _ => c,
+    }
+}
+
+// Diacritiques combinants latins, hébreux et arabes
+fn is_mark(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F | 0x0591..=0x05BD | 0x05BF | 0x05C1..=0x05C2 | 0x05C4..=0x05C5
+            | 0x05C7 | 0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC
+            | 0x06DF..=0x06E4 | 0x06E7..=0x06E8 | 0x06EA..=0x06ED
+    )
+}
@@ -17,4 +17,5 @@ pub enum BoxContent {
     Text(String),
     Element(String),
     Image(String),
+    Svg(String), // élément `<svg>` du document, resérialisé
 }
\ No newline at end of file
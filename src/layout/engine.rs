@@ -1,15 +1,52 @@
+use super::bidi;
 use super::box_model::{BoxContent, LayoutBox};
+use super::inline::{InlineItem, LineArea, Placement, layout_lines};
 use crate::css::parser::parse_css;
-use crate::css::styles::{Display, Position, Style, Stylesheet};
+use crate::css::styles::{
+    DEFAULT_FONT_SIZE, Display, ImageOrientation, LengthContext, Position, Style, Stylesheet,
+};
 use crate::html::dom::{Node, NodeType};
+use crate::image::decoder::image_dimensions;
+use crate::pdf::font_provider::FontProvider;
+use crate::pdf::fonts::FontCache;
+use crate::resources::data_url::DataUrls;
+use crate::resources::loader::ResourceLoader;
+use crate::svg::parser::{serialize_svg, svg_size};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 pub fn compute_layout(
     dom: &Node,
     page_width: f32,
     page_height: f32,
     sheet: Option<&Stylesheet>,
+    fonts: &dyn FontProvider,
+    loader: &dyn ResourceLoader,
 ) -> LayoutBox {
-    let root_style = parse_css(dom, sheet, None, None);
+    let loader = &DataUrls(loader);
+    let mut root_style = parse_css(dom, sheet, None, None);
+    root_style.resolve_lengths(&LengthContext {
+        font_size: DEFAULT_FONT_SIZE,
+        root_font_size: DEFAULT_FONT_SIZE,
+        containing_width: page_width,
+        containing_height: Some(page_height),
+        page_width,
+        page_height,
+    });
+    let ctx = LayoutContext {
+        sheet,
+        page_width,
+        page_height,
+        root_font_size: root_style.font_size.to_pt(),
+        fonts: RefCell::new(FontCache::new(
+            sheet.map_or(&[][..], |s| &s.font_faces),
+            fonts,
+            loader,
+        )),
+        loader,
+        shrink: Cell::new(false),
+        fitted: RefCell::new(HashMap::new()),
+    };
     let mut root = LayoutBox {
         x: 0.0,
         y: 0.0,
@@ -27,7 +64,7 @@ pub fn compute_layout(
         10.0,
         10.0,
         page_width - 20.0,
-        sheet,
+        &ctx,
         dom,
         &root_style,
     );
@@ -35,110 +72,427 @@ pub fn compute_layout(
     root
 }
 
+struct LayoutContext<'a> {
+    sheet: Option<&'a Stylesheet>,
+    page_width: f32,
+    page_height: f32,
+    root_font_size: f32,
+    fonts: RefCell<FontCache<'a>>,
+    loader: &'a dyn ResourceLoader,
+    shrink: Cell<bool>, // mesure d'une largeur ajustée au contenu en cours
+    fitted: RefCell<HashMap<(*const Node, u32), f32>>, // largeurs ajustées, par largeur offerte
+}
+
+// Place les enfants à partir de `y_offset` et renvoie le bas du contenu
+#[allow(clippy::too_many_arguments)]
 fn layout_children(
     node: &Node,
     parent: &mut LayoutBox,
     start_x: f32,
     mut y_offset: f32,
     available_width: f32,
-    sheet: Option<&Stylesheet>,
+    ctx: &LayoutContext,
     parent_node: &Node,
     parent_style: &Style,
-) {
-    let mut x_inline = 0.0;
+) -> f32 {
+    // Le contenu en ligne est mis en lignes d'un bloc, jusqu'au prochain enfant bloc
+    let mut inline = Vec::new();
+    let mut first_line = true;
     for child_rc in &node.children {
         let child = child_rc.borrow();
-        if let NodeType::Element(tag) = &child.node_type {
-            if tag == "style" || tag == "link" {
-                continue;
-            }
+        if let NodeType::Element(tag) = &child.node_type
+            && (tag == "style" || tag == "link")
+        {
+            continue;
+        }
+        if let NodeType::Comment(_) = &child.node_type {
+            continue;
         }
-        let style = parse_css(&child, sheet, Some(parent_node), Some(parent_style));
-        let mut child_box = LayoutBox {
-            x: 0.0,
-            y: 0.0,
-            width: style
-                .width
-                .unwrap_or(available_width - style.margin.left - style.margin.right),
-            height: style.height.unwrap_or(20.0),
-            style: style.clone(),
-            link: if let NodeType::Element(t) = &child.node_type {
-                if t == "a" {
-                    child.get_attribute("href").map(|s| s.to_string())
+        let style = child_style(&child, ctx, parent_node, parent_style, available_width);
+        if style.display == Display::None {
+            continue;
+        }
+        if is_inline_level(&child, &style) {
+            let placement = Placement::default();
+            collect_inline(
+                &child,
+                style,
+                parent_style,
+                placement,
+                available_width,
+                ctx,
+                &mut inline,
+            );
+            continue;
+        }
+        if !inline.is_empty() {
+            let area = LineArea {
+                x: start_x,
+                y: y_offset,
+                width: available_width,
+                indent: if first_line {
+                    text_indent(parent_style)
                 } else {
-                    None
-                }
-            } else {
-                None
-            },
-            content: match &child.node_type {
-                NodeType::Text(t) => BoxContent::Text(t.clone()),
-                NodeType::Element(tag) => {
-                    if tag == "img" {
-                        BoxContent::Image(child.get_attribute("src").unwrap_or("").to_string())
-                    } else {
-                        BoxContent::Element(tag.clone())
-                    }
-                }
-                NodeType::Comment(_) => continue,
-            },
-            children: vec![],
+                    0.0
+                },
+            };
+            y_offset = flush_inline(&mut inline, parent, &area, ctx, parent_style);
+            first_line = false;
+        }
+
+        let margin = pt_sides(&style.margin);
+        let Some(mut child_box) = new_box(
+            &child,
+            style,
+            available_width - margin.left - margin.right,
+            ctx,
+        ) else {
+            continue;
         };
+        let style = &child_box.style;
 
         match style.position {
             Position::Absolute => {
-                child_box.x = style.left.unwrap_or(0.0) + style.margin.left;
-                child_box.y = style.top.unwrap_or(0.0) + style.margin.top;
+                child_box.x = style.left.as_ref().map_or(0.0, |l| l.to_pt()) + margin.left;
+                child_box.y = style.top.as_ref().map_or(0.0, |t| t.to_pt()) + margin.top;
+                layout_contents(&child, &mut child_box, ctx);
             }
+            // Décalée après coup, la boîte relative garde sa place dans le flux
             Position::Relative => {
-                child_box.x = start_x + x_inline + style.margin.left + style.left.unwrap_or(0.0);
-                child_box.y = y_offset + style.margin.top + style.top.unwrap_or(0.0);
-                if style.display == Display::Block {
-                    y_offset += child_box.height + style.margin.top + style.margin.bottom;
-                    x_inline = 0.0;
-                } else {
-                    x_inline += child_box.width + style.margin.left + style.margin.right;
-                }
+                child_box.x =
+                    start_x + margin.left + style.left.as_ref().map_or(0.0, |l| l.to_pt());
+                child_box.y = y_offset + margin.top + style.top.as_ref().map_or(0.0, |t| t.to_pt());
+                layout_contents(&child, &mut child_box, ctx);
+                y_offset += child_box.height + margin.top + margin.bottom;
             }
             Position::Static => {
-                match style.display {
-                    Display::Block => {
-                        child_box.x = start_x + style.margin.left;
-                        child_box.y = y_offset + style.margin.top;
-                        y_offset += child_box.height + style.margin.top + style.margin.bottom;
-                        x_inline = 0.0;
+                child_box.x = start_x + margin.left;
+                child_box.y = y_offset + margin.top;
+                layout_contents(&child, &mut child_box, ctx);
+                y_offset += child_box.height + margin.top + margin.bottom;
+            }
+        }
+
+        parent.children.push(child_box);
+    }
+    if !inline.is_empty() {
+        let area = LineArea {
+            x: start_x,
+            y: y_offset,
+            width: available_width,
+            indent: if first_line {
+                text_indent(parent_style)
+            } else {
+                0.0
+            },
+        };
+        y_offset = flush_inline(&mut inline, parent, &area, ctx, parent_style);
+    }
+    y_offset
+}
+
+fn child_style(
+    child: &Node,
+    ctx: &LayoutContext,
+    parent_node: &Node,
+    parent_style: &Style,
+    available_width: f32,
+) -> Style {
+    let mut style = parse_css(child, ctx.sheet, Some(parent_node), Some(parent_style));
+    style.resolve_lengths(&LengthContext {
+        font_size: parent_style.font_size.to_pt(),
+        root_font_size: ctx.root_font_size,
+        containing_width: available_width,
+        containing_height: parent_style.height.as_ref().map(|h| h.to_pt()),
+        page_width: ctx.page_width,
+        page_height: ctx.page_height,
+    });
+    style
+}
+
+// Texte, éléments en ligne et `inline-block` ; une boîte positionnée en absolu sort du flux
+fn is_inline_level(node: &Node, style: &Style) -> bool {
+    match &node.node_type {
+        NodeType::Text(_) => true,
+        NodeType::Element(tag) if tag == "br" => true,
+        _ => {
+            matches!(style.display, Display::Inline | Display::InlineBlock)
+                && style.position != Position::Absolute
+        }
+    }
+}
+
+// `outer` : placement vertical de l'élément en ligne englobant
+fn collect_inline(
+    node: &Node,
+    style: Style,
+    parent_style: &Style,
+    outer: Placement,
+    available_width: f32,
+    ctx: &LayoutContext,
+    items: &mut Vec<InlineItem>,
+) {
+    let placement = outer.nest(Placement::of(
+        &style,
+        parent_style,
+        &mut ctx.fonts.borrow_mut(),
+    ));
+    match &node.node_type {
+        NodeType::Text(text) => items.push(InlineItem::Text {
+            text: text.clone(),
+            style,
+            link: None,
+            placement: outer,
+        }),
+        NodeType::Element(tag) if tag == "br" => items.push(InlineItem::Break),
+        NodeType::Element(_) if style.display == Display::Inline => {
+            // Un élément en ligne n'a pas de boîte propre : ses enfants suivent le fil
+            let link = link_of(node);
+            let (open, close) = bidi::controls(&style);
+            if !open.is_empty() {
+                items.push(InlineItem::Bidi(open));
+            }
+            let start = items.len();
+            for child_rc in &node.children {
+                let child = child_rc.borrow();
+                if let NodeType::Comment(_) = &child.node_type {
+                    continue;
+                }
+                let child_style = child_style(&child, ctx, node, &style, available_width);
+                if child_style.display == Display::None {
+                    continue;
+                }
+                // Un bloc dans un élément en ligne est traité comme une boîte atomique
+                let child_style = if is_inline_level(&child, &child_style) {
+                    child_style
+                } else {
+                    Style {
+                        display: Display::InlineBlock,
+                        ..child_style
                     }
-                    Display::Inline | Display::InlineBlock => {
-                        child_box.x = start_x + x_inline + style.margin.left;
-                        child_box.y = y_offset + style.margin.top;
-                        x_inline += child_box.width + style.margin.left + style.margin.right;
+                };
+                collect_inline(
+                    &child,
+                    child_style,
+                    &style,
+                    placement,
+                    available_width,
+                    ctx,
+                    items,
+                );
+            }
+            if link.is_some() {
+                for item in &mut items[start..] {
+                    match item {
+                        InlineItem::Text { link: l, .. } => *l = link.clone(),
+                        InlineItem::Atomic { b, .. } => b.link = b.link.clone().or(link.clone()),
+                        InlineItem::Break | InlineItem::Bidi(_) => {}
                     }
-                    Display::None => continue,
                 }
             }
+            if !close.is_empty() {
+                items.push(InlineItem::Bidi(close));
+            }
+        }
+        NodeType::Element(_) => {
+            let margin = pt_sides(&style.margin);
+            if let Some(b) = layout_atomic(node, style, available_width, ctx) {
+                items.push(InlineItem::Atomic {
+                    b,
+                    margin: [margin.top, margin.right, margin.bottom, margin.left],
+                    placement,
+                });
+            }
         }
+        NodeType::Comment(_) => {}
+    }
+}
 
-        if !child.children.is_empty() {
-            let child_start_x = child_box.x + style.padding.left + style.border_width.left;
-            let child_width = child_box.width
-                - style.padding.left
-                - style.padding.right
-                - style.border_width.left
-                - style.border_width.right;
-            let child_y = child_box.y + style.padding.top + style.border_width.top;
-            let child_style_clone = child_box.style.clone();
-            layout_children(
-                &child,
-                &mut child_box,
-                child_start_x,
-                child_y,
-                child_width,
-                sheet,
-                &child,
-                &child_style_clone,
-            );
+fn flush_inline(
+    items: &mut Vec<InlineItem>,
+    parent: &mut LayoutBox,
+    area: &LineArea,
+    ctx: &LayoutContext,
+    parent_style: &Style,
+) -> f32 {
+    let (boxes, bottom) = layout_lines(
+        std::mem::take(items),
+        parent_style,
+        area,
+        ctx.shrink.get(),
+        &mut ctx.fonts.borrow_mut(),
+    );
+    parent.children.extend(boxes);
+    bottom
+}
+
+// Boîte atomique mise en page à l'origine ; sans largeur, elle s'ajuste à son contenu
+fn layout_atomic(
+    node: &Node,
+    style: Style,
+    available_width: f32,
+    ctx: &LayoutContext,
+) -> Option<LayoutBox> {
+    let margin = pt_sides(&style.margin);
+    let available = available_width - margin.left - margin.right;
+    let auto_width = style.width.is_none();
+    let mut b = new_box(node, style, available, ctx)?;
+    if let BoxContent::Image(_) | BoxContent::Svg(_) = &b.content {
+        return Some(b);
+    }
+    // Mesure retenue par nœud : les boîtes imbriquées ne sont mesurées qu'une fois
+    let key = (node as *const Node, available.to_bits());
+    if auto_width && let Some(&width) = ctx.fitted.borrow().get(&key) {
+        b.width = width;
+    } else if auto_width && !node.children.is_empty() {
+        let shrink = ctx.shrink.replace(true);
+        let mut probe = new_box(node, b.style.clone(), available, ctx)?;
+        layout_contents(node, &mut probe, ctx);
+        ctx.shrink.set(shrink);
+        let content = probe
+            .children
+            .iter()
+            .map(right_edge)
+            .fold(probe.x, f32::max);
+        let sides = horizontal_sides(&probe.style);
+        // Marge d'arrondi : la seconde passe doit retrouver les mêmes coupures
+        b.width = (content - probe.x + sides.1 + 0.01).min(available);
+        ctx.fitted.borrow_mut().insert(key, b.width);
+    } else if auto_width {
+        let sides = horizontal_sides(&b.style);
+        b.width = sides.0 + sides.1;
+    }
+    layout_contents(node, &mut b, ctx);
+    Some(b)
+}
+
+// Bord droit du contenu effectif, marge comprise
+fn right_edge(b: &LayoutBox) -> f32 {
+    let margin = b.style.margin.right.to_pt();
+    match (&b.content, &b.style.width) {
+        (BoxContent::Element(_), None) if b.style.display == Display::Block => {
+            let sides = horizontal_sides(&b.style);
+            let content = b
+                .children
+                .iter()
+                .map(right_edge)
+                .fold(b.x + sides.0, f32::max);
+            content + sides.1 + margin
         }
+        _ => b.x + b.width + margin,
+    }
+}
 
-        parent.children.push(child_box);
+// Largeurs de bordure et de marge intérieure, à gauche puis à droite
+fn horizontal_sides(style: &Style) -> (f32, f32) {
+    (
+        style.border_width.left.to_pt() + style.padding.left.to_pt(),
+        style.border_width.right.to_pt() + style.padding.right.to_pt(),
+    )
+}
+
+// Dimensions déclarées, sinon intrinsèques (pixels CSS) en gardant les proportions
+fn image_size(intrinsic: Option<(f32, f32)>, style: &Style) -> (f32, f32) {
+    let width = style.width.as_ref().map(|w| w.to_pt());
+    let height = style.height.as_ref().map(|h| h.to_pt());
+    let intrinsic = intrinsic.map(|(w, h)| (w * 0.75, h * 0.75));
+    match (width, height, intrinsic) {
+        (Some(w), Some(h), _) => (w, h),
+        (Some(w), None, Some((iw, ih))) if iw > 0.0 => (w, w * ih / iw),
+        (None, Some(h), Some((iw, ih))) if ih > 0.0 => (h * iw / ih, h),
+        (None, None, Some(size)) => size,
+        (w, h, _) => (w.unwrap_or(0.0), h.unwrap_or(0.0)),
+    }
+}
+
+fn new_box(node: &Node, style: Style, width: f32, ctx: &LayoutContext) -> Option<LayoutBox> {
+    let content = match &node.node_type {
+        NodeType::Text(t) => BoxContent::Text(t.clone()),
+        NodeType::Element(tag) => {
+            if tag == "img" {
+                BoxContent::Image(node.get_attribute("src").unwrap_or("").to_string())
+            } else if tag == "svg" {
+                BoxContent::Svg(serialize_svg(node))
+            } else {
+                BoxContent::Element(tag.clone())
+            }
+        }
+        NodeType::Comment(_) => return None,
+    };
+    let (width, height) = match &content {
+        BoxContent::Image(src) => {
+            let oriented = style.image_orientation != Some(ImageOrientation::None);
+            image_size(image_dimensions(src, ctx.loader, oriented), &style)
+        }
+        BoxContent::Svg(markup) => image_size(svg_size(markup.as_bytes()), &style),
+        _ => (
+            style.width.as_ref().map_or(width, |w| w.to_pt()),
+            style.height.as_ref().map_or(0.0, |h| h.to_pt()),
+        ),
+    };
+    Some(LayoutBox {
+        x: 0.0,
+        y: 0.0,
+        width,
+        height,
+        link: link_of(node),
+        content,
+        style,
+        children: vec![],
+    })
+}
+
+fn link_of(node: &Node) -> Option<String> {
+    match &node.node_type {
+        NodeType::Element(t) if t == "a" => node.get_attribute("href").map(|s| s.to_string()),
+        _ => None,
     }
-}
\ No newline at end of file
+}
+
+// Met en page le contenu de la boîte ; sans hauteur déclarée, elle l'englobe
+fn layout_contents(node: &Node, b: &mut LayoutBox, ctx: &LayoutContext) {
+    if let BoxContent::Image(_) | BoxContent::Svg(_) = b.content {
+        return;
+    }
+    let padding = pt_sides(&b.style.padding);
+    let border = pt_sides(&b.style.border_width);
+    let mut bottom = b.y + padding.top + border.top;
+    if !node.children.is_empty() {
+        let child_start_x = b.x + padding.left + border.left;
+        let child_width = b.width - padding.left - padding.right - border.left - border.right;
+        let style = b.style.clone();
+        bottom = layout_children(
+            node,
+            b,
+            child_start_x,
+            bottom,
+            child_width,
+            ctx,
+            node,
+            &style,
+        );
+    }
+    if b.style.height.is_none() {
+        b.height = bottom - b.y + padding.bottom + border.bottom;
+    }
+}
+
+fn text_indent(style: &Style) -> f32 {
+    style.text_indent.as_ref().map_or(0.0, |l| l.to_pt())
+}
+
+struct PtSides {
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+}
+
+fn pt_sides(sides: &crate::css::styles::Sides) -> PtSides {
+    PtSides {
+        top: sides.top.to_pt(),
+        right: sides.right.to_pt(),
+        bottom: sides.bottom.to_pt(),
+        left: sides.left.to_pt(),
+    }
+}
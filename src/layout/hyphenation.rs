@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+// Césure par l'algorithme de Liang. Aucun motif n'est livré : l'application charge ceux de
+// son choix (fichiers hyph-*.tex du projet hyph-utf8 par exemple), sous leur propre licence
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    English,
+    French,
+    German,
+}
+
+struct Patterns {
+    levels: HashMap<String, Vec<u8>>, // lettres du motif -> niveaux entre elles
+    max_len: usize,                   // en caractères
+}
+
+impl Lang {
+    // Attribut `lang` : seule la langue principale compte (`fr-CA`, `en_GB`…)
+    pub fn from_tag(tag: &str) -> Option<Lang> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Lang::English),
+            "fr" => Some(Lang::French),
+            "de" => Some(Lang::German),
+            _ => None,
+        }
+    }
+
+    // Nombre minimal de lettres avant et après la coupure
+    fn bounds(self) -> (usize, usize) {
+        match self {
+            Lang::English => (2, 3),
+            Lang::French | Lang::German => (2, 2),
+        }
+    }
+
+    fn patterns(self) -> Option<Arc<Patterns>> {
+        let loaded = PATTERNS.read().unwrap_or_else(|e| e.into_inner());
+        loaded
+            .iter()
+            .find(|(lang, _)| *lang == self)
+            .map(|(_, patterns)| patterns.clone())
+    }
+}
+
+static PATTERNS: RwLock<Vec<(Lang, Arc<Patterns>)>> = RwLock::new(Vec::new());
+
+// Motifs TeX (`\patterns{...}`) de la langue ; remplace ceux déjà chargés
+pub fn load_patterns(lang: Lang, source: &str) {
+    let patterns = Arc::new(parse_patterns(source));
+    let mut loaded = PATTERNS.write().unwrap_or_else(|e| e.into_inner());
+    loaded.retain(|(l, _)| *l != lang);
+    loaded.push((lang, patterns));
+}
+
+// Contenu de `\patterns{...}` ; les commentaires `%` sont ignorés
+fn parse_patterns(source: &str) -> Patterns {
+    let mut patterns = Patterns {
+        levels: HashMap::new(),
+        max_len: 0,
+    };
+    let mut inside = false;
+    for line in source.lines() {
+        let line = line.split('%').next().unwrap_or("");
+        for token in line.split_whitespace() {
+            if let Some(rest) = token.strip_prefix("\\patterns{") {
+                inside = true;
+                if rest.is_empty() {
+                    continue;
+                }
+            }
+            if !inside {
+                continue;
+            }
+            let token = match token.strip_suffix('}') {
+                Some(t) => {
+                    inside = false;
+                    t
+                }
+                None => token,
+            };
+            let mut letters = String::new();
+            let mut levels = vec![0];
+            for c in token.chars() {
+                match c.to_digit(10) {
+                    Some(d) => *levels.last_mut().unwrap() = d as u8,
+                    None => {
+                        letters.push(c);
+                        levels.push(0);
+                    }
+                }
+            }
+            if !letters.is_empty() {
+                patterns.max_len = patterns.max_len.max(levels.len() - 1);
+                patterns.levels.insert(letters, levels);
+            }
+        }
+    }
+    patterns
+}
+
+// Indices (en caractères) où le mot peut être coupé ; aucun sans motifs chargés
+pub fn hyphenate(word: &str, lang: Lang) -> Vec<usize> {
+    let Some(patterns) = lang.patterns() else {
+        return Vec::new();
+    };
+    let chars: Vec<char> = word.chars().collect();
+    let (left, right) = lang.bounds();
+    if chars.len() < left + right {
+        return Vec::new();
+    }
+    let dotted: Vec<char> = std::iter::once('.')
+        .chain(chars.iter().map(|c| match c {
+            '’' => '\'',
+            _ => c.to_lowercase().next().unwrap_or(*c),
+        }))
+        .chain(std::iter::once('.'))
+        .collect();
+    // levels[k] : niveau entre dotted[k - 1] et dotted[k]
+    let mut levels = vec![0u8; dotted.len() + 1];
+    let mut key = String::new();
+    for start in 0..dotted.len() {
+        key.clear();
+        for c in dotted[start..].iter().take(patterns.max_len) {
+            key.push(*c);
+            if let Some(pattern) = patterns.levels.get(&key) {
+                for (k, level) in pattern.iter().enumerate() {
+                    let slot = &mut levels[start + k];
+                    *slot = (*slot).max(*level);
+                }
+            }
+        }
+    }
+    // Une coupure avant chars[i] se lit entre dotted[i] et dotted[i + 1]
+    (left..=chars.len() - right)
+        .filter(|&i| levels[i + 1] % 2 == 1)
+        .collect()
+}
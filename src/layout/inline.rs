@@ -0,0 +1,680 @@
+use super::bidi::{override_control, paragraph_levels, visual_order};
+use super::box_model::{BoxContent, LayoutBox};
+use super::hyphenation::{Lang, hyphenate};
+use crate::css::styles::{
+    Direction, Hyphens, Length, Overflow, Position, Style, TextAlign, UnicodeBidi, VerticalAlign,
+};
+use crate::pdf::fonts::FontCache;
+
+const SOFT_HYPHEN: char = '\u{AD}';
+
+// Contenu en ligne d'un bloc, dans l'ordre du document
+pub enum InlineItem {
+    Text {
+        text: String,
+        style: Style,
+        link: Option<String>,
+        placement: Placement,
+    },
+    // Boîte `inline-block` ou image, déjà mise en page à l'origine ; marges comprises
+    Atomic {
+        b: LayoutBox,
+        margin: [f32; 4], // haut, droite, bas, gauche
+        placement: Placement,
+    },
+    Break,
+    // Contrôles bidi ouvrant ou fermant un élément en ligne
+    Bidi(&'static str),
+}
+
+// Repère d'alignement vertical dans la ligne
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Anchor {
+    #[default]
+    Baseline, // ligne de base élevée de `raise`
+    Middle,     // milieu de la boîte à `raise`
+    TextTop,    // haut de la boîte à `raise`
+    TextBottom, // bas de la boîte à `raise`
+    LineTop,
+    LineBottom,
+}
+
+// Position verticale d'un contenu, relative à la ligne de base du bloc (vers le haut)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Placement {
+    pub anchor: Anchor,
+    pub raise: f32,
+}
+
+impl Placement {
+    // `vertical-align` d'un élément par rapport à la police de son parent
+    pub fn of(style: &Style, parent: &Style, fonts: &mut FontCache) -> Placement {
+        let size = parent.font_size.to_pt();
+        let metrics = fonts.for_style(parent).1.metrics;
+        let em = size / 1000.0;
+        let (anchor, raise) = match style.vertical_align.clone().unwrap_or_default() {
+            VerticalAlign::Baseline => (Anchor::Baseline, 0.0),
+            // Décalages des navigateurs : un cinquième et un tiers de la taille
+            VerticalAlign::Sub => (Anchor::Baseline, -size / 5.0),
+            VerticalAlign::Super => (Anchor::Baseline, size / 3.0),
+            VerticalAlign::Length(l) => (Anchor::Baseline, l.to_pt()),
+            VerticalAlign::Middle => (Anchor::Middle, metrics.x_height * em / 2.0),
+            VerticalAlign::TextTop => (Anchor::TextTop, metrics.ascent * em),
+            VerticalAlign::TextBottom => (Anchor::TextBottom, metrics.descent * em),
+            VerticalAlign::Top => (Anchor::LineTop, 0.0),
+            VerticalAlign::Bottom => (Anchor::LineBottom, 0.0),
+        };
+        Placement { anchor, raise }
+    }
+
+    // Placement d'un contenu à l'intérieur d'un élément en ligne placé en `self`.
+    // Hors alignement sur la ligne de base, le contenu suit simplement son parent.
+    pub fn nest(self, inner: Placement) -> Placement {
+        match self.anchor {
+            Anchor::Baseline => Placement {
+                raise: self.raise + inner.raise,
+                ..inner
+            },
+            _ => self,
+        }
+    }
+}
+
+struct Piece {
+    kind: Kind,
+    level: u8,   // niveau bidi
+    glued: bool, // pas de coupure possible avant ce mot
+}
+
+enum Kind {
+    Word(usize, String),
+    Space(usize),
+    Atomic(usize),
+    Break,
+}
+
+struct Line {
+    pieces: Vec<(Piece, f32)>, // morceau et chasse
+    width: f32,
+    forced_end: bool,
+}
+
+// Zone des lignes : origine, largeur et retrait de la première ligne
+pub struct LineArea {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub indent: f32,
+}
+
+// Découpe en lignes aux espaces et place les fragments ; renvoie le bas de la dernière ligne.
+// `shrink` : mesure de la largeur intrinsèque, sans alignement ni justification.
+pub fn layout_lines(
+    items: Vec<InlineItem>,
+    container: &Style,
+    area: &LineArea,
+    shrink: bool,
+    fonts: &mut FontCache,
+) -> (Vec<LayoutBox>, f32) {
+    let (pieces, rtl) = segment(&items, container);
+    let lines = break_lines(pieces, &items, area, fonts);
+    let align = match container.text_align.unwrap_or_default() {
+        _ if shrink => TextAlign::Left,
+        TextAlign::Start if rtl => TextAlign::Right,
+        TextAlign::End if !rtl => TextAlign::Right,
+        TextAlign::Start | TextAlign::End => TextAlign::Left,
+        align => align,
+    };
+    let strut = text_extent(container, fonts);
+
+    let mut items: Vec<Option<InlineItem>> = items.into_iter().map(Some).collect();
+    let mut boxes = Vec::new();
+    let mut y = area.y;
+    let count = lines.len();
+    for (n, line) in lines.into_iter().enumerate() {
+        let indent = if n == 0 { area.indent } else { 0.0 };
+        let free = area.width - indent - line.width;
+        let spaces = line
+            .pieces
+            .iter()
+            .filter(|(p, _)| matches!(p.kind, Kind::Space(_)))
+            .count();
+        let last = n + 1 == count || line.forced_end;
+        let (offset, extra) = match align {
+            TextAlign::Right => (free.max(0.0), 0.0),
+            TextAlign::Center => (free.max(0.0) / 2.0, 0.0),
+            TextAlign::Justify if !last && spaces > 0 && free > 0.0 => (0.0, free / spaces as f32),
+            // Dernière ligne justifiée : alignée au début
+            TextAlign::Justify if rtl => (free.max(0.0), 0.0),
+            _ => (0.0, 0.0),
+        };
+        let fragments = line_fragments(line, extra, &items, fonts);
+        let (ascent, height) = line_box(&fragments, strut);
+        let baseline = y + ascent;
+        // De droite à gauche, le retrait est à droite
+        let mut x = area.x + offset + if rtl { 0.0 } else { indent };
+        for fragment in fragments {
+            // Ligne de base propre au fragment
+            let own = match fragment.placement.anchor {
+                Anchor::LineTop => y + fragment.extent.above,
+                Anchor::LineBottom => y + height - fragment.extent.below,
+                _ => baseline - fragment.raise(),
+            };
+            let top = own - fragment.extent.top;
+            match items[fragment.item].take() {
+                Some(InlineItem::Atomic { mut b, margin, .. }) => {
+                    let (dx, dy) = relative_offset(&b.style);
+                    translate(&mut b, x + margin[3] + dx, top + dy);
+                    boxes.push(b);
+                }
+                Some(InlineItem::Text {
+                    style,
+                    link,
+                    placement,
+                    text,
+                }) => {
+                    let mut fragment_style = style.clone();
+                    fragment_style.direction = Some(if fragment.rtl {
+                        Direction::Rtl
+                    } else {
+                        Direction::Ltr
+                    });
+                    if extra > 0.0 {
+                        let spacing = style.word_spacing.as_ref().map_or(0.0, Length::to_pt);
+                        fragment_style.word_spacing = Some(Length::Pt(spacing + extra));
+                    }
+                    let (dx, dy) = relative_offset(&style);
+                    boxes.push(LayoutBox {
+                        x: x + dx,
+                        y: top + dy,
+                        width: fragment.width,
+                        height: fragment.extent.top + fragment.extent.bottom,
+                        style: fragment_style,
+                        link: link.clone(),
+                        content: BoxContent::Text(fragment.text.unwrap_or_default()),
+                        children: vec![],
+                    });
+                    // Le texte peut se poursuivre sur la ligne suivante
+                    items[fragment.item] = Some(InlineItem::Text {
+                        text,
+                        style,
+                        link,
+                        placement,
+                    });
+                }
+                _ => {}
+            }
+            x += fragment.width;
+        }
+        y += height;
+    }
+    (boxes, y)
+}
+
+// Encombrement vertical autour de la ligne de base, en points
+#[derive(Clone, Copy)]
+struct Extent {
+    above: f32,  // hauteur de ligne au-dessus de la ligne de base
+    below: f32,  // et au-dessous
+    top: f32,    // de la ligne de base au haut de la boîte
+    bottom: f32, // et à son bas
+}
+
+struct Fragment {
+    item: usize,
+    text: Option<String>,
+    width: f32,
+    extent: Extent,
+    placement: Placement,
+    rtl: bool, // texte de droite à gauche, retourné à l'affichage
+}
+
+impl Fragment {
+    // Élévation de la ligne de base du fragment au-dessus de celle du bloc
+    fn raise(&self) -> f32 {
+        let Extent { above, below, .. } = self.extent;
+        let raise = self.placement.raise;
+        match self.placement.anchor {
+            Anchor::Middle => raise - (above - below) / 2.0,
+            Anchor::TextTop => raise - above,
+            Anchor::TextBottom => raise + below,
+            _ => raise,
+        }
+    }
+}
+
+// Morceaux dans l'ordre d'affichage ; ceux qui se suivent dans un même texte forment un fragment
+fn line_fragments(
+    line: Line,
+    extra: f32,
+    items: &[Option<InlineItem>],
+    fonts: &mut FontCache,
+) -> Vec<Fragment> {
+    let levels: Vec<u8> = line.pieces.iter().map(|(p, _)| p.level).collect();
+    let mut pieces: Vec<Option<(Piece, f32)>> = line.pieces.into_iter().map(Some).collect();
+    let mut fragments: Vec<Fragment> = Vec::new();
+    for (piece, width) in visual_order(&levels)
+        .into_iter()
+        .filter_map(|k| pieces[k].take())
+    {
+        let rtl = piece.level % 2 == 1;
+        let (i, text, width) = match piece.kind {
+            Kind::Word(i, word) => (i, Some(visible(&word)), width),
+            Kind::Space(i) => (i, Some(" ".to_string()), width + extra),
+            Kind::Atomic(i) => (i, None, width),
+            Kind::Break => continue,
+        };
+        // Le texte garde l'ordre logique : de droite à gauche, il se complète par le début
+        if let Some(text) = &text
+            && let Some(last) = fragments.last_mut()
+            && last.item == i
+            && last.rtl == rtl
+            && let Some(t) = &mut last.text
+        {
+            if rtl {
+                t.insert_str(0, text);
+            } else {
+                t.push_str(text);
+            }
+            last.width += width;
+            continue;
+        }
+        let (extent, placement) = match &items[i] {
+            Some(InlineItem::Text {
+                style, placement, ..
+            }) => (text_extent(style, fonts), *placement),
+            Some(InlineItem::Atomic {
+                b,
+                margin,
+                placement,
+            }) => (atomic_extent(b, margin, fonts), *placement),
+            _ => continue,
+        };
+        fragments.push(Fragment {
+            item: i,
+            text,
+            width,
+            extent,
+            placement,
+            rtl,
+        });
+    }
+    fragments
+}
+
+// Demi-interlignage de part et d'autre de la zone de contenu de la police
+fn text_extent(style: &Style, fonts: &mut FontCache) -> Extent {
+    let size = style.font_size.to_pt();
+    let metrics = fonts.for_style(style).1.metrics;
+    let ascent = metrics.ascent * size / 1000.0;
+    let descent = -metrics.descent * size / 1000.0;
+    let line_height = style.line_height.clone().unwrap_or_default().to_pt(size);
+    let half_leading = (line_height - ascent - descent) / 2.0;
+    Extent {
+        above: ascent + half_leading,
+        below: descent + half_leading,
+        top: ascent,
+        bottom: descent,
+    }
+}
+
+// Ligne de base de la dernière ligne ; bord inférieur de la marge à défaut
+// ou si le contenu est rogné
+fn atomic_extent(b: &LayoutBox, margin: &[f32; 4], fonts: &mut FontCache) -> Extent {
+    let outer = b.height + margin[0] + margin[2];
+    let clipped =
+        b.style.overflow_x != Overflow::Visible || b.style.overflow_y != Overflow::Visible;
+    let above = match last_baseline(b, fonts) {
+        Some(baseline) if !clipped => margin[0] + baseline - b.y,
+        _ => outer,
+    };
+    Extent {
+        above,
+        below: outer - above,
+        top: above - margin[0],
+        bottom: outer - above - margin[2],
+    }
+}
+
+fn last_baseline(b: &LayoutBox, fonts: &mut FontCache) -> Option<f32> {
+    match &b.content {
+        BoxContent::Text(_) => {
+            let size = b.style.font_size.to_pt();
+            let ascent = fonts.for_style(&b.style).1.metrics.ascent;
+            Some(b.y + ascent * size / 1000.0)
+        }
+        BoxContent::Image(_) | BoxContent::Svg(_) => None,
+        BoxContent::Element(_) => b
+            .children
+            .iter()
+            .rev()
+            .filter(|child| child.style.position != Position::Absolute)
+            .find_map(|child| match child.content {
+                // Une image alignée se pose sur la ligne de base
+                BoxContent::Image(_) | BoxContent::Svg(_) => {
+                    Some(child.y + child.height + child.style.margin.bottom.to_pt())
+                }
+                _ => last_baseline(child, fonts),
+            }),
+    }
+}
+
+// Hauteur au-dessus de la ligne de base et hauteur totale de la ligne ;
+// le strut du bloc y entre toujours
+fn line_box(fragments: &[Fragment], strut: Extent) -> (f32, f32) {
+    let mut top = strut.above;
+    let mut bottom = -strut.below;
+    for fragment in fragments {
+        if matches!(
+            fragment.placement.anchor,
+            Anchor::LineTop | Anchor::LineBottom
+        ) {
+            continue;
+        }
+        let raise = fragment.raise();
+        top = top.max(raise + fragment.extent.above);
+        bottom = bottom.min(raise - fragment.extent.below);
+    }
+    // Les contenus alignés sur la ligne ne l'agrandissent que s'ils la dépassent
+    for fragment in fragments {
+        let height = fragment.extent.above + fragment.extent.below;
+        match fragment.placement.anchor {
+            Anchor::LineTop => bottom = bottom.min(top - height),
+            Anchor::LineBottom => top = top.max(bottom + height),
+            _ => {}
+        }
+    }
+    (top, top - bottom)
+}
+
+// Les blancs se fusionnent en une espace, d'un texte à l'autre ; l'espace insécable reste.
+// Les mots sont ensuite coupés aux possibilités de coupure (UAX #14) et aux changements
+// de niveau bidi (UAX #9) ; renvoie aussi le sens du paragraphe.
+fn segment(items: &[InlineItem], container: &Style) -> (Vec<Piece>, bool) {
+    // Texte du paragraphe : objets et sauts de ligne y ont leur caractère.
+    // Un bloc en `bidi-override` impose son sens à tout son contenu en ligne.
+    let mut text = match container.unicode_bidi {
+        Some(UnicodeBidi::BidiOverride | UnicodeBidi::IsolateOverride) => {
+            override_control(container.direction).to_string()
+        }
+        _ => String::new(),
+    };
+    let mut raw = Vec::new(); // morceau et position dans le texte
+    let mut after_space = true;
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            InlineItem::Text { text: content, .. } => {
+                let mut word = String::new();
+                for c in content.chars() {
+                    if matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C') {
+                        if !word.is_empty() {
+                            let start = text.len() - word.len();
+                            raw.push((Kind::Word(i, std::mem::take(&mut word)), start));
+                        }
+                        if !after_space {
+                            raw.push((Kind::Space(i), text.len()));
+                            text.push(' ');
+                        }
+                        after_space = true;
+                    } else {
+                        word.push(c);
+                        text.push(c);
+                        after_space = false;
+                    }
+                }
+                if !word.is_empty() {
+                    let start = text.len() - word.len();
+                    raw.push((Kind::Word(i, word), start));
+                }
+            }
+            InlineItem::Atomic { .. } => {
+                raw.push((Kind::Atomic(i), text.len()));
+                text.push('\u{FFFC}');
+                after_space = false;
+            }
+            InlineItem::Break => {
+                raw.push((Kind::Break, text.len()));
+                text.push('\u{2028}');
+                after_space = true;
+            }
+            InlineItem::Bidi(controls) => text.push_str(controls),
+        }
+    }
+
+    let mut opportunities = vec![false; text.len() + 1];
+    for (k, _) in unicode_linebreak::linebreaks(&text) {
+        // Après un trait d'union conditionnel, la coupure relève de la césure
+        if !text[..k].ends_with(SOFT_HYPHEN) {
+            opportunities[k] = true;
+        }
+    }
+    let base = match container.unicode_bidi {
+        Some(UnicodeBidi::Plaintext) => None,
+        _ => Some(container.direction.unwrap_or_default()),
+    };
+    let (levels, rtl) = paragraph_levels(&text, base);
+    let level_at = |k: usize| levels.get(k).copied().unwrap_or(0);
+
+    let mut pieces = Vec::new();
+    for (kind, start) in raw {
+        let Kind::Word(i, word) = kind else {
+            pieces.push(Piece {
+                kind,
+                level: level_at(start),
+                glued: false,
+            });
+            continue;
+        };
+        let mut from = 0;
+        for (k, _) in word.char_indices().skip(1).chain([(word.len(), ' ')]) {
+            let split = k == word.len()
+                || opportunities[start + k]
+                || level_at(start + k) != level_at(start + from);
+            if split {
+                pieces.push(Piece {
+                    kind: Kind::Word(i, word[from..k].to_string()),
+                    level: level_at(start + from),
+                    glued: !opportunities[start + from],
+                });
+                from = k;
+            }
+        }
+    }
+    (pieces, rtl)
+}
+
+fn break_lines(
+    pieces: Vec<Piece>,
+    items: &[InlineItem],
+    area: &LineArea,
+    fonts: &mut FontCache,
+) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line = Line {
+        pieces: Vec::new(),
+        width: 0.0,
+        forced_end: false,
+    };
+    for piece in pieces {
+        let mut width = match &piece.kind {
+            Kind::Word(i, word) => text_width(&items[*i], word, fonts),
+            Kind::Space(i) => text_width(&items[*i], " ", fonts),
+            Kind::Atomic(i) => match &items[*i] {
+                InlineItem::Atomic { b, margin, .. } => b.width + margin[1] + margin[3],
+                _ => 0.0,
+            },
+            Kind::Break => {
+                line.forced_end = true;
+                lines.push(end_line(&mut line));
+                continue;
+            }
+        };
+        if matches!(piece.kind, Kind::Space(_)) {
+            // Une espace en début de ligne disparaît
+            if !line.pieces.is_empty() {
+                line.pieces.push((piece, width));
+            }
+            continue;
+        }
+        let mut piece = piece;
+        loop {
+            let available = area.width - if lines.is_empty() { area.indent } else { 0.0 };
+            // Les espaces en attente comptent si le mot reste sur la ligne
+            let pending: f32 = line.pieces.iter().map(|(_, w)| w).sum();
+            if pending + width <= available {
+                break;
+            }
+            // Le mot est coupé en fin de ligne s'il le peut, la suite passe à la ligne
+            if let Kind::Word(i, word) = &piece.kind
+                && let Some((head, tail)) =
+                    hyphenate_to_fit(&items[*i], word, available - pending, fonts)
+            {
+                let i = *i;
+                let head_width = text_width(&items[i], &head, fonts);
+                let level = piece.level;
+                line.pieces.push((
+                    Piece {
+                        kind: Kind::Word(i, head),
+                        ..piece
+                    },
+                    head_width,
+                ));
+                lines.push(end_line(&mut line));
+                width = text_width(&items[i], &tail, fonts);
+                piece = Piece {
+                    kind: Kind::Word(i, tail),
+                    level,
+                    glued: false,
+                };
+                continue;
+            }
+            let breakable = match (line.pieces.last(), &piece.kind) {
+                (None, _) => false,
+                (Some((last, _)), Kind::Word(..)) if matches!(last.kind, Kind::Word(..)) => {
+                    !piece.glued
+                }
+                _ => true,
+            };
+            if breakable {
+                lines.push(end_line(&mut line));
+            }
+            break;
+        }
+        line.pieces.push((piece, width));
+        line.width = line_width(&line.pieces);
+    }
+    if !line.pieces.is_empty() {
+        lines.push(end_line(&mut line));
+    }
+    lines
+}
+
+// Les espaces de fin de ligne ne comptent pas
+fn end_line(line: &mut Line) -> Line {
+    while let Some((
+        Piece {
+            kind: Kind::Space(_),
+            ..
+        },
+        _,
+    )) = line.pieces.last()
+    {
+        line.pieces.pop();
+    }
+    let pieces = std::mem::take(&mut line.pieces);
+    Line {
+        width: line_width(&pieces),
+        pieces,
+        forced_end: std::mem::take(&mut line.forced_end),
+    }
+}
+
+fn line_width(pieces: &[(Piece, f32)]) -> f32 {
+    let end = pieces
+        .iter()
+        .rposition(|(p, _)| !matches!(p.kind, Kind::Space(_)))
+        .map_or(0, |i| i + 1);
+    pieces[..end].iter().map(|(_, w)| w).sum()
+}
+
+// Chasse d'un morceau de texte, espacements compris
+fn text_width(item: &InlineItem, text: &str, fonts: &mut FontCache) -> f32 {
+    let InlineItem::Text { style, .. } = item else {
+        return 0.0;
+    };
+    fonts.text_width(style, &visible(text))
+}
+
+// Les traits d'union conditionnels ne s'affichent qu'en fin de ligne, remplacés par un trait
+fn visible(word: &str) -> String {
+    word.replace(SOFT_HYPHEN, "")
+}
+
+// Coupe le mot au dernier point de césure dont la partie gauche, trait compris, tient dans `room`
+fn hyphenate_to_fit(
+    item: &InlineItem,
+    word: &str,
+    room: f32,
+    fonts: &mut FontCache,
+) -> Option<(String, String)> {
+    let InlineItem::Text { style, .. } = item else {
+        return None;
+    };
+    let hyphens = style.hyphens.unwrap_or_default();
+    if hyphens == Hyphens::None {
+        return None;
+    }
+    // Les traits conditionnels priment sur la césure automatique
+    let mut breaks: Vec<usize> = word
+        .match_indices(SOFT_HYPHEN)
+        .map(|(k, _)| k + SOFT_HYPHEN.len_utf8())
+        .filter(|&k| k < word.len())
+        .collect();
+    if breaks.is_empty()
+        && hyphens == Hyphens::Auto
+        && let Some(lang) = style.lang.as_deref().and_then(Lang::from_tag)
+    {
+        breaks = auto_breaks(word, lang);
+    }
+    breaks.into_iter().rev().find_map(|k| {
+        let head = format!("{}-", word[..k].trim_end_matches(SOFT_HYPHEN));
+        (text_width(item, &head, fonts) <= room).then(|| (head, word[k..].to_string()))
+    })
+}
+
+// Points de césure (en octets) des lettres du mot, ponctuation autour exclue
+fn auto_breaks(word: &str, lang: Lang) -> Vec<usize> {
+    let is_letter = |c: char| c.is_alphabetic();
+    let (Some(start), Some(end)) = (word.find(is_letter), word.rfind(is_letter)) else {
+        return Vec::new();
+    };
+    let end = end + word[end..].chars().next().map_or(0, char::len_utf8);
+    let core = &word[start..end];
+    // Un mot composé ou abrégé (`peut-être`, `etc.`) n'est pas coupé
+    if !core.chars().all(|c| is_letter(c) || c == '\'' || c == '’') {
+        return Vec::new();
+    }
+    let offsets: Vec<usize> = core.char_indices().map(|(k, _)| start + k).collect();
+    hyphenate(core, lang)
+        .into_iter()
+        .map(|i| offsets[i])
+        .collect()
+}
+
+fn relative_offset(style: &Style) -> (f32, f32) {
+    if style.position != Position::Relative {
+        return (0.0, 0.0);
+    }
+    (
+        style.left.as_ref().map_or(0.0, Length::to_pt),
+        style.top.as_ref().map_or(0.0, Length::to_pt),
+    )
+}
+
+pub fn translate(b: &mut LayoutBox, dx: f32, dy: f32) {
+    b.x += dx;
+    b.y += dy;
+    for child in &mut b.children {
+        translate(child, dx, dy);
+    }
+}
@@ -1,2 +1,5 @@
+pub mod bidi;
 pub mod box_model;
-pub mod engine;
\ No newline at end of file
+pub mod engine;
+pub mod hyphenation;
+pub mod inline;
\ No newline at end of file
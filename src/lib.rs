@@ -3,6 +3,8 @@ pub mod css;
 pub mod layout;
 pub mod pdf;
 pub mod image;
+pub mod resources;
+pub mod svg;
 
 #[cfg(test)]
 mod testes;
@@ -10,4 +12,12 @@ mod testes;
 pub use html::parser::parse_html;
 pub use css::parser::{collect_stylesheets, parse_stylesheet};
 pub use layout::engine::compute_layout;
-pub use pdf::writer::write_pdf;
\ No newline at end of file
+pub use layout::hyphenation::{Lang, load_patterns};
+pub use pdf::font_provider::{
+    FileFontProvider, FontProvider, MemoryFontProvider, SystemFontProvider, default_provider,
+};
+pub use pdf::options::{ImageOptions, ImageStats, PdfOptions};
+pub use pdf::writer::{write_pdf, write_pdf_with_options};
+pub use resources::loader::{
+    FileLoader, MemoryLoader, ResourceError, ResourceLoader, default_loader,
+};
\ No newline at end of file
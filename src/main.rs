@@ -1,5 +1,7 @@
 use std::fs;
-use zyou_htmltopdf::{collect_stylesheets, compute_layout, parse_html, write_pdf};
+use zyou_htmltopdf::{
+    collect_stylesheets, compute_layout, default_loader, default_provider, parse_html, write_pdf,
+};
 
 #[derive(Debug)]
 enum PdfError {
@@ -15,10 +17,12 @@ impl From<std::io::Error> for PdfError {
 fn main() -> Result<(), PdfError> {
     let html = fs::read_to_string("input.html")?;
     let dom = parse_html(&html);
-    let sheet = collect_stylesheets(&dom.borrow());
-    let layout = compute_layout(&dom.borrow(), 595.0, 842.0, Some(&sheet));
-    let pdf = write_pdf(&layout);
+    let loader = default_loader();
+    let sheet = collect_stylesheets(&dom.borrow(), &loader);
+    let fonts = default_provider();
+    let layout = compute_layout(&dom.borrow(), 595.0, 842.0, Some(&sheet), &fonts, &loader);
+    let pdf = write_pdf(&layout, Some(&sheet), &fonts, &loader);
     fs::create_dir_all("output")?;
     fs::write("output/output.pdf", pdf)?;
     Ok(())
-}
\ No newline at end of file
+}
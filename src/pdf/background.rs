@@ -0,0 +1,445 @@
+use super::path::RoundedRect;
+use super::writer::{Resources, ensure_alpha, rgb};
+use crate::css::styles::{
+    BackgroundImage, BackgroundSize, BoxArea, Color, ColorStop, GradientDirection, LengthContext,
+    RadialExtent, RadialSize, Repeat,
+};
+use crate::layout::box_model::LayoutBox;
+
+// Au-delà, les tuiles restantes sont ignorées (motif de quelques points répété sur une page)
+const MAX_TILES: usize = 10_000;
+
+// Couleur puis couches d'images, de la dernière (dessous) à la première (dessus)
+pub(crate) fn write_background(
+    b: &LayoutBox,
+    border_box: &RoundedRect,
+    stream: &mut Vec<u8>,
+    res: &mut Resources,
+) {
+    let style = &b.style;
+    let font_size = style.font_size.to_pt();
+    let border = &style.border_width;
+    let padding = &style.padding;
+    let padding_box = border_box.inset(
+        border.top.to_pt(),
+        border.right.to_pt(),
+        border.bottom.to_pt(),
+        border.left.to_pt(),
+    );
+    let content_box = padding_box.inset(
+        padding.top.to_pt(),
+        padding.right.to_pt(),
+        padding.bottom.to_pt(),
+        padding.left.to_pt(),
+    );
+    let area = |a: BoxArea| match a {
+        BoxArea::BorderBox => border_box,
+        BoxArea::PaddingBox => &padding_box,
+        BoxArea::ContentBox => &content_box,
+    };
+    let layers = style.background_image.len();
+
+    if let Some(bg) = style.background.as_ref().filter(|bg| bg.a > 0.0) {
+        let clip = layer(
+            &style.background_clip,
+            layers.max(1) - 1,
+            BoxArea::BorderBox,
+        );
+        stream.extend(b"q\n");
+        if bg.a < 1.0 {
+            stream.extend(format!("/{} gs\n", ensure_alpha(bg.a, &mut res.alphas)).as_bytes());
+        }
+        stream.extend(format!("{} rg\n{}f\nQ\n", rgb(bg), area(clip).path()).as_bytes());
+    }
+
+    for i in (0..layers).rev() {
+        let clip = area(layer(&style.background_clip, i, BoxArea::BorderBox));
+        let origin = area(layer(&style.background_origin, i, BoxArea::PaddingBox));
+        let image = &style.background_image[i];
+        // Une image a une taille intrinsèque (1px = 0,75pt), un dégradé non
+        let (image_name, intrinsic) = match image {
+            BackgroundImage::None => continue,
+            BackgroundImage::Url(src) => match res.image(src) {
+                Some((name, w, h, _)) => (Some(name), Some((w as f32 * 0.75, h as f32 * 0.75))),
+                None => continue,
+            },
+            _ => (None, None),
+        };
+        let (mut tw, mut th) = tile_size(
+            &layer(&style.background_size, i, BackgroundSize::Auto),
+            intrinsic,
+            (origin.width, origin.height),
+            font_size,
+        );
+        let repeat = layer(&style.background_repeat, i, Default::default());
+        if repeat.x == Repeat::Round {
+            tw = origin.width / (origin.width / tw).round().max(1.0);
+        }
+        if repeat.y == Repeat::Round {
+            th = origin.height / (origin.height / th).round().max(1.0);
+        }
+        if tw <= 0.0 || th <= 0.0 || !tw.is_finite() || !th.is_finite() {
+            continue;
+        }
+        if let BackgroundImage::Url(src) = image {
+            res.image_drawn(src, (tw, th));
+        }
+        let paint = match image_name {
+            Some(name) => Paint::Image(name),
+            None => match gradient(image, tw, th, font_size, res) {
+                Some(gradient) => gradient,
+                None => continue,
+            },
+        };
+
+        // Positions en coordonnées CSS (vers le bas) relatives au coin haut-gauche de la zone d'origine
+        let position = layer(&style.background_position, i, Default::default());
+        let px = position.x.resolve(&basis(origin.width - tw, font_size));
+        let py = position.y.resolve(&basis(origin.height - th, font_size));
+        let origin_top = origin.y + origin.height;
+        let xs = tile_starts(
+            repeat.x,
+            px,
+            tw,
+            origin.width,
+            clip.x - origin.x,
+            clip.x + clip.width - origin.x,
+        );
+        let ys = tile_starts(
+            repeat.y,
+            py,
+            th,
+            origin.height,
+            origin_top - (clip.y + clip.height),
+            origin_top - clip.y,
+        );
+
+        stream.extend(format!("q\n{}W n\n", clip.path()).as_bytes());
+        for (y, x) in ys
+            .iter()
+            .flat_map(|y| xs.iter().map(move |x| (y, x)))
+            .take(MAX_TILES)
+        {
+            let (x, y) = (origin.x + x, origin_top - y - th);
+            match &paint {
+                Paint::Image(name) => stream.extend(
+                    format!("q\n{} 0 0 {} {} {} cm\n/{} Do\nQ\n", tw, th, x, y, name).as_bytes(),
+                ),
+                Paint::Shading { name, matrix, mask } => {
+                    stream.extend(
+                        format!(
+                            "q\n{} {} {} {} re W n\n1 0 0 1 {} {} cm\n",
+                            x, y, tw, th, x, y
+                        )
+                        .as_bytes(),
+                    );
+                    if let Some(matrix) = matrix {
+                        stream.extend(format!("{} cm\n", matrix).as_bytes());
+                    }
+                    // Le masque prend le repère courant : il est posé après les transformations
+                    if let Some(mask) = mask {
+                        stream.extend(format!("/{} gs\n", mask).as_bytes());
+                    }
+                    stream.extend(format!("/{} sh\nQ\n", name).as_bytes());
+                }
+            }
+        }
+        stream.extend(b"Q\n");
+    }
+}
+
+enum Paint {
+    Image(String),
+    Shading {
+        name: String,
+        matrix: Option<String>,
+        mask: Option<String>,
+    },
+}
+
+// Valeur de la couche `i` ; les listes plus courtes que background-image sont répétées
+fn layer<T: Clone>(list: &[T], i: usize, default: T) -> T {
+    if list.is_empty() {
+        default
+    } else {
+        list[i % list.len()].clone()
+    }
+}
+
+pub(crate) fn basis(containing_width: f32, font_size: f32) -> LengthContext {
+    LengthContext {
+        font_size,
+        root_font_size: font_size,
+        containing_width,
+        containing_height: None,
+        page_width: 0.0,
+        page_height: 0.0,
+    }
+}
+
+fn tile_size(
+    size: &BackgroundSize,
+    intrinsic: Option<(f32, f32)>,
+    (area_w, area_h): (f32, f32),
+    font_size: f32,
+) -> (f32, f32) {
+    let ratio = intrinsic
+        .filter(|&(w, h)| w > 0.0 && h > 0.0)
+        .map(|(w, h)| w / h);
+    match (size, ratio) {
+        (BackgroundSize::Cover | BackgroundSize::Contain, Some(ratio)) => {
+            let cover = matches!(size, BackgroundSize::Cover);
+            let by_width = (area_w / ratio > area_h) == cover;
+            if by_width {
+                (area_w, area_w / ratio)
+            } else {
+                (area_h * ratio, area_h)
+            }
+        }
+        (BackgroundSize::Cover | BackgroundSize::Contain, None) => (area_w, area_h),
+        (BackgroundSize::Auto, _) => intrinsic.unwrap_or((area_w, area_h)),
+        (BackgroundSize::Explicit(w, h), _) => {
+            let w = w.as_ref().map(|l| l.resolve(&basis(area_w, font_size)));
+            let h = h.as_ref().map(|l| l.resolve(&basis(area_h, font_size)));
+            match (w, h, ratio) {
+                (Some(w), Some(h), _) => (w, h),
+                (Some(w), None, Some(ratio)) => (w, w / ratio),
+                (None, Some(h), Some(ratio)) => (h * ratio, h),
+                (Some(w), None, None) => (w, intrinsic.map_or(area_h, |i| i.1)),
+                (None, Some(h), None) => (intrinsic.map_or(area_w, |i| i.0), h),
+                (None, None, _) => intrinsic.unwrap_or((area_w, area_h)),
+            }
+        }
+    }
+}
+
+// Débuts des tuiles sur un axe, couvrant [lo, hi] (zone de découpe) si l'image se répète
+fn tile_starts(repeat: Repeat, position: f32, tile: f32, area: f32, lo: f32, hi: f32) -> Vec<f32> {
+    let (start, step) = match repeat {
+        Repeat::NoRepeat => return vec![position],
+        Repeat::Space => {
+            let count = (area / tile).floor();
+            if count < 2.0 {
+                return vec![position];
+            }
+            (0.0, tile + (area - count * tile) / (count - 1.0))
+        }
+        Repeat::Repeat | Repeat::Round => (position, tile),
+    };
+    let first = start - ((start - lo) / step).ceil() * step;
+    (0..)
+        .map(|k| first + k as f32 * step)
+        .take_while(|&p| p < hi)
+        .take(MAX_TILES)
+        .collect()
+}
+
+// Dégradé en coordonnées de tuile (origine en bas à gauche)
+fn gradient(
+    image: &BackgroundImage,
+    w: f32,
+    h: f32,
+    font_size: f32,
+    res: &mut Resources,
+) -> Option<Paint> {
+    let (shading_type, coords, stops, matrix) = match image {
+        BackgroundImage::LinearGradient(g) => {
+            let angle = match g.direction {
+                GradientDirection::Angle(deg) => deg.to_radians(),
+                GradientDirection::Corner { right, bottom } => {
+                    let sx = if right { 1.0 } else { -1.0 };
+                    let sy = if bottom { 1.0 } else { -1.0 };
+                    (sx * h).atan2(-sy * w)
+                }
+            };
+            let (sin, cos) = angle.sin_cos();
+            let length = (w * sin).abs() + (h * cos).abs();
+            let stops = resolve_stops(&g.stops, length, font_size);
+            let (t0, t1) = span(&stops);
+            let start = (w / 2.0 - sin * length / 2.0, h / 2.0 - cos * length / 2.0);
+            let coords = [
+                start.0 + sin * t0,
+                start.1 + cos * t0,
+                start.0 + sin * t1,
+                start.1 + cos * t1,
+            ];
+            (2, coords.to_vec(), stops, None)
+        }
+        BackgroundImage::RadialGradient(g) => {
+            let cx = g.position.x.resolve(&basis(w, font_size));
+            let cy_down = g.position.y.resolve(&basis(h, font_size));
+            let (rx, ry) = radial_radii(&g.size, g.circle, (cx, cy_down), (w, h), font_size);
+            if rx <= 0.0 || ry <= 0.0 {
+                return None;
+            }
+            let mut stops = resolve_stops(&g.stops, rx, font_size);
+            for stop in &mut stops {
+                stop.0 = stop.0.max(0.0);
+            }
+            let (r0, r1) = span(&stops);
+            let cy = h - cy_down;
+            // Une ellipse est un cercle de rayon rx écrasé verticalement autour du centre
+            let k = ry / rx;
+            let matrix = ((k - 1.0).abs() > 1e-4).then(|| format!("1 0 0 {} 0 {}", k, cy - k * cy));
+            (3, vec![cx, cy, r0, cx, cy, r1], stops, matrix)
+        }
+        _ => return None,
+    };
+    let (t0, t1) = span(&stops);
+    let normalized: Vec<(f32, Color)> = stops
+        .iter()
+        .map(|(p, c)| ((p - t0) / (t1 - t0), c.clone()))
+        .collect();
+    let coords: Vec<String> = coords.iter().map(|c| c.to_string()).collect();
+    let dict = |color_space: &str, function: String| {
+        format!(
+            "<< /ShadingType {} /ColorSpace /{} /Coords [{}] /Function {} /Extend [true true] >>",
+            shading_type,
+            color_space,
+            coords.join(" "),
+            function
+        )
+    };
+    let colors: Vec<(f32, Vec<f32>)> = normalized
+        .iter()
+        .map(|(t, c)| {
+            (
+                *t,
+                vec![c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0],
+            )
+        })
+        .collect();
+    let name = res.shading(dict("DeviceRGB", function(&colors)));
+    let mask = normalized.iter().any(|(_, c)| c.a < 1.0).then(|| {
+        let alphas: Vec<(f32, Vec<f32>)> =
+            normalized.iter().map(|(t, c)| (*t, vec![c.a])).collect();
+        res.soft_mask(dict("DeviceGray", function(&alphas)))
+    });
+    Some(Paint::Shading { name, matrix, mask })
+}
+
+fn radial_radii(
+    size: &RadialSize,
+    circle: bool,
+    (cx, cy): (f32, f32),
+    (w, h): (f32, f32),
+    font_size: f32,
+) -> (f32, f32) {
+    let (near_x, far_x) = (cx.abs().min((w - cx).abs()), cx.abs().max((w - cx).abs()));
+    let (near_y, far_y) = (cy.abs().min((h - cy).abs()), cy.abs().max((h - cy).abs()));
+    match size {
+        RadialSize::Explicit(rx, ry) => (
+            rx.resolve(&basis(w, font_size)),
+            ry.resolve(&basis(h, font_size)),
+        ),
+        RadialSize::Extent(extent) if circle => {
+            let r = match extent {
+                RadialExtent::ClosestSide => near_x.min(near_y),
+                RadialExtent::FarthestSide => far_x.max(far_y),
+                RadialExtent::ClosestCorner => near_x.hypot(near_y),
+                RadialExtent::FarthestCorner => far_x.hypot(far_y),
+            };
+            (r, r)
+        }
+        // Les coins gardent les proportions du côté correspondant
+        RadialSize::Extent(extent) => match extent {
+            RadialExtent::ClosestSide => (near_x, near_y),
+            RadialExtent::FarthestSide => (far_x, far_y),
+            RadialExtent::ClosestCorner => (near_x * 2f32.sqrt(), near_y * 2f32.sqrt()),
+            RadialExtent::FarthestCorner => (far_x * 2f32.sqrt(), far_y * 2f32.sqrt()),
+        },
+    }
+}
+
+// Positions des arrêts en points le long de la ligne du dégradé (CSS Images §3.5.3)
+fn resolve_stops(stops: &[ColorStop], length: f32, font_size: f32) -> Vec<(f32, Color)> {
+    let mut positions: Vec<Option<f32>> = stops
+        .iter()
+        .map(|s| {
+            s.position
+                .as_ref()
+                .map(|p| p.resolve(&basis(length, font_size)))
+        })
+        .collect();
+    let last = positions.len() - 1;
+    positions[0] = positions[0].or(Some(0.0));
+    positions[last] = positions[last].or(Some(length));
+    let mut max = f32::MIN;
+    for p in positions.iter_mut().flatten() {
+        max = max.max(*p);
+        *p = max;
+    }
+    let mut i = 0;
+    while i < last {
+        let j = (i + 1..=last)
+            .find(|&j| positions[j].is_some())
+            .unwrap_or(last);
+        let (a, b) = (positions[i].unwrap_or(0.0), positions[j].unwrap_or(length));
+        for (n, p) in positions[i + 1..j].iter_mut().enumerate() {
+            *p = Some(a + (b - a) * (n + 1) as f32 / (j - i) as f32);
+        }
+        i = j;
+    }
+    // Interpolation prémultipliée : un arrêt transparent prend la teinte de ses voisins
+    let mut resolved = Vec::new();
+    for (k, (stop, position)) in stops.iter().zip(positions).enumerate() {
+        let position = position.unwrap_or(0.0);
+        if stop.color.a > 0.0 {
+            resolved.push((position, stop.color.clone()));
+            continue;
+        }
+        let visible = |s: &ColorStop| s.color.a > 0.0;
+        let before = stops[..k].iter().rev().find(|s| visible(s));
+        let after = stops[k + 1..].iter().find(|s| visible(s));
+        for neighbour in [before.or(after), after.or(before)] {
+            let color = neighbour.map_or(stop.color.clone(), |n| Color {
+                a: 0.0,
+                ..n.color.clone()
+            });
+            resolved.push((position, color));
+        }
+    }
+    resolved
+}
+
+// Étendue des arrêts, jamais nulle (tous confondus : transition franche)
+fn span(stops: &[(f32, Color)]) -> (f32, f32) {
+    let first = stops.first().map_or(0.0, |s| s.0);
+    let last = stops.last().map_or(0.0, |s| s.0);
+    (first, last.max(first + 0.01))
+}
+
+// Fonction de type 3 assemblant des interpolations linéaires (type 2) entre arrêts
+pub(crate) fn function(stops: &[(f32, Vec<f32>)]) -> String {
+    let values = |v: &[f32]| {
+        v.iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let segment = |a: &[f32], b: &[f32]| {
+        format!(
+            "<< /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >>",
+            values(a),
+            values(b)
+        )
+    };
+    if stops.len() == 2 {
+        return segment(&stops[0].1, &stops[1].1);
+    }
+    let functions: Vec<String> = stops
+        .windows(2)
+        .map(|w| segment(&w[0].1, &w[1].1))
+        .collect();
+    let bounds: Vec<String> = stops[1..stops.len() - 1]
+        .iter()
+        .map(|(t, _)| t.clamp(0.0, 1.0).to_string())
+        .collect();
+    let encode = vec!["0 1"; functions.len()];
+    format!(
+        "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+        functions.join(" "),
+        bounds.join(" "),
+        encode.join(" ")
+    )
+}
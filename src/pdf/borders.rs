@@ -0,0 +1,186 @@
+use super::path::{RoundedRect, polygon};
+use super::writer::{ensure_alpha, rgb};
+use crate::css::styles::{BorderStyle, Color};
+use crate::layout::box_model::LayoutBox;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+// Trace les quatre côtés de la bordure à l'intérieur de `outer` (la boîte de bordure)
+pub fn write_borders(
+    b: &LayoutBox,
+    outer: &RoundedRect,
+    stream: &mut Vec<u8>,
+    alphas: &mut Vec<(f32, String)>,
+) {
+    let style = &b.style;
+    let widths = [
+        style.border_width.top.to_pt(),
+        style.border_width.right.to_pt(),
+        style.border_width.bottom.to_pt(),
+        style.border_width.left.to_pt(),
+    ];
+    if widths.iter().all(|&w| w <= 0.0) {
+        return;
+    }
+    let current = style.color.clone().unwrap_or_default();
+    let sides = [
+        (Side::Top, style.border_style.top, &style.border_color.top),
+        (
+            Side::Right,
+            style.border_style.right,
+            &style.border_color.right,
+        ),
+        (
+            Side::Bottom,
+            style.border_style.bottom,
+            &style.border_color.bottom,
+        ),
+        (
+            Side::Left,
+            style.border_style.left,
+            &style.border_color.left,
+        ),
+    ];
+    for (i, (side, border_style, color)) in sides.into_iter().enumerate() {
+        if widths[i] <= 0.0 || !border_style.is_visible() {
+            continue;
+        }
+        let color = color.clone().unwrap_or_else(|| current.clone());
+        if color.a <= 0.0 {
+            continue;
+        }
+        let clip = side_region(outer, widths, side);
+        stream.extend(b"q\n");
+        if color.a < 1.0 {
+            stream.extend(format!("/{} gs\n", ensure_alpha(color.a, alphas)).as_bytes());
+        }
+        stream.extend(format!("{}W n\n", clip).as_bytes());
+        match border_style {
+            BorderStyle::Dashed | BorderStyle::Dotted => {
+                let w = widths[i];
+                let center = outer.inset(
+                    widths[0] / 2.0,
+                    widths[1] / 2.0,
+                    widths[2] / 2.0,
+                    widths[3] / 2.0,
+                );
+                let dash = if border_style == BorderStyle::Dotted {
+                    format!("1 J [0 {}] 0 d", 2.0 * w)
+                } else {
+                    format!("0 J [{} {}] 0 d", 3.0 * w, 3.0 * w)
+                };
+                stream.extend(
+                    format!(
+                        "{} RG\n{} w\n{}\n{}S\n",
+                        rgb(&color),
+                        w,
+                        dash,
+                        center.path()
+                    )
+                    .as_bytes(),
+                );
+            }
+            _ => {
+                for (from, to, shade) in bands(border_style, side) {
+                    let band_outer = inset_fraction(outer, widths, from);
+                    let band_inner = inset_fraction(outer, widths, to);
+                    stream.extend(
+                        format!(
+                            "{} rg\n{}{}f*\n",
+                            rgb(&shaded(&color, shade)),
+                            band_outer.path(),
+                            band_inner.path()
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+        }
+        stream.extend(b"Q\n");
+    }
+}
+
+// Bandes (début, fin, teinte) en fraction de l'épaisseur, depuis le bord extérieur
+fn bands(style: BorderStyle, side: Side) -> Vec<(f32, f32, Shade)> {
+    let top_left = matches!(side, Side::Top | Side::Left);
+    let (first, second) = if top_left {
+        (Shade::Dark, Shade::Light)
+    } else {
+        (Shade::Light, Shade::Dark)
+    };
+    match style {
+        BorderStyle::Double => vec![
+            (0.0, 1.0 / 3.0, Shade::Normal),
+            (2.0 / 3.0, 1.0, Shade::Normal),
+        ],
+        BorderStyle::Groove => vec![(0.0, 0.5, first), (0.5, 1.0, second)],
+        BorderStyle::Ridge => vec![(0.0, 0.5, second), (0.5, 1.0, first)],
+        BorderStyle::Inset => vec![(0.0, 1.0, first)],
+        BorderStyle::Outset => vec![(0.0, 1.0, second)],
+        _ => vec![(0.0, 1.0, Shade::Normal)],
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Shade {
+    Normal,
+    Dark,
+    Light,
+}
+
+fn shaded(color: &Color, shade: Shade) -> Color {
+    let factor = match shade {
+        Shade::Normal | Shade::Light => return color.clone(),
+        Shade::Dark => 0.5,
+    };
+    Color {
+        r: (color.r as f32 * factor) as u8,
+        g: (color.g as f32 * factor) as u8,
+        b: (color.b as f32 * factor) as u8,
+        a: color.a,
+    }
+}
+
+fn inset_fraction(outer: &RoundedRect, widths: [f32; 4], fraction: f32) -> RoundedRect {
+    outer.inset(
+        widths[0] * fraction,
+        widths[1] * fraction,
+        widths[2] * fraction,
+        widths[3] * fraction,
+    )
+}
+
+// Zone du côté : du bord extérieur jusqu'aux diagonales passant par les coins intérieurs
+fn side_region(outer: &RoundedRect, widths: [f32; 4], side: Side) -> String {
+    let [wt, wr, wb, wl] = widths;
+    let (x0, y0) = (outer.x, outer.y);
+    let (x1, y1) = (outer.x + outer.width, outer.y + outer.height);
+    let (half_w, half_h) = (outer.width / 2.0, outer.height / 2.0);
+    // Prolonge la diagonale coin extérieur -> coin intérieur jusqu'au milieu de la boîte
+    let reach = |dx: f32, dy: f32| {
+        let tx = if dx > 0.0 { half_w / dx } else { f32::INFINITY };
+        let ty = if dy > 0.0 { half_h / dy } else { f32::INFINITY };
+        let t = tx.min(ty);
+        if t.is_finite() { t } else { 0.0 }
+    };
+    let t_tl = reach(wl, wt);
+    let t_tr = reach(wr, wt);
+    let t_br = reach(wr, wb);
+    let t_bl = reach(wl, wb);
+    let tl = (x0 + wl * t_tl, y1 - wt * t_tl);
+    let tr = (x1 - wr * t_tr, y1 - wt * t_tr);
+    let br = (x1 - wr * t_br, y0 + wb * t_br);
+    let bl = (x0 + wl * t_bl, y0 + wb * t_bl);
+    match side {
+        Side::Top => polygon(&[(x0, y1), (x1, y1), tr, tl]),
+        Side::Right => polygon(&[(x1, y1), (x1, y0), br, tr]),
+        Side::Bottom => polygon(&[(x1, y0), (x0, y0), bl, br]),
+        Side::Left => polygon(&[(x0, y0), (x0, y1), tl, bl]),
+    }
+}
@@ -0,0 +1,162 @@
+use super::fonts::extract_font;
+use font_kit::family_name::FamilyName;
+use font_kit::handle::Handle;
+use font_kit::properties::{Properties, Style as FaceStyle, Weight};
+use font_kit::source::SystemSource;
+use std::fs;
+use std::path::PathBuf;
+
+// Source des polices nommées : octets d'un fichier TrueType, OpenType, WOFF ou WOFF2 pour
+// une famille, une graisse (100 à 900) et un style. Les familles génériques arrivent sous
+// leur nom CSS (`cursive`, `system-ui`…) ; les polices standard du PDF ne passent pas ici.
+pub trait FontProvider {
+    fn font(&self, family: &str, weight: u16, italic: bool) -> Option<Vec<u8>>;
+
+    // Familles à essayer pour un caractère qu'aucune police demandée n'a
+    fn families(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// Premier fournisseur qui connaît la famille
+impl FontProvider for Vec<Box<dyn FontProvider>> {
+    fn font(&self, family: &str, weight: u16, italic: bool) -> Option<Vec<u8>> {
+        self.iter()
+            .find_map(|provider| provider.font(family, weight, italic))
+    }
+
+    fn families(&self) -> Vec<String> {
+        self.iter()
+            .flat_map(|provider| provider.families())
+            .collect()
+    }
+}
+
+// Dossier `fonts/` puis polices installées
+pub fn default_provider() -> Vec<Box<dyn FontProvider>> {
+    vec![
+        Box::new(FileFontProvider::new("fonts")),
+        Box::new(SystemFontProvider),
+    ]
+}
+
+// Fichiers `{famille}{-Bold,-Italic…}.{ttf,otf,woff,woff2}` d'un dossier
+pub struct FileFontProvider {
+    dir: PathBuf,
+}
+
+impl FileFontProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileFontProvider { dir: dir.into() }
+    }
+}
+
+impl FontProvider for FileFontProvider {
+    fn font(&self, family: &str, weight: u16, italic: bool) -> Option<Vec<u8>> {
+        let suffixes: &[&str] = match (weight >= 600, italic) {
+            (true, true) => &["-BoldItalic", "-BoldOblique", "-Bold", "-Italic", ""],
+            (true, false) => &["-Bold", ""],
+            (false, true) => &["-Italic", "-Oblique", ""],
+            (false, false) => &["-Regular", ""],
+        };
+        suffixes.iter().find_map(|suffix| {
+            ["ttf", "otf", "woff", "woff2"]
+                .iter()
+                .find_map(|extension| {
+                    fs::read(self.dir.join(format!("{}{}.{}", family, suffix, extension))).ok()
+                })
+        })
+    }
+}
+
+// Polices fournies par l'application, choisies comme les règles `@font-face`
+#[derive(Default)]
+pub struct MemoryFontProvider {
+    faces: Vec<(String, u16, bool, Vec<u8>)>,
+}
+
+impl MemoryFontProvider {
+    pub fn add(&mut self, family: &str, weight: u16, italic: bool, data: Vec<u8>) {
+        self.faces.push((family.to_string(), weight, italic, data));
+    }
+}
+
+impl FontProvider for MemoryFontProvider {
+    fn font(&self, family: &str, weight: u16, italic: bool) -> Option<Vec<u8>> {
+        self.faces
+            .iter()
+            .rev()
+            .filter(|face| face.0.eq_ignore_ascii_case(family))
+            .min_by_key(|face| match_score((face.1, face.1), face.2, weight, italic))
+            .map(|face| face.3.clone())
+    }
+
+    fn families(&self) -> Vec<String> {
+        let mut families: Vec<String> = Vec::new();
+        for (family, ..) in &self.faces {
+            if !families.contains(family) {
+                families.push(family.clone());
+            }
+        }
+        families
+    }
+}
+
+// Écart d'une face à la demande : style d'abord, puis distance de graisse
+pub fn match_score(
+    range: (u16, u16),
+    italic: bool,
+    weight: u16,
+    wanted_italic: bool,
+) -> (u16, u16) {
+    let distance = match weight {
+        w if w < range.0 => range.0 - w,
+        w if w > range.1 => w - range.1,
+        _ => 0,
+    };
+    ((italic != wanted_italic) as u16, distance)
+}
+
+// Polices installées, par nom de famille puis par nom PostScript (`local()`)
+pub struct SystemFontProvider;
+
+thread_local! {
+    static SYSTEM: SystemSource = SystemSource::new();
+}
+
+impl FontProvider for SystemFontProvider {
+    fn font(&self, family: &str, weight: u16, italic: bool) -> Option<Vec<u8>> {
+        let name = match family.to_ascii_lowercase().as_str() {
+            "serif" => FamilyName::Serif,
+            "sans-serif" | "system-ui" => FamilyName::SansSerif,
+            "monospace" => FamilyName::Monospace,
+            "cursive" => FamilyName::Cursive,
+            "fantasy" => FamilyName::Fantasy,
+            _ => FamilyName::Title(family.to_string()),
+        };
+        let mut properties = Properties::new();
+        properties.weight(Weight(weight as f32));
+        if italic {
+            properties.style(FaceStyle::Italic);
+        }
+        let handle = SYSTEM
+            .with(|system| system.select_best_match(&[name], &properties))
+            .or_else(|_| SYSTEM.with(|system| system.select_by_postscript_name(family)))
+            .ok()?;
+        let (data, index) = match handle {
+            Handle::Path { path, font_index } => (fs::read(&path).ok()?, font_index),
+            Handle::Memory { bytes, font_index } => (bytes.to_vec(), font_index),
+        };
+        if data.starts_with(b"ttcf") {
+            extract_font(&data, index)
+        } else {
+            Some(data)
+        }
+    }
+
+    fn families(&self) -> Vec<String> {
+        SYSTEM
+            .with(|system| system.all_families())
+            .unwrap_or_default()
+    }
+}
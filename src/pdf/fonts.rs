@@ -1,42 +1,588 @@
-use std::fs;
+use super::font_provider::{FontProvider, match_score};
+use super::shaping::{Glyph, features, shape};
+use super::standard_fonts::{standard_face, standard_metrics, standard_widths};
+use super::woff;
+use crate::css::styles::{FontFace, FontFamily, FontSource, FontStyle, FontWeight, Length, Style};
+use crate::resources::loader::ResourceLoader;
+use font_kit::properties::Style as FaceStyle;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory};
 
 #[derive(Debug, Clone)]
 pub struct Font {
-    pub name: String,
-    pub data: Option<Vec<u8>>,
-    pub size: f32,
+    pub name: String,          // nom PostScript
+    pub data: Option<Vec<u8>>, // None : police standard, non incorporée
+    pub metrics: FontMetrics,
+    pub widths: Vec<u16>, // codes WinAnsi 32 à 255
+    pub bold: bool,       // graisse et style réels de la face
+    pub italic: bool,
+    pub glyphs: BTreeMap<u16, (f32, String)>, // glyphes affichés : chasse et texte (ToUnicode)
+    pub unicode_range: Vec<(u32, u32)>,       // `@font-face` ; vide : tous les caractères
+    pub coverage: OnceLock<Vec<(u32, u32)>>,  // caractères du cmap, lus au premier besoin
+}
+
+// En millièmes de em, ordonnées vers le haut depuis la ligne de base
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub cap_height: f32,
+    pub x_height: f32,
+    pub underline_position: f32, // milieu du trait
+    pub underline_thickness: f32,
+    pub italic_angle: f32,
 }
 
 #[derive(Debug)]
 pub enum FontError {
-    IoError(std::io::Error),
-    InvalidPath(String),
+    InvalidFont(String),
 }
 
-pub fn default_font() -> Font {
-    Font {
-        name: "Helvetica".to_string(),
-        data: None,
-        size: 12.0,
+impl Font {
+    pub fn standard(name: &str) -> Font {
+        Font {
+            name: name.to_string(),
+            data: None,
+            metrics: standard_metrics(name),
+            widths: standard_widths(name),
+            bold: name.contains("Bold"),
+            italic: name.contains("Italic") || name.contains("Oblique"),
+            glyphs: BTreeMap::new(),
+            unicode_range: Vec::new(),
+            coverage: OnceLock::new(),
+        }
+    }
+
+    // Chasse du texte encodé en WinAnsi, en points
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = text
+            .chars()
+            .map(|ch| match win_ansi(ch).unwrap_or(b'?') {
+                code @ 32.. => self.widths[(code - 32) as usize] as u32,
+                _ => 0,
+            })
+            .sum();
+        units as f32 * size / 1000.0
     }
+
+    // Une police standard n'a que les caractères WinAnsi
+    pub fn has_glyph(&self, ch: char) -> bool {
+        let code = ch as u32;
+        if !self.unicode_range.is_empty()
+            && !self
+                .unicode_range
+                .iter()
+                .any(|&(a, b)| (a..=b).contains(&code))
+        {
+            return false;
+        }
+        match &self.data {
+            None => win_ansi(ch).is_some(),
+            Some(data) => covers(self.coverage.get_or_init(|| coverage(data)), code),
+        }
+    }
+}
+
+fn covers(ranges: &[(u32, u32)], code: u32) -> bool {
+    ranges
+        .binary_search_by(|&(a, b)| {
+            if b < code {
+                Ordering::Less
+            } else if a > code {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .is_ok()
 }
 
-pub fn load_font(path: &str, size: f32) -> Result<Font, FontError> {
-    if path.is_empty() {
-        return Err(FontError::InvalidPath("Le chemin du fichier de police est vide".to_string()));
+// Familles essayées en premier pour un caractère manquant, avant celles du fournisseur
+const FALLBACK_FAMILIES: [&str; 4] = ["system-ui", "sans-serif", "serif", "monospace"];
+
+// Faces chargées ; une même face peut répondre à plusieurs familles
+pub struct FontCache<'a> {
+    keys: BTreeMap<String, Option<usize>>, // famille, graisse et style ; None : introuvable
+    faces: Vec<Font>,
+    uncovered: BTreeSet<char>, // caractères qu'aucune police du fournisseur n'a
+    fallbacks: Option<Vec<String>>,
+    // Couverture des familles de repli essayées, sans garder leurs octets
+    coverages: BTreeMap<String, Option<Vec<(u32, u32)>>>,
+    used: BTreeSet<usize>, // faces écrites dans le document
+    font_faces: Vec<FontFace>,
+    declared: BTreeMap<String, Vec<usize>>, // famille `@font-face`, graisse et style
+    provider: &'a dyn FontProvider,
+    loader: &'a dyn ResourceLoader,
+}
+
+impl<'a> FontCache<'a> {
+    // Les familles déclarées par `@font-face` passent avant celles du fournisseur
+    pub fn new(
+        font_faces: &[FontFace],
+        provider: &'a dyn FontProvider,
+        loader: &'a dyn ResourceLoader,
+    ) -> Self {
+        FontCache {
+            keys: BTreeMap::new(),
+            faces: Vec::new(),
+            uncovered: BTreeSet::new(),
+            fallbacks: None,
+            coverages: BTreeMap::new(),
+            used: BTreeSet::new(),
+            font_faces: font_faces.to_vec(),
+            declared: BTreeMap::new(),
+            provider,
+            loader,
+        }
+    }
+
+    // Indice stable de la face, si la famille existe
+    pub fn face(&mut self, family: &FontFamily, weight: u16, italic: bool) -> Option<usize> {
+        let key = format!("{:?}:{}:{}", family, weight, italic);
+        if let Some(index) = self.keys.get(&key) {
+            return *index;
+        }
+        let index =
+            select_font(family, weight, italic, self.provider).map(|font| self.insert(font));
+        self.keys.insert(key, index);
+        index
+    }
+
+    fn insert(&mut self, font: Font) -> usize {
+        let same = |f: &Font| {
+            f.name == font.name
+                && f.bold == font.bold
+                && f.italic == font.italic
+                && f.unicode_range == font.unicode_range
+                && f.data == font.data
+        };
+        match self.faces.iter().position(same) {
+            Some(i) => i,
+            None => {
+                self.faces.push(font);
+                self.faces.len() - 1
+            }
+        }
+    }
+
+    // Faces `@font-face` de la famille : pour chaque plage Unicode, la règle la mieux
+    // assortie en style puis en graisse, la dernière déclarée à égalité
+    fn declared(&mut self, name: &str, wanted: u16, italic: bool) -> Vec<usize> {
+        let key = format!("{}:{}:{}", name.to_lowercase(), wanted, italic);
+        if let Some(indices) = self.declared.get(&key) {
+            return indices.clone();
+        }
+        let mut best: Vec<&FontFace> = Vec::new();
+        for face in self
+            .font_faces
+            .iter()
+            .filter(|f| f.family.eq_ignore_ascii_case(name))
+        {
+            let score = |f: &FontFace| match_score(f.weight, f.italic, wanted, italic);
+            match best
+                .iter_mut()
+                .find(|b| b.unicode_range == face.unicode_range)
+            {
+                Some(b) if score(face) <= score(b) => *b = face,
+                Some(_) => {}
+                None => best.push(face),
+            }
+        }
+        let fonts: Vec<Font> = best
+            .into_iter()
+            .filter_map(|face| {
+                let mut font = face.sources.iter().find_map(|source| match source {
+                    FontSource::Local(name) => {
+                        let data = self.provider.font(name, wanted, italic)?;
+                        font_from_bytes(data, name)
+                    }
+                    FontSource::Url(url) => url_font(url, self.loader),
+                })?;
+                // Graisse retenue dans la plage de la règle : grasse ou non
+                font.bold = wanted.clamp(face.weight.0, face.weight.1) >= 600;
+                font.italic = face.italic;
+                font.unicode_range = face.unicode_range.clone();
+                Some(font)
+            })
+            .collect();
+        let indices: Vec<usize> = fonts.into_iter().map(|font| self.insert(font)).collect();
+        self.declared.insert(key, indices.clone());
+        indices
+    }
+
+    pub fn font(&self, index: usize) -> &Font {
+        &self.faces[index]
+    }
+
+    pub fn provider(&self) -> &'a dyn FontProvider {
+        self.provider
+    }
+
+    // Faces de `font-family` trouvées, dans l'ordre ; Helvetica si aucune
+    fn chain(&mut self, style: &Style) -> Vec<usize> {
+        let (weight, italic) = weight_and_style(style);
+        let families = style.font_family.as_deref().unwrap_or_default();
+        let mut chain = Vec::new();
+        for family in families {
+            if let FontFamily::Named(name) = family {
+                let declared = self.declared(name, weight, italic);
+                if !declared.is_empty() {
+                    chain.extend(declared);
+                    continue;
+                }
+            }
+            chain.extend(self.face(family, weight, italic));
+        }
+        if chain.is_empty() {
+            let helvetica = FontFamily::Named("Helvetica".to_string());
+            chain.extend(self.face(&helvetica, weight, italic));
+        }
+        chain
+    }
+
+    // Première face disponible : ses métriques valent pour tout le texte
+    pub fn for_style(&mut self, style: &Style) -> (usize, &Font) {
+        let index = self.chain(style)[0];
+        (index, &self.faces[index])
+    }
+
+    // Suites de caractères servies par une même face : la première de la liste qui a le
+    // glyphe, sinon une police du système ; les marques restent si possible avec leur base
+    pub fn runs(&mut self, style: &Style, text: &str) -> Vec<(usize, Range<usize>)> {
+        let chain = self.chain(style);
+        let (weight, italic) = weight_and_style(style);
+        let mut runs: Vec<(usize, Range<usize>)> = Vec::new();
+        for (i, ch) in text.char_indices() {
+            let end = i + ch.len_utf8();
+            if let Some((k, range)) = runs.last_mut()
+                && joins_previous(ch)
+                && self.faces[*k].has_glyph(ch)
+            {
+                range.end = end;
+                continue;
+            }
+            let index = chain
+                .iter()
+                .copied()
+                .find(|&k| self.faces[k].has_glyph(ch))
+                .or_else(|| self.fallback(ch, weight, italic))
+                .unwrap_or(chain[0]);
+            match runs.last_mut() {
+                Some((k, range)) if *k == index => range.end = end,
+                _ => runs.push((index, i..end)),
+            }
+        }
+        runs
+    }
+
+    // Police du fournisseur ayant le caractère : les familles génériques, puis les autres
+    // familles qu'il connaît. Seule la couverture des candidates est gardée ; la face n'est
+    // chargée que pour la famille retenue
+    fn fallback(&mut self, ch: char, weight: u16, italic: bool) -> Option<usize> {
+        if ch.is_whitespace() || ch.is_control() || self.uncovered.contains(&ch) {
+            return None;
+        }
+        let provider = self.provider;
+        let families = self
+            .fallbacks
+            .get_or_insert_with(|| {
+                let mut families: Vec<String> =
+                    FALLBACK_FAMILIES.iter().map(|f| f.to_string()).collect();
+                for family in provider.families() {
+                    if !families.contains(&family) {
+                        families.push(family);
+                    }
+                }
+                families
+            })
+            .clone();
+        for family in &families {
+            let key = format!("{}:{}:{}", family, weight, italic);
+            let ranges = self.coverages.entry(key.clone()).or_insert_with(|| {
+                let data = sfnt(provider.font(family, weight, italic)?)?;
+                Some(coverage(&data))
+            });
+            if !ranges.as_deref().is_some_and(|r| covers(r, ch as u32)) {
+                continue;
+            }
+            let key = format!("fallback:{}", key);
+            let index = match self.keys.get(&key) {
+                Some(index) => *index,
+                None => {
+                    let font = provider
+                        .font(family, weight, italic)
+                        .and_then(|data| font_from_bytes(data, family));
+                    let index = font.map(|font| self.insert(font));
+                    self.keys.insert(key, index);
+                    index
+                }
+            };
+            if index.is_some() {
+                return index;
+            }
+        }
+        self.uncovered.insert(ch);
+        None
+    }
+
+    // Chasse du texte, espacements compris, en points ; mis en forme si la police le permet
+    pub fn text_width(&mut self, style: &Style, text: &str) -> f32 {
+        let size = style.font_size.to_pt();
+        let letter_spacing = style.letter_spacing.as_ref().map_or(0.0, Length::to_pt);
+        let word_spacing = style.word_spacing.as_ref().map_or(0.0, Length::to_pt);
+        let features = features(style);
+        let mut width = 0.0;
+        for (index, range) in self.runs(style, text) {
+            let run = &text[range];
+            let font = &self.faces[index];
+            let (advance, count, spaces) = match shape(font, run, None, &features) {
+                Some(glyphs) => (
+                    glyphs.iter().map(|g| g.advance).sum::<f32>() * size / 1000.0,
+                    glyphs.len(),
+                    glyphs.iter().filter(|g| g.text == " ").count(),
+                ),
+                None => (
+                    font.text_width(run, size),
+                    run.chars().count(),
+                    run.chars().filter(|c| *c == ' ').count(),
+                ),
+            };
+            width += advance + letter_spacing * count as f32 + word_spacing * spaces as f32;
+        }
+        width
+    }
+
+    // Glyphes écrits avec la face `index`, à déclarer dans la police du PDF
+    pub fn record(&mut self, index: usize, glyphs: &[Glyph]) {
+        self.used.insert(index);
+        let used = &mut self.faces[index].glyphs;
+        for glyph in glyphs {
+            let entry = used.entry(glyph.id).or_insert((glyph.width, String::new()));
+            if entry.1.is_empty() {
+                entry.1 = glyph.text.clone();
+            }
+        }
+    }
+
+    // Faces écrites, avec leur indice
+    pub fn fonts(&self) -> impl Iterator<Item = (usize, &Font)> {
+        self.used.iter().map(|&i| (i, &self.faces[i]))
     }
+}
+
+fn weight_and_style(style: &Style) -> (u16, bool) {
+    let weight = style.font_weight.map_or(400, FontWeight::value);
+    let italic = style.font_style.is_some_and(|s| s != FontStyle::Normal);
+    (weight, italic)
+}
+
+// Marques combinantes, liants et sélecteurs de variante
+fn joins_previous(ch: char) -> bool {
+    ch.general_category_group() == GeneralCategoryGroup::Mark
+        || matches!(ch, '\u{200C}' | '\u{200D}' | '\u{FE00}'..='\u{FE0F}')
+}
 
-    let data = fs::read(path).map_err(FontError::IoError)?;
-    let name = path
-        .split('/')
-        .last()
-        .and_then(|s| s.strip_suffix(".ttf").or_else(|| s.strip_suffix(".otf")))
-        .ok_or_else(|| FontError::InvalidPath("Nom de fichier invalide".to_string()))?
-        .to_string();
+// Police TrueType ou OpenType seule (pas une collection) ; `stem` sert de nom à défaut
+pub fn font_from_data(data: Vec<u8>, stem: &str) -> Result<Font, FontError> {
+    let face = font_kit::font::Font::from_bytes(Arc::new(data.clone()), 0)
+        .map_err(|e| FontError::InvalidFont(format!("{:?}", e)))?;
+    let m = face.metrics();
+    let scale = 1000.0 / m.units_per_em as f32;
+    let ascent = m.ascent * scale;
+    let metrics = FontMetrics {
+        ascent,
+        descent: m.descent * scale,
+        cap_height: if m.cap_height > 0.0 {
+            m.cap_height * scale
+        } else {
+            ascent
+        },
+        x_height: if m.x_height > 0.0 {
+            m.x_height * scale
+        } else {
+            ascent / 2.0
+        },
+        // font-kit donne le haut du trait
+        underline_position: (m.underline_position - m.underline_thickness / 2.0) * scale,
+        underline_thickness: m.underline_thickness.max(1.0) * scale,
+        italic_angle: 0.0,
+    };
+    let widths = (32..=255u8)
+        .map(|code| {
+            face.glyph_for_char(win_ansi_char(code))
+                .and_then(|glyph| face.advance(glyph).ok())
+                .map_or(0, |advance| (advance.x() * scale).round() as u16)
+        })
+        .collect();
+    let properties = face.properties();
+    let italic = properties.style != FaceStyle::Normal;
+    // Les noms PDF n'admettent ni espaces ni délimiteurs
+    let name: String = face
+        .postscript_name()
+        .unwrap_or_else(|| stem.to_string())
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
 
     Ok(Font {
         name,
         data: Some(data),
-        size,
+        metrics: FontMetrics {
+            italic_angle: if italic { -12.0 } else { 0.0 },
+            ..metrics
+        },
+        widths,
+        bold: properties.weight.0 >= 600.0,
+        italic,
+        glyphs: BTreeMap::new(),
+        unicode_range: Vec::new(),
+        coverage: OnceLock::new(),
     })
-}
\ No newline at end of file
+}
+
+// Caractères des tables cmap Unicode, lus une fois pour toutes
+fn coverage(data: &[u8]) -> Vec<(u32, u32)> {
+    let Ok(face) = rustybuzz::ttf_parser::Face::parse(data, 0) else {
+        return Vec::new();
+    };
+    let subtables = face.tables().cmap.map(|cmap| cmap.subtables);
+    // Un caractère déjà couvert n'est pas recherché dans les tables suivantes
+    let mut codes = BTreeSet::new();
+    for subtable in subtables.into_iter().flatten() {
+        if subtable.is_unicode() {
+            subtable.codepoints(|code| {
+                if !codes.contains(&code) && subtable.glyph_index(code).is_some() {
+                    codes.insert(code);
+                }
+            });
+        }
+    }
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for code in codes {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == code => *end = code,
+            _ => ranges.push((code, code)),
+        }
+    }
+    ranges
+}
+
+// Famille standard du PDF, sinon demandée au fournisseur
+pub fn select_font(
+    family: &FontFamily,
+    weight: u16,
+    italic: bool,
+    provider: &dyn FontProvider,
+) -> Option<Font> {
+    let name = match family {
+        FontFamily::Named(name) => name.as_str(),
+        FontFamily::Serif => "Times",
+        FontFamily::SansSerif => "Helvetica",
+        FontFamily::Monospace => "Courier",
+        FontFamily::Cursive => "cursive",
+        FontFamily::Fantasy => "fantasy",
+        FontFamily::SystemUi => "system-ui",
+    };
+    if let Some(name) = standard_face(name, weight >= 600, italic) {
+        return Some(Font::standard(name));
+    }
+    let data = provider.font(name, weight, italic)?;
+    font_from_bytes(data, name)
+}
+
+fn url_font(url: &str, loader: &dyn ResourceLoader) -> Option<Font> {
+    let data = loader.load(url).ok()?;
+    let stem = url.rsplit('/').next().and_then(|s| s.split('.').next());
+    font_from_bytes(data, stem.unwrap_or_default())
+}
+
+// WOFF et WOFF2 décompressés, première police d'une collection
+fn font_from_bytes(data: Vec<u8>, stem: &str) -> Option<Font> {
+    font_from_data(sfnt(data)?, stem).ok()
+}
+
+fn sfnt(data: Vec<u8>) -> Option<Vec<u8>> {
+    let data = woff::decode(data)?;
+    if data.starts_with(b"ttcf") {
+        extract_font(&data, 0)
+    } else {
+        Some(data)
+    }
+}
+
+// Police `index` d'une collection (.ttc), recopiée en fichier autonome : répertoire
+// des tables puis tables alignées sur quatre octets
+pub fn extract_font(data: &[u8], index: u32) -> Option<Vec<u8>> {
+    let u16_at = |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let u32_at = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let start = u32_at(12 + 4 * index as usize)? as usize;
+    let count = u16_at(start + 4)? as usize;
+    let mut out = data.get(start..start + 12)?.to_vec();
+    let mut tables = Vec::new();
+    let mut offset = 12 + 16 * count;
+    for k in 0..count {
+        let record = start + 12 + 16 * k;
+        let table_offset = u32_at(record + 8)? as usize;
+        let length = u32_at(record + 12)? as usize;
+        out.extend(data.get(record..record + 8)?);
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((length as u32).to_be_bytes());
+        let mut table = data.get(table_offset..table_offset + length)?.to_vec();
+        table.resize(length.div_ceil(4) * 4, 0);
+        offset += table.len();
+        tables.push(table);
+    }
+    out.extend(tables.concat());
+    Some(out)
+}
+
+const WIN_ANSI_HIGH: [(u8, char); 27] = [
+    (0x80, '€'),
+    (0x82, '‚'),
+    (0x83, 'ƒ'),
+    (0x84, '„'),
+    (0x85, '…'),
+    (0x86, '†'),
+    (0x87, '‡'),
+    (0x88, 'ˆ'),
+    (0x89, '‰'),
+    (0x8A, 'Š'),
+    (0x8B, '‹'),
+    (0x8C, 'Œ'),
+    (0x8E, 'Ž'),
+    (0x91, '‘'),
+    (0x92, '’'),
+    (0x93, '“'),
+    (0x94, '”'),
+    (0x95, '•'),
+    (0x96, '–'),
+    (0x97, '—'),
+    (0x98, '˜'),
+    (0x99, '™'),
+    (0x9A, 'š'),
+    (0x9B, '›'),
+    (0x9C, 'œ'),
+    (0x9E, 'ž'),
+    (0x9F, 'Ÿ'),
+];
+
+// Code WinAnsi d'un caractère, s'il en a un
+pub fn win_ansi(ch: char) -> Option<u8> {
+    match ch as u32 {
+        0..=0x7F | 0xA0..=0xFF => Some(ch as u8),
+        _ => WIN_ANSI_HIGH
+            .iter()
+            .find(|(_, c)| *c == ch)
+            .map(|(code, _)| *code),
+    }
+}
+
+fn win_ansi_char(code: u8) -> char {
+    WIN_ANSI_HIGH
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(code as char, |(_, ch)| *ch)
+}
@@ -1,12 +1,159 @@
-use crate::image::decoder::Image;
-
-pub fn embed_image(image: &Image, obj_id: usize) -> String {
-    format!(
-        "{} 0 obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Length {} >>\nstream\n{}\nendstream\nendobj\n",
-        obj_id,
-        image.width,
-        image.height,
-        image.data.len(),
-        String::from_utf8_lossy(&image.data)
+use super::background::basis;
+use crate::css::styles::{BackgroundPosition, Length, ObjectFit, Style};
+use crate::image::decoder::{ColorSpace, Image};
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use std::io::Write;
+
+// Flux écrits d'une image et de son masque : le JPEG tel quel, le reste compressé
+pub type ImageStreams = (Vec<u8>, Option<Vec<u8>>);
+
+pub fn image_streams(image: &Image) -> ImageStreams {
+    let data = match image.jpeg {
+        true => image.data.clone(),
+        false => deflate(&image.data),
+    };
+    (data, image.alpha.as_deref().map(deflate))
+}
+
+pub(crate) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // L'écriture en mémoire n'échoue pas
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+// Objets de l'image : l'image elle-même (`obj_id`), son masque d'opacité éventuel puis son
+// profil ICC éventuel
+pub fn embed_image(image: &Image, (data, alpha): &ImageStreams, obj_id: usize) -> Vec<Vec<u8>> {
+    let icc_id = obj_id + 1 + image.alpha.is_some() as usize;
+    let color_space = match image.icc {
+        Some(_) => format!("[/ICCBased {} 0 R]", icc_id),
+        None => format!("/{}", device(image.color_space)),
+    };
+    let mut dict = format!(
+        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent {}",
+        image.width, image.height, color_space, image.bits_per_component
+    );
+    match image.jpeg {
+        true => dict.push_str(" /Filter /DCTDecode"),
+        false => dict.push_str(" /Filter /FlateDecode"),
+    }
+    if image.inverted {
+        let decode = vec!["1 0"; image.color_space.components()];
+        dict.push_str(&format!(" /Decode [{}]", decode.join(" ")));
+    }
+    if image.alpha.is_some() {
+        dict.push_str(&format!(" /SMask {} 0 R", obj_id + 1));
+    }
+    let mut objects = vec![stream_object(obj_id, &dict, data)];
+    if let Some(alpha) = alpha {
+        let dict = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent {} /Filter /FlateDecode",
+            image.width, image.height, image.bits_per_component
+        );
+        objects.push(stream_object(obj_id + 1, &dict, alpha));
+    }
+    if let Some(icc) = &image.icc {
+        objects.push(icc_profile(icc_id, icc, image.color_space));
+    }
+    objects
+}
+
+fn device(color_space: ColorSpace) -> &'static str {
+    match color_space {
+        ColorSpace::Gray => "DeviceGray",
+        ColorSpace::Rgb => "DeviceRGB",
+        ColorSpace::Cmyk => "DeviceCMYK",
+    }
+}
+
+// Flux d'un profil ICC, l'espace de périphérique servant de repli
+pub(crate) fn icc_profile(id: usize, profile: &[u8], color_space: ColorSpace) -> Vec<u8> {
+    let dict = format!(
+        "/N {} /Alternate /{}",
+        color_space.components(),
+        device(color_space)
+    );
+    stream_object(id, &dict, profile)
+}
+
+fn stream_object(id: usize, dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut obj = format!(
+        "{} 0 obj\n<< {} /Length {} >>\nstream\n",
+        id,
+        dict,
+        data.len()
     )
-}
\ No newline at end of file
+    .into_bytes();
+    obj.extend(data);
+    obj.extend(b"\nendstream\nendobj\n");
+    obj
+}
+
+// Rectangle (x, y du coin bas gauche, largeur, hauteur) d'un contenu de taille naturelle
+// `natural` (points) dans `area`, selon object-fit et object-position
+pub(crate) fn object_rect(
+    natural: (f32, f32),
+    style: &Style,
+    area: (f32, f32, f32, f32),
+) -> (f32, f32, f32, f32) {
+    let (_, _, w, h) = area;
+    let (nw, nh) = natural;
+    if nw <= 0.0 || nh <= 0.0 {
+        return area;
+    }
+    let contain = (w / nw).min(h / nh);
+    let scale = match style.object_fit.unwrap_or_default() {
+        ObjectFit::Fill => return object_position(style, area, (w, h)),
+        ObjectFit::Contain => contain,
+        ObjectFit::Cover => (w / nw).max(h / nh),
+        ObjectFit::None => 1.0,
+        ObjectFit::ScaleDown => contain.min(1.0),
+    };
+    object_position(style, area, (nw * scale, nh * scale))
+}
+
+// Les pourcentages portent sur l'espace libre, comme pour un fond
+fn object_position(
+    style: &Style,
+    (x, y, w, h): (f32, f32, f32, f32),
+    (dw, dh): (f32, f32),
+) -> (f32, f32, f32, f32) {
+    let center = BackgroundPosition {
+        x: Length::Percent(50.0),
+        y: Length::Percent(50.0),
+    };
+    let position = style.object_position.as_ref().unwrap_or(&center);
+    let font_size = style.font_size.to_pt();
+    let left = position.x.resolve(&basis(w - dw, font_size));
+    let top = position.y.resolve(&basis(h - dh, font_size));
+    (x + left, y + h - top - dh, dw, dh)
+}
+
+// Découpe à la boîte d'un contenu qui la déborde
+pub(crate) fn fit_clip(area: (f32, f32, f32, f32), rect: (f32, f32, f32, f32)) -> String {
+    let (x, y, w, h) = area;
+    let inside = rect.0 >= x - 0.01
+        && rect.1 >= y - 0.01
+        && rect.0 + rect.2 <= x + w + 0.01
+        && rect.1 + rect.3 <= y + h + 0.01;
+    match inside {
+        true => String::new(),
+        false => format!("{} {} {} {} re W n\n", x, y, w, h),
+    }
+}
+
+// Matrice posant l'image (carré unité) sur le rectangle, redressée selon l'orientation EXIF
+pub(crate) fn image_matrix(orientation: u8, (x, y, w, h): (f32, f32, f32, f32)) -> [f32; 6] {
+    match orientation {
+        2 => [-w, 0.0, 0.0, h, x + w, y],
+        3 => [-w, 0.0, 0.0, -h, x + w, y + h],
+        4 => [w, 0.0, 0.0, -h, x, y + h],
+        5 => [0.0, -h, -w, 0.0, x + w, y + h],
+        6 => [0.0, -h, w, 0.0, x, y + h],
+        7 => [0.0, h, w, 0.0, x, y],
+        8 => [0.0, h, -w, 0.0, x + w, y],
+        _ => [w, 0.0, 0.0, h, x, y],
+    }
+}
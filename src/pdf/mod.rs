@@ -1,3 +1,14 @@
 pub mod writer;
 pub mod fonts;
-pub mod images;
\ No newline at end of file
+pub mod font_provider;
+pub mod images;
+pub mod borders;
+pub mod path;
+pub mod background;
+pub mod shadows;
+pub mod shaping;
+pub mod transform;
+pub mod standard_fonts;
+pub mod text;
+pub mod woff;
+pub mod options;
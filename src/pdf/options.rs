@@ -0,0 +1,31 @@
+// Réglages de la conversion ; les valeurs par défaut reproduisent `write_pdf`
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    pub images: ImageOptions,
+    // Couleurs de périphérique rapportées au profil sRGB, par une intention GTS_PDFA1 ;
+    // le fichier n'est pas pour autant déclaré PDF/A
+    pub srgb_output_intent: bool,
+}
+
+// Allègement des images : 150 ppp suffisent à l'écran, 300 à l'impression
+#[derive(Debug, Clone, Default)]
+pub struct ImageOptions {
+    pub max_dpi: Option<f32>, // résolution effective maximale à la taille de dessin
+    pub jpeg_quality: Option<u8>, // ré-encodage JPEG (1 à 100) quand il allège l'image
+    pub grayscale: bool,
+}
+
+// Bilan des images embarquées, en octets de flux (masques d'opacité compris)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImageStats {
+    pub images: usize,
+    pub optimized: usize, // images réduites, passées en gris ou ré-encodées
+    pub original_bytes: usize,
+    pub written_bytes: usize,
+}
+
+impl ImageStats {
+    pub fn saved_bytes(&self) -> usize {
+        self.original_bytes.saturating_sub(self.written_bytes)
+    }
+}
@@ -0,0 +1,164 @@
+use crate::css::styles::{Corners, LengthContext, Radius};
+
+// Approximation d'un quart d'ellipse par une courbe de Bézier cubique
+const KAPPA: f32 = 0.552_284_8;
+
+// Rectangle à coins elliptiques en coordonnées PDF (origine en bas à gauche)
+#[derive(Debug, Clone)]
+pub struct RoundedRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radii: [(f32, f32); 4], // haut-gauche, haut-droit, bas-droit, bas-gauche
+}
+
+impl RoundedRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, radii: [(f32, f32); 4]) -> Self {
+        let mut rect = RoundedRect {
+            x,
+            y,
+            width: width.max(0.0),
+            height: height.max(0.0),
+            radii: radii.map(|(rx, ry)| (rx.max(0.0), ry.max(0.0))),
+        };
+        // Les rayons qui se chevauchent sont réduits proportionnellement (CSS Backgrounds §5.5)
+        let [tl, tr, br, bl] = rect.radii;
+        let mut factor: f32 = 1.0;
+        for (sum, side) in [
+            (tl.0 + tr.0, rect.width),
+            (bl.0 + br.0, rect.width),
+            (tl.1 + bl.1, rect.height),
+            (tr.1 + br.1, rect.height),
+        ] {
+            if sum > 0.0 {
+                factor = factor.min(side / sum);
+            }
+        }
+        if factor < 1.0 {
+            rect.radii = rect.radii.map(|(rx, ry)| (rx * factor, ry * factor));
+        }
+        rect
+    }
+
+    pub fn from_corners(
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        corners: &Corners,
+        font_size: f32,
+    ) -> Self {
+        let ctx = |basis: f32| LengthContext {
+            font_size,
+            root_font_size: font_size,
+            containing_width: basis,
+            containing_height: None,
+            page_width: 0.0,
+            page_height: 0.0,
+        };
+        let (horizontal, vertical) = (ctx(width), ctx(height));
+        let resolve = |r: &Radius| {
+            (
+                r.horizontal.resolve(&horizontal),
+                r.vertical.resolve(&vertical),
+            )
+        };
+        RoundedRect::new(
+            x,
+            y,
+            width,
+            height,
+            [
+                resolve(&corners.top_left),
+                resolve(&corners.top_right),
+                resolve(&corners.bottom_right),
+                resolve(&corners.bottom_left),
+            ],
+        )
+    }
+
+    pub fn is_rounded(&self) -> bool {
+        self.radii.iter().any(|&(rx, ry)| rx > 0.0 && ry > 0.0)
+    }
+
+    // Rectangle intérieur ; les rayons diminuent de l'épaisseur retirée
+    pub fn inset(&self, top: f32, right: f32, bottom: f32, left: f32) -> RoundedRect {
+        let [tl, tr, br, bl] = self.radii;
+        RoundedRect::new(
+            self.x + left,
+            self.y + bottom,
+            self.width - left - right,
+            self.height - top - bottom,
+            [
+                (tl.0 - left, tl.1 - top),
+                (tr.0 - right, tr.1 - top),
+                (br.0 - right, br.1 - bottom),
+                (bl.0 - left, bl.1 - bottom),
+            ],
+        )
+    }
+
+    // Chemin fermé (sans opérateur de peinture)
+    pub fn path(&self) -> String {
+        if !self.is_rounded() {
+            return format!("{} {} {} {} re\n", self.x, self.y, self.width, self.height);
+        }
+        let (left, bottom) = (self.x, self.y);
+        let (right, top) = (self.x + self.width, self.y + self.height);
+        let [tl, tr, br, bl] = self.radii;
+        let k = 1.0 - KAPPA;
+        let mut p = String::new();
+        p.push_str(&format!("{} {} m\n", left + tl.0, top));
+        p.push_str(&format!("{} {} l\n", right - tr.0, top));
+        p.push_str(&format!(
+            "{} {} {} {} {} {} c\n",
+            right - tr.0 * k,
+            top,
+            right,
+            top - tr.1 * k,
+            right,
+            top - tr.1
+        ));
+        p.push_str(&format!("{} {} l\n", right, bottom + br.1));
+        p.push_str(&format!(
+            "{} {} {} {} {} {} c\n",
+            right,
+            bottom + br.1 * k,
+            right - br.0 * k,
+            bottom,
+            right - br.0,
+            bottom
+        ));
+        p.push_str(&format!("{} {} l\n", left + bl.0, bottom));
+        p.push_str(&format!(
+            "{} {} {} {} {} {} c\n",
+            left + bl.0 * k,
+            bottom,
+            left,
+            bottom + bl.1 * k,
+            left,
+            bottom + bl.1
+        ));
+        p.push_str(&format!("{} {} l\n", left, top - tl.1));
+        p.push_str(&format!(
+            "{} {} {} {} {} {} c\nh\n",
+            left,
+            top - tl.1 * k,
+            left + tl.0 * k,
+            top,
+            left + tl.0,
+            top
+        ));
+        p
+    }
+}
+
+pub fn polygon(points: &[(f32, f32)]) -> String {
+    let mut p = String::new();
+    for (i, (x, y)) in points.iter().enumerate() {
+        p.push_str(&format!("{} {} {}\n", x, y, if i == 0 { "m" } else { "l" }));
+    }
+    p.push_str("h\n");
+    p
+}
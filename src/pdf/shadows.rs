@@ -0,0 +1,190 @@
+use super::path::RoundedRect;
+use super::writer::{ensure_alpha, rgb};
+use crate::css::styles::{Color, Shadow};
+use crate::layout::box_model::LayoutBox;
+
+// Le PDF n'a pas de flou : une ombre floue est un empilement de bandes semi-transparentes
+const MAX_BANDS: usize = 24;
+
+// Ombres extérieures (sous le fond) ou intérieures (au-dessus du fond, sous la bordure)
+pub fn write_box_shadows(
+    b: &LayoutBox,
+    border_box: &RoundedRect,
+    inset: bool,
+    stream: &mut Vec<u8>,
+    alphas: &mut Vec<(f32, String)>,
+) {
+    let style = &b.style;
+    let shadows = style.box_shadow.as_deref().unwrap_or_default();
+    let padding_box = border_box.inset(
+        style.border_width.top.to_pt(),
+        style.border_width.right.to_pt(),
+        style.border_width.bottom.to_pt(),
+        style.border_width.left.to_pt(),
+    );
+    // La première ombre est au-dessus des suivantes
+    for shadow in shadows.iter().rev().filter(|s| s.inset == inset) {
+        let color = shadow_color(shadow, &style.color);
+        if color.a <= 0.0 {
+            continue;
+        }
+        let spread = shadow.spread.to_pt();
+        let shape = if inset {
+            spread_rect(&padding_box, -spread)
+        } else {
+            spread_rect(border_box, spread)
+        };
+        let shape = RoundedRect {
+            x: shape.x + shadow.offset_x.to_pt(),
+            y: shape.y - shadow.offset_y.to_pt(),
+            ..shape
+        };
+        stream.extend(b"q\n");
+        // Extérieure : jamais sous la boîte ; intérieure : limitée à la boîte de remplissage
+        if inset {
+            stream.extend(format!("{}W n\n", padding_box.path()).as_bytes());
+        } else {
+            stream.extend(
+                format!("-10000 -10000 20000 20000 re\n{}W* n\n", border_box.path()).as_bytes(),
+            );
+        }
+        stream.extend(format!("{} rg\n", rgb(&color)).as_bytes());
+        for (expansion, alpha) in bands(shadow.blur.to_pt(), color.a, inset) {
+            let band = shape.inset(-expansion, -expansion, -expansion, -expansion);
+            stream.extend(format!("/{} gs\n", ensure_alpha(alpha, alphas)).as_bytes());
+            if inset {
+                stream.extend(format!("{}{}f*\n", padding_box.path(), band.path()).as_bytes());
+            } else {
+                stream.extend(format!("{}f\n", band.path()).as_bytes());
+            }
+        }
+        stream.extend(b"Q\n");
+    }
+}
+
+// Ombres du texte, dessinées avant les glyphes ; `font` et `show` sont les opérateurs Tf et Tj
+pub fn write_text_shadows(
+    b: &LayoutBox,
+    font: &str,
+    show: &str,
+    (x, y): (f32, f32),
+    stream: &mut Vec<u8>,
+    alphas: &mut Vec<(f32, String)>,
+) {
+    let shadows = b.style.text_shadow.as_deref().unwrap_or_default();
+    for shadow in shadows.iter().rev() {
+        let color = shadow_color(shadow, &b.style.color);
+        if color.a <= 0.0 {
+            continue;
+        }
+        let (x, y) = (x + shadow.offset_x.to_pt(), y - shadow.offset_y.to_pt());
+        stream.extend(format!("q\n{0} rg\n{0} RG\n1 j\n", rgb(&color)).as_bytes());
+        // Les glyphes ne peuvent qu'être épaissis (contour) : les bandes intérieures sont fusionnées
+        let mut transmittance = 1.0;
+        for (expansion, alpha) in bands(shadow.blur.to_pt(), color.a, false) {
+            if expansion <= 0.0 {
+                transmittance *= 1.0 - alpha;
+                continue;
+            }
+            stream.extend(
+                format!(
+                    "/{} gs\nBT\n{}\n{} {} Td\n2 Tr\n{} w\n{}\nET\n",
+                    ensure_alpha(alpha, alphas),
+                    font,
+                    x,
+                    y,
+                    2.0 * expansion,
+                    show
+                )
+                .as_bytes(),
+            );
+        }
+        if transmittance < 1.0 {
+            stream.extend(
+                format!(
+                    "/{} gs\nBT\n{}\n{} {} Td\n{}\nET\n",
+                    ensure_alpha(1.0 - transmittance, alphas),
+                    font,
+                    x,
+                    y,
+                    show
+                )
+                .as_bytes(),
+            );
+        }
+        stream.extend(b"Q\n");
+    }
+}
+
+fn shadow_color(shadow: &Shadow, current: &Option<Color>) -> Color {
+    shadow
+        .color
+        .clone()
+        .or_else(|| current.clone())
+        .unwrap_or_default()
+}
+
+// L'étalement agrandit aussi les rayons, mais un coin droit reste droit
+fn spread_rect(rect: &RoundedRect, spread: f32) -> RoundedRect {
+    RoundedRect::new(
+        rect.x - spread,
+        rect.y - spread,
+        rect.width + 2.0 * spread,
+        rect.height + 2.0 * spread,
+        rect.radii.map(|(rx, ry)| {
+            if rx > 0.0 && ry > 0.0 {
+                (rx + spread, ry + spread)
+            } else {
+                (0.0, 0.0)
+            }
+        }),
+    )
+}
+
+// Bandes (agrandissement de la forme, opacité) de la plus large à la plus étroite.
+// Leur superposition donne en chaque point l'opacité d'un flou gaussien d'écart type flou / 2.
+fn bands(blur: f32, alpha: f32, inset: bool) -> Vec<(f32, f32)> {
+    if blur <= 0.0 {
+        return vec![(0.0, alpha)];
+    }
+    let sigma = blur / 2.0;
+    let n = ((2.0 * blur).ceil() as usize).clamp(2, MAX_BANDS);
+    let step = 2.0 * blur / n as f32;
+    // Intérieure : la zone peinte est hors de la forme, elle s'élargit quand la forme rétrécit
+    let sign = if inset { -1.0 } else { 1.0 };
+    let mut covered = 0.0;
+    let mut bands = Vec::new();
+    for k in 0..=n {
+        let expansion = sign * (blur - k as f32 * step);
+        let target = if k == n {
+            alpha
+        } else {
+            alpha * normal_cdf(-sign * (expansion - sign * step / 2.0) / sigma)
+        };
+        let layer = if covered < 1.0 {
+            1.0 - (1.0 - target) / (1.0 - covered)
+        } else {
+            0.0
+        };
+        covered = target;
+        let layer = (layer * 1000.0).round() / 1000.0;
+        if layer > 0.0 {
+            bands.push((expansion, layer));
+        }
+    }
+    bands
+}
+
+fn normal_cdf(x: f32) -> f32 {
+    0.5 * (1.0 + erf(x / std::f32::consts::SQRT_2))
+}
+
+// Abramowitz & Stegun 7.1.26 (erreur < 1,5e-7)
+fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_6
+            + t * (-0.284_496_74 + t * (1.421_413_8 + t * (-1.453_152_1 + t * 1.061_405_4))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 { -y } else { y }
+}
@@ -0,0 +1,100 @@
+use super::fonts::Font;
+use crate::css::styles::{Direction, FontKerning, Style};
+use rustybuzz::ttf_parser::{GlyphId, Tag};
+use rustybuzz::{Face, Feature, UnicodeBuffer};
+
+// Mise en forme OpenType (GSUB, GPOS) des polices incorporées ; les polices standard,
+// sans fichier, restent encodées caractère par caractère en WinAnsi
+
+// Chasses et décalages en millièmes de em
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    pub id: u16,
+    pub advance: f32, // après crénage et positionnement
+    pub width: f32,   // chasse nominale, celle du tableau /W
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub text: String, // caractères représentés ; vide pour la suite d'une grappe
+}
+
+// Fonctionnalités demandées par `font-kerning`, `font-variant-ligatures` et
+// `font-feature-settings`, ce dernier l'emportant
+pub fn features(style: &Style) -> Vec<Feature> {
+    let mut features = Vec::new();
+    let mut set = |tag: &[u8; 4], value: u32| {
+        features.push(Feature::new(Tag::from_bytes(tag), value, ..));
+    };
+    if style.font_kerning == Some(FontKerning::None) {
+        set(b"kern", 0);
+    }
+    let ligatures = style.font_variant_ligatures.unwrap_or_default();
+    // Un interlettrage non nul désactive les ligatures facultatives
+    let spaced = style
+        .letter_spacing
+        .as_ref()
+        .is_some_and(|l| l.to_pt() != 0.0);
+    let common = ligatures.common && !spaced;
+    set(b"liga", common as u32);
+    set(b"clig", common as u32);
+    set(b"dlig", (ligatures.discretionary && !spaced) as u32);
+    set(b"hlig", (ligatures.historical && !spaced) as u32);
+    set(b"calt", ligatures.contextual as u32);
+    for setting in style.font_feature_settings.iter().flatten() {
+        set(&setting.tag, setting.value);
+    }
+    features
+}
+
+// Glyphes dans l'ordre d'affichage ; sans sens donné, il est déduit de l'écriture.
+// None pour une police standard.
+pub fn shape(
+    font: &Font,
+    text: &str,
+    direction: Option<Direction>,
+    features: &[Feature],
+) -> Option<Vec<Glyph>> {
+    let face = Face::from_slice(font.data.as_ref()?, 0)?;
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    match direction {
+        Some(Direction::Ltr) => buffer.set_direction(rustybuzz::Direction::LeftToRight),
+        Some(Direction::Rtl) => buffer.set_direction(rustybuzz::Direction::RightToLeft),
+        None => {}
+    }
+    let output = rustybuzz::shape(&face, features, buffer);
+    let scale = 1000.0 / face.units_per_em() as f32;
+    let infos = output.glyph_infos();
+    // Fin de chaque grappe : début de la grappe suivante dans le texte
+    let mut starts: Vec<usize> = infos.iter().map(|info| info.cluster as usize).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    let mut seen = Vec::new();
+    let glyphs = infos
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, position)| {
+            let id = info.glyph_id as u16;
+            let start = info.cluster as usize;
+            let end = starts
+                .iter()
+                .find(|&&s| s > start)
+                .copied()
+                .unwrap_or(text.len());
+            let text = if seen.contains(&start) {
+                String::new()
+            } else {
+                seen.push(start);
+                text[start..end].to_string()
+            };
+            Glyph {
+                id,
+                advance: position.x_advance as f32 * scale,
+                width: face.glyph_hor_advance(GlyphId(id)).unwrap_or(0) as f32 * scale,
+                x_offset: position.x_offset as f32 * scale,
+                y_offset: position.y_offset as f32 * scale,
+                text,
+            }
+        })
+        .collect();
+    Some(glyphs)
+}
@@ -0,0 +1,168 @@
+// Métriques des polices standard du PDF (fichiers AFM d'Adobe), en millièmes de em
+
+use super::fonts::FontMetrics;
+
+// Chasses des caractères 32 à 126, seize par ligne
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+const TIMES_ROMAN: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+const TIMES_BOLD: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+const TIMES_ITALIC: [u16; 95] = [
+    250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500,
+    920, 611, 611, 667, 722, 611, 611, 722, 722, 333, 444, 667, 556, 833, 667, 722,
+    611, 722, 611, 500, 556, 722, 611, 833, 611, 556, 556, 389, 278, 389, 422, 500,
+    333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444, 278, 722, 500, 500,
+    500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+];
+
+const TIMES_BOLD_ITALIC: [u16; 95] = [
+    250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    832, 667, 667, 667, 722, 667, 667, 722, 778, 389, 500, 667, 611, 889, 722, 722,
+    611, 722, 667, 556, 611, 722, 667, 889, 667, 611, 611, 333, 278, 333, 570, 500,
+    333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500, 278, 778, 556, 500,
+    500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+];
+
+// Nom PostScript de la face d'une famille standard
+pub fn standard_face(family: &str, bold: bool, italic: bool) -> Option<&'static str> {
+    let family = match family.trim().to_ascii_lowercase().as_str() {
+        "helvetica" | "arial" => "Helvetica",
+        "times" | "times-roman" | "times new roman" => "Times",
+        "courier" | "courier new" => "Courier",
+        _ => return None,
+    };
+    Some(match (family, bold, italic) {
+        ("Helvetica", false, false) => "Helvetica",
+        ("Helvetica", true, false) => "Helvetica-Bold",
+        ("Helvetica", false, true) => "Helvetica-Oblique",
+        ("Helvetica", true, true) => "Helvetica-BoldOblique",
+        ("Times", false, false) => "Times-Roman",
+        ("Times", true, false) => "Times-Bold",
+        ("Times", false, true) => "Times-Italic",
+        ("Times", true, true) => "Times-BoldItalic",
+        (_, false, false) => "Courier",
+        (_, true, false) => "Courier-Bold",
+        (_, false, true) => "Courier-Oblique",
+        (_, true, true) => "Courier-BoldOblique",
+    })
+}
+
+pub fn standard_metrics(name: &str) -> FontMetrics {
+    let (ascent, descent, cap_height, x_height) = match name {
+        "Helvetica" | "Helvetica-Oblique" => (718.0, -207.0, 718.0, 523.0),
+        "Helvetica-Bold" | "Helvetica-BoldOblique" => (718.0, -207.0, 718.0, 532.0),
+        "Times-Roman" => (683.0, -217.0, 662.0, 450.0),
+        "Times-Bold" => (683.0, -217.0, 676.0, 461.0),
+        "Times-Italic" => (683.0, -217.0, 653.0, 441.0),
+        "Times-BoldItalic" => (683.0, -217.0, 669.0, 462.0),
+        "Courier" | "Courier-Oblique" => (629.0, -157.0, 562.0, 426.0),
+        _ => (629.0, -157.0, 562.0, 439.0),
+    };
+    FontMetrics {
+        ascent,
+        descent,
+        cap_height,
+        x_height,
+        underline_position: -100.0,
+        underline_thickness: 50.0,
+        italic_angle: if name.ends_with("Italic") || name.ends_with("Oblique") {
+            -12.0
+        } else {
+            0.0
+        },
+    }
+}
+
+// Chasses des codes WinAnsi 32 à 255
+pub fn standard_widths(name: &str) -> Vec<u16> {
+    let table = match name {
+        "Helvetica" | "Helvetica-Oblique" => &HELVETICA,
+        "Helvetica-Bold" | "Helvetica-BoldOblique" => &HELVETICA_BOLD,
+        "Times-Roman" => &TIMES_ROMAN,
+        "Times-Bold" => &TIMES_BOLD,
+        "Times-Italic" => &TIMES_ITALIC,
+        "Times-BoldItalic" => &TIMES_BOLD_ITALIC,
+        _ => return vec![600; 224],
+    };
+    (32..=255u8)
+        .map(|code| table[(width_proxy(code) - 32) as usize])
+        .collect()
+}
+
+// Au-delà de l'ASCII, la chasse est celle d'un caractère de forme voisine
+// (exacte pour les lettres accentuées, approchée pour les symboles)
+fn width_proxy(code: u8) -> u8 {
+    match code {
+        32..=126 => code,
+        0x80 | 0xA2..=0xA5 | 0xA7 | 0xAB | 0xBB | 0x96 => b'0', // €, £, «, –…
+        0x82 | 0x91 | 0x92 | 0x8B | 0x9B => b'\'',
+        0x84 | 0x93 | 0x94 | 0xA8 | 0xB4 => b'"',
+        0x85 | 0x89 | 0x97 | 0x99 | 0x8C | 0x9C | 0xC6 | 0xE6 => b'W', // …, —, ™, Œ, Æ
+        0x8A => b'S',
+        0x9A => b's',
+        0x8E => b'Z',
+        0x9E => b'z',
+        0x9F | 0xDD => b'Y',
+        0x95 | 0xB0 => b'*',
+        0xA1 => b'!',
+        0xA6 => b'|',
+        0xAD => b'-',
+        0xB7 => b'.',
+        0xB8 => b',',
+        0xD7 | 0xF7 | 0xB1 | 0xAC => b'+',
+        0xC0..=0xC5 => b'A',
+        0xC7 => b'C',
+        0xC8..=0xCB => b'E',
+        0xCC..=0xCF => b'I',
+        0xD0 => b'D',
+        0xD1 => b'N',
+        0xD2..=0xD6 | 0xD8 | 0xA9 | 0xAE => b'O',
+        0xD9..=0xDC => b'U',
+        0xDE => b'P',
+        0xDF => b'b',
+        0xE0..=0xE5 | 0xAA => b'a',
+        0xE7 => b'c',
+        0xE8..=0xEB => b'e',
+        0xEC..=0xEF => b'i',
+        0xF1 => b'n',
+        0xF0 | 0xF2..=0xF6 | 0xF8 | 0xBA => b'o',
+        0xF9..=0xFC | 0xB5 => b'u',
+        0xFD | 0xFF => b'y',
+        0xFE => b'p',
+        _ => b' ',
+    }
+}
@@ -0,0 +1,277 @@
+use super::fonts::{FontMetrics, win_ansi};
+use super::shadows::write_text_shadows;
+use super::shaping::{Glyph, features, shape};
+use super::writer::{Resources, ensure_alpha, rgb};
+use crate::css::styles::{
+    DecorationStyle, Direction, FontStyle, FontWeight, Length, Style, TextDecoration,
+};
+use crate::layout::bidi::reverse_run;
+use crate::layout::box_model::LayoutBox;
+
+// Pente des italiques simulés (environ 12°)
+const FAKE_ITALIC_SKEW: f32 = 0.21;
+
+pub(crate) fn write_text(b: &LayoutBox, text: &str, stream: &mut Vec<u8>, res: &mut Resources) {
+    let style = &b.style;
+    let size = style.font_size.to_pt();
+    let bold = style.font_weight.is_some_and(FontWeight::is_bold);
+    let italic = style.font_style.is_some_and(|s| s != FontStyle::Normal);
+    let (name, font) = res.font(style);
+    // Sans face grasse ou italique, la police est épaissie ou inclinée
+    let fake_bold = bold && !font.bold;
+    let fake_italic = italic && !font.italic;
+    let metrics = font.metrics;
+    // La boîte commence au haut de la zone de contenu de la police
+    let y = 842.0 - b.y - metrics.ascent * size / 1000.0;
+    let width = b.width;
+
+    let letter_spacing = style.letter_spacing.as_ref().map_or(0.0, Length::to_pt);
+    let word_spacing = style.word_spacing.as_ref().map_or(0.0, Length::to_pt);
+    let mut font = format!("/{} {} Tf", name, size);
+    if letter_spacing != 0.0 {
+        font.push_str(&format!("\n{} Tc", letter_spacing));
+    }
+    let show = show_runs(style, text, &name, word_spacing, res);
+    write_text_shadows(b, &font, &show, (b.x, y), stream, &mut res.alphas);
+    // Soulignement et surlignement passent sous le texte, le barré au-dessus
+    let line = Line {
+        x: b.x,
+        baseline: y,
+        width,
+        size,
+    };
+    write_decorations(b, &metrics, &line, false, stream, &mut res.alphas);
+    let color = style.color.clone().unwrap_or_default();
+    stream.extend(b"q\n");
+    if color.a < 1.0 {
+        stream.extend(format!("/{} gs\n", ensure_alpha(color.a, &mut res.alphas)).as_bytes());
+    }
+    stream.extend(format!("BT\n{}\n", font).as_bytes());
+    if fake_italic {
+        stream.extend(format!("1 0 {} 1 {} {} Tm\n", FAKE_ITALIC_SKEW, b.x, y).as_bytes());
+    } else {
+        stream.extend(format!("{} {} Td\n", b.x, y).as_bytes());
+    }
+    stream.extend(format!("{} rg\n", rgb(&color)).as_bytes());
+    if fake_bold {
+        stream.extend(format!("{} RG\n2 Tr\n{} w\n", rgb(&color), size * 0.03).as_bytes());
+    }
+    stream.extend(format!("{}\nET\nQ\n", show).as_bytes());
+    write_decorations(b, &metrics, &line, true, stream, &mut res.alphas);
+}
+
+struct Line {
+    x: f32,
+    baseline: f32,
+    width: f32,
+    size: f32,
+}
+
+fn write_decorations(
+    b: &LayoutBox,
+    metrics: &FontMetrics,
+    line: &Line,
+    line_through: bool,
+    stream: &mut Vec<u8>,
+    alphas: &mut Vec<(f32, String)>,
+) {
+    let em = line.size / 1000.0;
+    for decoration in &b.style.text_decorations {
+        let lines = decoration.line.unwrap_or_default();
+        let color = decoration.color.clone().unwrap_or_default();
+        if color.a <= 0.0 || line.width <= 0.0 {
+            continue;
+        }
+        let thickness = decoration
+            .thickness
+            .as_ref()
+            .map_or(metrics.underline_thickness * em, |t| t.to_pt())
+            .max(0.1);
+        // Milieu du trait par rapport à la ligne de base, et sens d'écartement du second trait
+        let mut positions = Vec::new();
+        if line_through {
+            if lines.line_through {
+                positions.push((metrics.x_height / 2.0 * em, 0.0));
+            }
+        } else {
+            if lines.underline {
+                positions.push((metrics.underline_position * em, -1.0));
+            }
+            if lines.overline {
+                positions.push((metrics.ascent * em - thickness / 2.0, 1.0));
+            }
+        }
+        if positions.is_empty() {
+            continue;
+        }
+        stream.extend(format!("q\n{} RG\n{} w\n", rgb(&color), thickness).as_bytes());
+        if color.a < 1.0 {
+            stream.extend(format!("/{} gs\n", ensure_alpha(color.a, alphas)).as_bytes());
+        }
+        for (offset, away) in positions {
+            let y = line.baseline + offset;
+            stream.extend(decoration_path(decoration, line, y, away, thickness).as_bytes());
+        }
+        stream.extend(b"Q\n");
+    }
+}
+
+fn decoration_path(decoration: &TextDecoration, line: &Line, y: f32, away: f32, t: f32) -> String {
+    let (x1, x2) = (line.x, line.x + line.width);
+    let segment = |y: f32| format!("{} {} m\n{} {} l\nS\n", x1, y, x2, y);
+    match decoration.style.unwrap_or_default() {
+        DecorationStyle::Solid => segment(y),
+        DecorationStyle::Double => {
+            // Le barré s'écarte de part et d'autre de sa position
+            if away == 0.0 {
+                segment(y + t) + &segment(y - t)
+            } else {
+                segment(y) + &segment(y + away * 2.0 * t)
+            }
+        }
+        DecorationStyle::Dotted => format!("[0 {}] 0 d\n1 J\n{}", 2.0 * t, segment(y)),
+        DecorationStyle::Dashed => format!("[{0} {0}] 0 d\n{1}", 3.0 * t, segment(y)),
+        DecorationStyle::Wavy => {
+            // Demi-ondes en courbes de Bézier ; le sommet d'une courbe atteint 3/4 de `k`
+            let amplitude = (1.5 * t).max(line.size / 11.0);
+            let half = 2.0 * amplitude;
+            let k = 4.0 / 3.0 * amplitude;
+            let mut path = format!("{} {} m\n", x1, y);
+            let mut x = x1;
+            let mut sign = 1.0;
+            while x < x2 {
+                path.push_str(&format!(
+                    "{} {} {} {} {} {} c\n",
+                    x + half / 3.0,
+                    y + sign * k,
+                    x + 2.0 * half / 3.0,
+                    y + sign * k,
+                    x + half,
+                    y
+                ));
+                x += half;
+                sign = -sign;
+            }
+            path + "S\n"
+        }
+    }
+}
+
+// Opérateur d'affichage ; l'espacement des mots (justification comprise) est un décalage
+// TJ après chaque espace, indépendant de l'encodage contrairement à Tw
+fn show_text(text: &str, word_spacing: f32, size: f32) -> String {
+    if word_spacing == 0.0 || !text.contains(' ') {
+        return format!("({}) Tj", escape_text(text));
+    }
+    let adjustment = -word_spacing * 1000.0 / size;
+    let mut parts = Vec::new();
+    for word in text.split_inclusive(' ') {
+        parts.push(format!("({})", escape_text(word)));
+        if word.ends_with(' ') {
+            parts.push(adjustment.to_string());
+        }
+    }
+    format!("[{}] TJ", parts.join(" "))
+}
+
+// Chaque suite de caractères dans sa face, dans l'ordre d'affichage ; `current` est la
+// police déjà choisie. Sans mise en forme, le texte de droite à gauche est retourné ici.
+fn show_runs(
+    style: &Style,
+    text: &str,
+    current: &str,
+    word_spacing: f32,
+    res: &mut Resources,
+) -> String {
+    let size = style.font_size.to_pt();
+    let rtl = style.direction == Some(Direction::Rtl);
+    let features = features(style);
+    let mut runs = res.fonts.runs(style, text);
+    if rtl {
+        runs.reverse();
+    }
+    let mut current = current.to_string();
+    let mut parts = Vec::new();
+    for (index, range) in runs {
+        let run = &text[range];
+        let glyphs = shape(res.fonts.font(index), run, style.direction, &features);
+        res.fonts
+            .record(index, glyphs.as_deref().unwrap_or_default());
+        let name = format!("F{}", index + 1);
+        if name != current {
+            parts.push(format!("/{} {} Tf", name, size));
+            current = name;
+        }
+        parts.push(match &glyphs {
+            Some(glyphs) => show_glyphs(glyphs, word_spacing, size),
+            None if rtl => show_text(&reverse_run(run), word_spacing, size),
+            None => show_text(run, word_spacing, size),
+        });
+    }
+    parts.join("\n")
+}
+
+// Glyphes d'une police Identity-H : le crénage, les décalages des marques et l'espacement
+// des mots s'ajoutent à la chasse nominale en décalages TJ ; les décalages verticaux
+// passent par l'élévation Ts
+fn show_glyphs(glyphs: &[Glyph], word_spacing: f32, size: f32) -> String {
+    let mut out = String::new();
+    let mut parts: Vec<String> = Vec::new();
+    let mut codes = String::new();
+    let mut rise = 0.0;
+    let flush = |parts: &mut Vec<String>, codes: &mut String, out: &mut String| {
+        if !codes.is_empty() {
+            parts.push(format!("<{}>", std::mem::take(codes)));
+        }
+        if !parts.is_empty() {
+            out.push_str(&format!("[{}] TJ\n", parts.join(" ")));
+            parts.clear();
+        }
+    };
+    for glyph in glyphs {
+        let y = glyph.y_offset * size / 1000.0;
+        if y != rise {
+            flush(&mut parts, &mut codes, &mut out);
+            out.push_str(&format!("{} Ts\n", y));
+            rise = y;
+        }
+        let mut after = glyph.advance - glyph.width - glyph.x_offset;
+        if glyph.text == " " {
+            after += word_spacing * 1000.0 / size;
+        }
+        shift(&mut parts, &mut codes, glyph.x_offset);
+        codes.push_str(&format!("{:04X}", glyph.id));
+        shift(&mut parts, &mut codes, after);
+    }
+    flush(&mut parts, &mut codes, &mut out);
+    if rise != 0.0 {
+        out.push_str("0 Ts\n");
+    }
+    out.trim_end().to_string()
+}
+
+// Déplacement horizontal en millièmes de em, vers la droite
+fn shift(parts: &mut Vec<String>, codes: &mut String, value: f32) {
+    if value.abs() <= 0.001 {
+        return;
+    }
+    if !codes.is_empty() {
+        parts.push(format!("<{}>", std::mem::take(codes)));
+    }
+    parts.push((-value).to_string());
+}
+
+// Chaîne PDF encodée en WinAnsi ; les octets non ASCII sont écrits en octal
+pub(crate) fn escape_text(text: &str) -> String {
+    let mut out = String::new();
+    for ch in text.chars() {
+        match win_ansi(ch).unwrap_or(b'?') {
+            b'(' => out.push_str("\\("),
+            b')' => out.push_str("\\)"),
+            b'\\' => out.push_str("\\\\"),
+            code @ 0x20..=0x7E => out.push(code as char),
+            code => out.push_str(&format!("\\{:03o}", code)),
+        }
+    }
+    out
+}
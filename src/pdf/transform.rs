@@ -0,0 +1,72 @@
+use crate::css::styles::{LengthContext, TransformFunction};
+use crate::layout::box_model::LayoutBox;
+
+// Matrice affine [a b c d e f] : x' = a x + c y + e, y' = b x + d y + f
+pub type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+// `m · n` : `n` est appliquée en premier
+fn multiply(m: Matrix, n: Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[2] * n[1],
+        m[1] * n[0] + m[3] * n[1],
+        m[0] * n[2] + m[2] * n[3],
+        m[1] * n[2] + m[3] * n[3],
+        m[0] * n[4] + m[2] * n[5] + m[4],
+        m[1] * n[4] + m[3] * n[5] + m[5],
+    ]
+}
+
+fn translation(x: f32, y: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, x, y]
+}
+
+// Matrice `cm` de la propriété `transform`, en coordonnées PDF (origine en bas de page)
+pub fn box_transform(b: &LayoutBox, page_height: f32) -> Option<Matrix> {
+    let functions = b.style.transform.as_deref().filter(|f| !f.is_empty())?;
+    let font_size = b.style.font_size.to_pt();
+    let ctx = |basis: f32| LengthContext {
+        font_size,
+        root_font_size: font_size,
+        containing_width: basis,
+        containing_height: None,
+        page_width: 0.0,
+        page_height: 0.0,
+    };
+    let (width, height) = (ctx(b.width), ctx(b.height));
+    // En CSS, l'axe y descend : les angles positifs tournent dans le sens horaire
+    let mut css = IDENTITY;
+    for function in functions {
+        let m = match function {
+            TransformFunction::Translate(x, y) => {
+                translation(x.resolve(&width), y.resolve(&height))
+            }
+            TransformFunction::Scale(sx, sy) => [*sx, 0.0, 0.0, *sy, 0.0, 0.0],
+            TransformFunction::Rotate(deg) => {
+                let (sin, cos) = deg.to_radians().sin_cos();
+                [cos, sin, -sin, cos, 0.0, 0.0]
+            }
+            TransformFunction::Skew(ax, ay) => [
+                1.0,
+                ay.to_radians().tan(),
+                ax.to_radians().tan(),
+                1.0,
+                0.0,
+                0.0,
+            ],
+            TransformFunction::Matrix([a, b, c, d, e, f]) => [*a, *b, *c, *d, e * 0.75, f * 0.75],
+        };
+        css = multiply(css, m);
+    }
+    let (ox, oy) = match &b.style.transform_origin {
+        Some(origin) => (origin.x.resolve(&width), origin.y.resolve(&height)),
+        None => (b.width / 2.0, b.height / 2.0),
+    };
+    let (ox, oy) = (b.x + ox, b.y + oy);
+    let css = multiply(translation(ox, oy), multiply(css, translation(-ox, -oy)));
+    // Passage au repère PDF : y ↦ hauteur - y de part et d'autre
+    let flip = [1.0, 0.0, 0.0, -1.0, 0.0, page_height];
+    let pdf = multiply(flip, multiply(css, flip));
+    (pdf != IDENTITY).then_some(pdf)
+}
@@ -0,0 +1,459 @@
+use std::io::Read;
+
+// Polices web WOFF (tables zlib) et WOFF2 (flux Brotli, tables glyf, loca et hmtx
+// transformées) reconstituées en fichier TrueType ou OpenType
+
+// Fichier sfnt, décompressé si besoin ; None si la police est illisible
+pub fn decode(data: Vec<u8>) -> Option<Vec<u8>> {
+    match data.get(..4)? {
+        b"wOFF" => decode_woff(&data),
+        b"wOF2" => decode_woff2(&data),
+        _ => Some(data),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        Some(self.u16()? as i16)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    // Entier de 1 à 5 octets, sept bits par octet
+    fn base128(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            if i == 0 && byte == 0x80 {
+                return None;
+            }
+            value = value.checked_mul(128)? | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    // Entier de 1 à 3 octets, préfixé par 253, 254 ou 255
+    fn u255(&mut self) -> Option<u16> {
+        Some(match self.u8()? {
+            253 => self.u16()?,
+            254 => self.u8()? as u16 + 506,
+            255 => self.u8()? as u16 + 253,
+            code => code as u16,
+        })
+    }
+}
+
+// Répertoire des tables puis tables alignées sur quatre octets, triées par étiquette ;
+// None au-delà de 4095 tables, que les champs de recherche sur 16 bits ne décrivent plus
+fn build_sfnt(flavor: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Option<Vec<u8>> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len();
+    let power = count.checked_ilog2().unwrap_or(0) as usize;
+    let search_range = (1usize << power) * 16;
+    let field = |value: usize| u16::try_from(value).ok().map(u16::to_be_bytes);
+    let mut out = Vec::new();
+    out.extend(flavor.to_be_bytes());
+    out.extend(field(count)?);
+    out.extend(field(search_range)?);
+    out.extend(field(power)?);
+    out.extend(field(count * 16 - search_range)?);
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, table) in &tables {
+        out.extend(tag);
+        out.extend(checksum(table).to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((table.len() as u32).to_be_bytes());
+        offset += table.len().div_ceil(4) * 4;
+    }
+    for (_, table) in &tables {
+        out.extend(table);
+        out.resize(out.len().div_ceil(4) * 4, 0);
+    }
+    Some(out)
+}
+
+fn checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn decode_woff(data: &[u8]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    r.bytes(4)?;
+    let flavor = r.u32()?;
+    r.u32()?;
+    let count = r.u16()?;
+    r.pos = 44;
+    let mut tables = Vec::new();
+    for _ in 0..count {
+        let tag: [u8; 4] = r.bytes(4)?.try_into().ok()?;
+        let offset = r.u32()? as usize;
+        let compressed = r.u32()? as usize;
+        let length = r.u32()? as usize;
+        r.u32()?;
+        let raw = data.get(offset..offset.checked_add(compressed)?)?;
+        // Une table est stockée telle quelle si la compression ne la réduit pas ; la longueur
+        // annoncée borne la décompression sans servir à réserver la mémoire
+        let table = if compressed < length {
+            let mut table = Vec::new();
+            flate2::read::ZlibDecoder::new(raw)
+                .take(length as u64 + 1)
+                .read_to_end(&mut table)
+                .ok()?;
+            table
+        } else {
+            raw.to_vec()
+        };
+        if table.len() != length {
+            return None;
+        }
+        tables.push((tag, table));
+    }
+    build_sfnt(flavor, tables)
+}
+
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+struct Entry {
+    tag: [u8; 4],
+    length: usize,
+    transformed: bool,
+}
+
+// Les collections WOFF2 ne sont pas prises en charge
+fn decode_woff2(data: &[u8]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    r.bytes(4)?;
+    let flavor = r.u32()?;
+    if flavor == u32::from_be_bytes(*b"ttcf") {
+        return None;
+    }
+    r.u32()?;
+    let count = r.u16()?;
+    r.u16()?;
+    r.u32()?;
+    let compressed_size = r.u32()? as usize;
+    r.pos = 48;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let flags = r.u8()?;
+        let tag = match flags & 0x3F {
+            63 => r.bytes(4)?.try_into().ok()?,
+            known => *KNOWN_TAGS[known as usize],
+        };
+        let version = flags >> 6;
+        let original = r.base128()? as usize;
+        // glyf et loca sont transformées en version 0, les autres tables au-delà
+        let transformed = match &tag {
+            b"glyf" | b"loca" => version == 0,
+            _ => version != 0,
+        };
+        let length = if transformed {
+            r.base128()? as usize
+        } else {
+            original
+        };
+        entries.push(Entry {
+            tag,
+            length,
+            transformed,
+        });
+    }
+    // Le flux ne dépasse pas la somme des longueurs annoncées
+    let total = entries
+        .iter()
+        .try_fold(0usize, |sum, e| sum.checked_add(e.length))?;
+    let mut stream = Vec::new();
+    brotli::Decompressor::new(r.bytes(compressed_size)?, 4096)
+        .take(total as u64 + 1)
+        .read_to_end(&mut stream)
+        .ok()?;
+    if stream.len() > total {
+        return None;
+    }
+
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+    let mut offset = 0usize;
+    for entry in &entries {
+        let table = stream.get(offset..offset.checked_add(entry.length)?)?;
+        offset += entry.length;
+        tables.push((entry.tag, table.to_vec()));
+    }
+    let find = |tag: &[u8; 4]| entries.iter().position(|e| &e.tag == tag);
+
+    let mut x_mins = None;
+    if let Some(glyf) = find(b"glyf")
+        && entries[glyf].transformed
+    {
+        let (glyf_table, loca_table, mins) = reconstruct_glyf(&tables[glyf].1)?;
+        tables[glyf].1 = glyf_table;
+        tables[find(b"loca")?].1 = loca_table;
+        x_mins = Some(mins);
+    }
+    if let Some(hmtx) = find(b"hmtx")
+        && entries[hmtx].transformed
+    {
+        let hhea = &tables[find(b"hhea")?].1;
+        let metrics = u16::from_be_bytes(hhea.get(34..36)?.try_into().ok()?) as usize;
+        tables[hmtx].1 = reconstruct_hmtx(&tables[hmtx].1, metrics, x_mins.as_deref()?)?;
+    }
+    build_sfnt(flavor, tables)
+}
+
+// Table glyf transformée : sept flux séparés (contours, points, drapeaux, coordonnées,
+// composites, boîtes, instructions). Renvoie glyf, loca et le xMin de chaque glyphe.
+fn reconstruct_glyf(data: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<i16>)> {
+    let mut header = Reader::new(data);
+    header.u16()?;
+    let options = header.u16()?;
+    let glyph_count = header.u16()? as usize;
+    let long_loca = header.u16()? != 0;
+    let mut sizes = [0usize; 7];
+    for size in &mut sizes {
+        *size = header.u32()? as usize;
+    }
+    let mut start = header.pos;
+    let mut streams = Vec::new();
+    for size in sizes {
+        streams.push(Reader::new(data.get(start..start.checked_add(size)?)?));
+        start += size;
+    }
+    let overlaps = match options & 1 {
+        0 => None,
+        _ => Some(data.get(start..start + glyph_count.div_ceil(8))?),
+    };
+    let [
+        mut contours,
+        mut points,
+        mut flags,
+        mut coords,
+        mut composites,
+        mut bboxes,
+        mut code,
+    ] = <[Reader; 7]>::try_from(streams).ok()?;
+    let bitmap = bboxes.bytes(glyph_count.div_ceil(32) * 4)?;
+    let has_bbox = |i: usize| bitmap[i / 8] & (0x80 >> (i % 8)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut offsets = vec![0u32];
+    let mut x_mins = Vec::with_capacity(glyph_count);
+    for i in 0..glyph_count {
+        let contour_count = contours.i16()?;
+        let mut glyph = Vec::new();
+        let mut x_min = 0;
+        if contour_count == -1 {
+            // Composite : composants recopiés, boîte toujours explicite
+            let bbox = bboxes.bytes(8)?;
+            x_min = i16::from_be_bytes(bbox[..2].try_into().ok()?);
+            let begin = composites.pos;
+            let mut instructions = false;
+            loop {
+                let flag = composites.u16()?;
+                composites.u16()?;
+                let mut size = if flag & 0x0001 != 0 { 4 } else { 2 };
+                if flag & 0x0008 != 0 {
+                    size += 2;
+                } else if flag & 0x0040 != 0 {
+                    size += 4;
+                } else if flag & 0x0080 != 0 {
+                    size += 8;
+                }
+                composites.bytes(size)?;
+                instructions |= flag & 0x0100 != 0;
+                if flag & 0x0020 == 0 {
+                    break;
+                }
+            }
+            glyph.extend((-1i16).to_be_bytes());
+            glyph.extend(bbox);
+            glyph.extend(&composites.data[begin..composites.pos]);
+            if instructions {
+                let length = coords.u255()?;
+                glyph.extend(length.to_be_bytes());
+                glyph.extend(code.bytes(length as usize)?);
+            }
+        } else if contour_count > 0 {
+            let mut ends = Vec::new();
+            let mut total = 0u16;
+            for _ in 0..contour_count {
+                total = total.checked_add(points.u255()?)?;
+                ends.push(total.checked_sub(1)?);
+            }
+            let mut deltas = Vec::with_capacity(total as usize);
+            let (mut x, mut y) = (0i32, 0i32);
+            let (mut min, mut max) = ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN));
+            for _ in 0..total {
+                let flag = flags.u8()?;
+                let (dx, dy) = triplet(flag & 0x7F, &mut coords)?;
+                x += dx;
+                y += dy;
+                min = (min.0.min(x), min.1.min(y));
+                max = (max.0.max(x), max.1.max(y));
+                deltas.push((dx, dy, flag & 0x80 == 0));
+            }
+            let instruction_length = coords.u255()?;
+            glyph.extend(contour_count.to_be_bytes());
+            if has_bbox(i) {
+                let bbox = bboxes.bytes(8)?;
+                x_min = i16::from_be_bytes(bbox[..2].try_into().ok()?);
+                glyph.extend(bbox);
+            } else {
+                x_min = min.0 as i16;
+                for v in [min.0, min.1, max.0, max.1] {
+                    glyph.extend((v as i16).to_be_bytes());
+                }
+            }
+            for end in ends {
+                glyph.extend(end.to_be_bytes());
+            }
+            glyph.extend(instruction_length.to_be_bytes());
+            glyph.extend(code.bytes(instruction_length as usize)?);
+            let overlap = overlaps.is_some_and(|o| o[i / 8] & (0x80 >> (i % 8)) != 0);
+            encode_points(&deltas, overlap, &mut glyph);
+        }
+        x_mins.push(x_min);
+        glyf.extend(&glyph);
+        glyf.resize(glyf.len().div_ceil(4) * 4, 0);
+        offsets.push(glyf.len() as u32);
+    }
+    let loca = offsets
+        .iter()
+        .flat_map(|&o| match long_loca {
+            true => o.to_be_bytes().to_vec(),
+            false => ((o / 2) as u16).to_be_bytes().to_vec(),
+        })
+        .collect();
+    Some((glyf, loca, x_mins))
+}
+
+// Déplacement d'un point codé sur 1 à 4 octets selon son drapeau
+fn triplet(flag: u8, r: &mut Reader) -> Option<(i32, i32)> {
+    let sign = |bit: u8, v: i32| if flag & bit != 0 { v } else { -v };
+    let f = flag as i32;
+    Some(match flag {
+        0..10 => (0, sign(1, ((f & 14) << 7) + r.u8()? as i32)),
+        10..20 => (sign(1, (((f - 10) & 14) << 7) + r.u8()? as i32), 0),
+        20..84 => {
+            let b0 = f - 20;
+            let b1 = r.u8()? as i32;
+            (
+                sign(1, 1 + (b0 & 0x30) + (b1 >> 4)),
+                sign(2, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+            )
+        }
+        84..120 => {
+            let b0 = f - 84;
+            (
+                sign(1, 1 + ((b0 / 12) << 8) + r.u8()? as i32),
+                sign(2, 1 + (((b0 % 12) >> 2) << 8) + r.u8()? as i32),
+            )
+        }
+        120..124 => {
+            let b0 = r.u8()? as i32;
+            let b1 = r.u8()? as i32;
+            let b2 = r.u8()? as i32;
+            (
+                sign(1, (b0 << 4) + (b1 >> 4)),
+                sign(2, ((b1 & 0x0F) << 8) + b2),
+            )
+        }
+        _ => {
+            let dx = r.u16()? as i32;
+            let dy = r.u16()? as i32;
+            (sign(1, dx), sign(2, dy))
+        }
+    })
+}
+
+// Drapeaux et coordonnées d'un glyphe simple, sans répétition de drapeaux
+fn encode_points(deltas: &[(i32, i32, bool)], overlap: bool, out: &mut Vec<u8>) {
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    for (i, &(dx, dy, on_curve)) in deltas.iter().enumerate() {
+        let mut flag = on_curve as u8;
+        if i == 0 && overlap {
+            flag |= 0x40;
+        }
+        flag |= encode_coordinate(dx, 0x02, 0x10, &mut xs);
+        flag |= encode_coordinate(dy, 0x04, 0x20, &mut ys);
+        out.push(flag);
+    }
+    out.extend(xs);
+    out.extend(ys);
+}
+
+fn encode_coordinate(delta: i32, short: u8, same: u8, out: &mut Vec<u8>) -> u8 {
+    match delta {
+        0 => same,
+        -255..=255 => {
+            out.push(delta.unsigned_abs() as u8);
+            if delta > 0 { short | same } else { short }
+        }
+        _ => {
+            out.extend((delta as i16).to_be_bytes());
+            0
+        }
+    }
+}
+
+// Table hmtx transformée : chasses seules, approches gauches déduites des xMin
+fn reconstruct_hmtx(data: &[u8], metrics: usize, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    let flags = r.u8()?;
+    let advances: Vec<u16> = (0..metrics).map(|_| r.u16()).collect::<Option<_>>()?;
+    let mut bearings = Vec::with_capacity(x_mins.len());
+    for (i, &x_min) in x_mins.iter().enumerate() {
+        let explicit = match i < metrics {
+            true => flags & 1 == 0,
+            false => flags & 2 == 0,
+        };
+        bearings.push(if explicit { r.i16()? } else { x_min });
+    }
+    let mut out = Vec::new();
+    for (i, bearing) in bearings.iter().enumerate() {
+        if let Some(advance) = advances.get(i) {
+            out.extend(advance.to_be_bytes());
+        }
+        out.extend(bearing.to_be_bytes());
+    }
+    Some(out)
+}
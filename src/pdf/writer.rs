@@ -1,9 +1,25 @@
-use crate::css::styles::Color;
-use crate::image::decoder::decode_image;
+use crate::css::styles::{Color, ImageOrientation, Overflow, Style, Stylesheet};
+use crate::image::decoder::{decode_image, decode_image_data, ColorSpace, Image};
+use crate::image::optimize::optimize_image;
 use crate::layout::box_model::{BoxContent, LayoutBox};
-use crate::pdf::fonts::{load_font, Font};
-use crate::pdf::images::embed_image;
-use std::collections::HashMap;
+use crate::pdf::background::write_background;
+use crate::pdf::borders::write_borders;
+use crate::pdf::font_provider::FontProvider;
+use crate::pdf::fonts::{Font, FontCache};
+use crate::pdf::images::{
+    ImageStreams, embed_image, fit_clip, icc_profile, image_matrix, image_streams, object_rect,
+};
+use crate::pdf::options::{ImageStats, PdfOptions};
+use crate::pdf::path::RoundedRect;
+use crate::pdf::shadows::write_box_shadows;
+use crate::pdf::text::write_text;
+use crate::pdf::transform::box_transform;
+use crate::resources::data_url::DataUrls;
+use crate::resources::loader::ResourceLoader;
+use crate::svg::parser::parse_svg;
+use crate::svg::writer::write_svg;
+use moxcms::ColorProfile;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 struct LinkInfo {
     x1: f32,
@@ -13,31 +29,186 @@ struct LinkInfo {
     url: String,
 }
 
-pub fn write_pdf(layout: &LayoutBox) -> Vec<u8> {
+// Plus grande taille de dessin d'une image, en points dans le sens des pixels
+type DrawnSize = Option<(f32, f32)>;
+
+// Ressources de la page, numérotées à l'assemblage du document
+pub(crate) struct Resources<'a> {
+    links: Vec<LinkInfo>,
+    pub alphas: Vec<(f32, String)>,
+    pub fonts: FontCache<'a>,
+    images: Vec<(String, Image, DrawnSize)>,
+    shadings: Vec<String>,
+    soft_masks: Vec<String>,
+    groups: Vec<Vec<u8>>,
+    loader: &'a dyn ResourceLoader,
+}
+
+impl Resources<'_> {
+    // Nom de l'image (décodée une seule fois par source), taille en pixels et orientation EXIF
+    pub fn image(&mut self, src: &str) -> Option<(String, u32, u32, u8)> {
+        let index = match self.images.iter().position(|(s, ..)| s == src) {
+            Some(i) => i,
+            None => {
+                let image = decode_image(src, self.loader)?;
+                self.images.push((src.to_string(), image, None));
+                self.images.len() - 1
+            }
+        };
+        let img = &self.images[index].1;
+        Some((
+            format!("Img{}", index + 1),
+            img.width,
+            img.height,
+            img.orientation,
+        ))
+    }
+
+    // Image contenue dans un SVG, reconnue à l'empreinte de ses octets
+    pub fn image_data(&mut self, data: &[u8]) -> Option<(String, u32, u32, u8)> {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let key = format!("#{:x}", hasher.finish());
+        if !self.images.iter().any(|(s, ..)| *s == key) {
+            self.images.push((key.clone(), decode_image_data(data)?, None));
+        }
+        // Taille de dessin inconnue sous les transformations du SVG : pleine résolution
+        self.image_drawn(&key, (f32::INFINITY, f32::INFINITY));
+        self.image(&key)
+    }
+
+    // Les réductions de résolution s'en tiennent au plus grand dessin de l'image
+    pub fn image_drawn(&mut self, src: &str, (width, height): (f32, f32)) {
+        if let Some((_, _, drawn)) = self.images.iter_mut().find(|(s, ..)| s == src) {
+            let (w, h) = drawn.unwrap_or((0.0, 0.0));
+            *drawn = Some((w.max(width), h.max(height)));
+        }
+    }
+
+    // Première face du style, nommée F1, F2… dans l'ordre de chargement
+    pub fn font(&mut self, style: &Style) -> (String, &Font) {
+        let (index, _) = self.fonts.for_style(style);
+        self.fonts.record(index, &[]);
+        (format!("F{}", index + 1), self.fonts.font(index))
+    }
+
+    pub fn shading(&mut self, dict: String) -> String {
+        self.shadings.push(dict);
+        format!("Sh{}", self.shadings.len())
+    }
+
+    // État graphique appliquant un masque de luminosité peint avec `shading`
+    pub fn soft_mask(&mut self, shading: String) -> String {
+        self.soft_masks.push(shading);
+        format!("SM{}", self.soft_masks.len())
+    }
+
+    // Groupe de transparence : le contenu est composé à part puis peint d'un bloc
+    pub fn group(&mut self, content: Vec<u8>) -> String {
+        self.groups.push(content);
+        format!("Fm{}", self.groups.len())
+    }
+}
+
+// Mêmes feuille de style (polices `@font-face`) et fournisseur de polices que la mise en page
+pub fn write_pdf(
+    layout: &LayoutBox,
+    sheet: Option<&Stylesheet>,
+    fonts: &dyn FontProvider,
+    loader: &dyn ResourceLoader,
+) -> Vec<u8> {
+    write_pdf_with_options(layout, sheet, fonts, loader, &PdfOptions::default()).0
+}
+
+// Avec le bilan des images embarquées
+pub fn write_pdf_with_options(
+    layout: &LayoutBox,
+    sheet: Option<&Stylesheet>,
+    fonts: &dyn FontProvider,
+    loader: &dyn ResourceLoader,
+    options: &PdfOptions,
+) -> (Vec<u8>, ImageStats) {
+    let loader = &DataUrls(loader);
     let mut stream = Vec::new();
-    let mut links = Vec::new();
-    let mut alphas: Vec<(f32, String)> = Vec::new();
-    let mut fonts: HashMap<String, Font> = HashMap::new();
-    let mut images: Vec<(usize, String)> = Vec::new();
-    let mut next_obj_id = 5;
-
-    write_box(
-        layout,
-        &mut stream,
-        &mut links,
-        &mut images,
-        &mut alphas,
-        &mut fonts,
-        &mut next_obj_id,
-    );
+    let mut res = Resources {
+        links: Vec::new(),
+        alphas: Vec::new(),
+        fonts: FontCache::new(sheet.map_or(&[][..], |s| &s.font_faces), fonts, loader),
+        images: Vec::new(),
+        shadings: Vec::new(),
+        soft_masks: Vec::new(),
+        groups: Vec::new(),
+        loader,
+    };
+
+    write_box(layout, &mut stream, &mut res);
+    let Resources {
+        links,
+        alphas,
+        fonts,
+        images,
+        shadings,
+        soft_masks,
+        groups,
+        ..
+    } = res;
+
+    // Bilan en octets réellement écrits, flux compressés
+    let mut stats = ImageStats::default();
+    let size = |(data, alpha): &ImageStreams| data.len() + alpha.as_ref().map_or(0, Vec::len);
+    let images: Vec<_> = images
+        .into_iter()
+        .map(|(_, image, drawn)| {
+            let original = image_streams(&image);
+            stats.images += 1;
+            stats.original_bytes += size(&original);
+            let (image, streams) = match optimize_image(&image, drawn, &options.images) {
+                Some(optimized) => {
+                    stats.optimized += 1;
+                    let streams = image_streams(&optimized);
+                    (optimized, streams)
+                }
+                None => (image, original),
+            };
+            stats.written_bytes += size(&streams);
+            (image, streams)
+        })
+        .collect();
 
     let mut pdf = Vec::new();
     let mut offsets = Vec::new();
 
-    pdf.extend(b"%PDF-1.5\n% zyHTMLtoPDF\n");
+    // Les polices OpenType à contours CFF demandent PDF 1.6
+    let cff = fonts
+        .fonts()
+        .any(|(_, font)| font.data.as_ref().is_some_and(|d| d.starts_with(b"OTTO")));
+    let version = if cff { "1.6" } else { "1.5" };
+    pdf.extend(format!("%PDF-{}\n% zyHTMLtoPDF\n", version).as_bytes());
 
+    // Profil sRGB auquel se rapportent les couleurs de périphérique, juste après les ressources ;
+    // sans profil encodé, pas d'intention de sortie. Le sous-type GTS_PDFA1 est celui que les
+    // lecteurs appliquent aux couleurs RGB : il ne déclare pas le fichier conforme PDF/A, faute
+    // de métadonnées XMP `pdfaid`
+    let output_profile = options
+        .srgb_output_intent
+        .then(|| ColorProfile::new_srgb().encode().ok())
+        .flatten();
+    let output_profile_id = 6;
+    let output_intent = match output_profile {
+        Some(_) => format!(
+            " /OutputIntents [<< /Type /OutputIntent /S /GTS_PDFA1 /OutputConditionIdentifier (sRGB IEC61966-2.1) /Info (sRGB IEC61966-2.1) /DestOutputProfile {} 0 R >>]",
+            output_profile_id
+        ),
+        None => String::new(),
+    };
     offsets.push(pdf.len());
-    pdf.extend(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+    pdf.extend(
+        format!(
+            "1 0 obj\n<< /Type /Catalog /Pages 2 0 R{} >>\nendobj\n",
+            output_intent
+        )
+        .as_bytes(),
+    );
 
     let page_id = 3;
     let content_id = 4;
@@ -51,47 +222,76 @@ pub fn write_pdf(layout: &LayoutBox) -> Vec<u8> {
         .as_bytes(),
     );
 
-    let ext_base = next_obj_id;
+    let resources_id = 5;
+    let ext_base = output_profile_id + output_profile.is_some() as usize;
     let annot_base = ext_base + alphas.len();
     let font_base = annot_base + links.len();
-    let image_base = font_base + fonts.len() * 2;
-
-    offsets.push(pdf.len());
-    let mut font_resources = String::new();
-    let mut font_id = 1;
-    for _font_name in fonts.keys() {
-        font_resources.push_str(&format!(
-            "/F{} {} 0 R ",
-            font_id,
-            font_base + (font_id - 1) * 2
-        ));
-        font_id += 1;
+    // Une police standard tient en un objet ; une TrueType est composite (Type0) : police
+    // descendante, descripteur, fichier et table ToUnicode
+    let mut font_ids = Vec::new();
+    let mut next_id = font_base;
+    for (_, font) in fonts.fonts() {
+        font_ids.push(next_id);
+        next_id += if font.data.is_some() { 5 } else { 1 };
     }
-    pdf.extend(
-        format!(
-            "3 0 obj\n<< /Type /Page /Parent 2 0 R /Resources << /Font << {} /F1 << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> /F2 << /Type /Font /Subtype /Type1 /BaseFont /Times-Roman >> /F3 << /Type /Font /Subtype /Type1 /BaseFont /Courier >> >>",
-            font_resources
-        )
-        .as_bytes(),
-    );
-    if !alphas.is_empty() {
-        let gs: Vec<String> = alphas
+    // Une image est suivie de son masque d'opacité et de son profil ICC s'il y en a
+    let mut image_ids = Vec::new();
+    for (image, _) in &images {
+        image_ids.push(next_id);
+        next_id += 1 + image.alpha.is_some() as usize + image.icc.is_some() as usize;
+    }
+
+    let mask_base = next_id;
+    let group_base = mask_base + soft_masks.len();
+
+    // Dictionnaire de ressources partagé par la page et les groupes de transparence
+    let font_resources: Vec<String> = fonts
+        .fonts()
+        .zip(&font_ids)
+        .map(|((i, _), id)| format!("/F{} {} 0 R", i + 1, id))
+        .collect();
+    let mut resources = format!("<< /Font << {} >>", font_resources.join(" "));
+    if !alphas.is_empty() || !soft_masks.is_empty() {
+        let mut gs: Vec<String> = alphas
             .iter()
             .enumerate()
             .map(|(i, (_, name))| format!("/{} {} 0 R", name, ext_base + i))
             .collect();
-        pdf.extend(format!(" /ExtGState << {} >>", gs.join(" ")).as_bytes());
+        gs.extend((0..soft_masks.len()).map(|i| {
+            format!(
+                "/SM{} << /Type /ExtGState /SMask << /Type /Mask /S /Luminosity /G {} 0 R >> >>",
+                i + 1,
+                mask_base + i
+            )
+        }));
+        resources.push_str(&format!(" /ExtGState << {} >>", gs.join(" ")));
     }
-    if !images.is_empty() {
-        let img_resources: Vec<String> = images
+    if !shadings.is_empty() {
+        let sh: Vec<String> = shadings
             .iter()
             .enumerate()
-            .map(|(i, _)| format!("/Img{} {} 0 R", i + 1, image_base + i))
+            .map(|(i, dict)| format!("/Sh{} {}", i + 1, dict))
             .collect();
-        pdf.extend(format!(" /XObject << {} >>", img_resources.join(" ")).as_bytes());
+        resources.push_str(&format!(" /Shading << {} >>", sh.join(" ")));
     }
+    if !images.is_empty() || !groups.is_empty() {
+        let mut xobjects: Vec<String> = image_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| format!("/Img{} {} 0 R", i + 1, id))
+            .collect();
+        xobjects.extend((0..groups.len()).map(|i| format!("/Fm{} {} 0 R", i + 1, group_base + i)));
+        resources.push_str(&format!(" /XObject << {} >>", xobjects.join(" ")));
+    }
+    resources.push_str(" >>");
+
+    offsets.push(pdf.len());
     pdf.extend(
-        format!(" >> /Contents {} 0 R /MediaBox [0 0 595 842]", content_id).as_bytes(),
+        format!(
+            "3 0 obj\n<< /Type /Page /Parent 2 0 R /Resources {} 0 R /Contents {} 0 R /MediaBox [0 0 595 842]",
+            resources_id, content_id
+        )
+        .as_bytes(),
     );
     if !links.is_empty() {
         let annot_ids: Vec<String> = (0..links.len())
@@ -108,6 +308,14 @@ pub fn write_pdf(layout: &LayoutBox) -> Vec<u8> {
     pdf.extend(&stream);
     pdf.extend(b"\nendstream\nendobj\n");
 
+    offsets.push(pdf.len());
+    pdf.extend(format!("{} 0 obj\n{}\nendobj\n", resources_id, resources).as_bytes());
+
+    if let Some(profile) = &output_profile {
+        offsets.push(pdf.len());
+        pdf.extend(icc_profile(output_profile_id, profile, ColorSpace::Rgb));
+    }
+
     for (i, (alpha, _name)) in alphas.iter().enumerate() {
         let id = ext_base + i;
         offsets.push(pdf.len());
@@ -132,34 +340,139 @@ pub fn write_pdf(layout: &LayoutBox) -> Vec<u8> {
         );
     }
 
-    let mut font_obj_id = font_base;
-    for (font_name, _font) in fonts.iter() {
+    for ((_, font), id) in fonts.fonts().zip(font_ids) {
+        offsets.push(pdf.len());
+        let Some(data) = &font.data else {
+            pdf.extend(
+                format!(
+                    "{} 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>\nendobj\n",
+                    id, font.name
+                )
+                .as_bytes(),
+            );
+            continue;
+        };
+        // Contours CFF : police OpenType incorporée telle quelle (FontFile3)
+        let cff = data.starts_with(b"OTTO");
+        // Codes sur deux octets égaux aux indices des glyphes (Identity-H)
+        let widths: Vec<String> = font
+            .glyphs
+            .iter()
+            .map(|(glyph, (width, _))| format!("{} [{}]", glyph, width.round()))
+            .collect();
+        pdf.extend(
+            format!(
+                "{} 0 obj\n<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>\nendobj\n",
+                id,
+                font.name,
+                id + 1,
+                id + 4
+            )
+            .as_bytes(),
+        );
         offsets.push(pdf.len());
         pdf.extend(
             format!(
-                "{} 0 obj\n<< /Type /Font /Subtype /TrueType /BaseFont /{} /FontDescriptor {} 0 R >>\nendobj\n",
-                font_obj_id, font_name, font_obj_id + 1
+                "{} 0 obj\n<< /Type /Font /Subtype /{} /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /W [{}]{} >>\nendobj\n",
+                id + 1,
+                if cff { "CIDFontType0" } else { "CIDFontType2" },
+                font.name,
+                id + 2,
+                widths.join(" "),
+                if cff { "" } else { " /CIDToGIDMap /Identity" }
             )
             .as_bytes(),
         );
 
+        let m = &font.metrics;
+        // Non symbolique (32), italique (64)
+        let flags = if font.italic { 96 } else { 32 };
         offsets.push(pdf.len());
         pdf.extend(
             format!(
-                "{} 0 obj\n<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [-1000 -1000 2000 2000] /ItalicAngle 0 /Ascent 1000 /Descent -200 /CapHeight 800 /StemV 80 >>\nendobj\n",
-                font_obj_id + 1, font_name
+                "{} 0 obj\n<< /Type /FontDescriptor /FontName /{} /Flags {} /FontBBox [-1000 -1000 2000 2000] /ItalicAngle {} /Ascent {} /Descent {} /CapHeight {} /StemV {} /{} {} 0 R >>\nendobj\n",
+                id + 2,
+                font.name,
+                flags,
+                m.italic_angle,
+                m.ascent,
+                m.descent,
+                m.cap_height,
+                if font.bold { 140 } else { 80 },
+                if cff { "FontFile3" } else { "FontFile2" },
+                id + 3
+            )
+            .as_bytes(),
+        );
+
+        offsets.push(pdf.len());
+        pdf.extend(
+            format!(
+                "{} 0 obj\n<< /Length {} {} >>\nstream\n",
+                id + 3,
+                data.len(),
+                if cff {
+                    "/Subtype /OpenType".to_string()
+                } else {
+                    format!("/Length1 {}", data.len())
+                }
+            )
+            .as_bytes(),
+        );
+        pdf.extend(data);
+        pdf.extend(b"\nendstream\nendobj\n");
+
+        let cmap = to_unicode(font);
+        offsets.push(pdf.len());
+        pdf.extend(
+            format!(
+                "{} 0 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+                id + 4,
+                cmap.len(),
+                cmap
             )
             .as_bytes(),
         );
-        font_obj_id += 2;
     }
 
-    for (_id, img_obj) in &images {
+    for ((img, streams), id) in images.iter().zip(&image_ids) {
+        for object in embed_image(img, streams, *id) {
+            offsets.push(pdf.len());
+            pdf.extend(object);
+        }
+    }
+
+    for (i, shading) in soft_masks.iter().enumerate() {
+        let content = "/Sh0 sh\n";
         offsets.push(pdf.len());
-        pdf.extend(img_obj.as_bytes());
+        pdf.extend(
+            format!(
+                "{} 0 obj\n<< /Type /XObject /Subtype /Form /BBox [-10000 -10000 10000 10000] /Group << /S /Transparency /CS /DeviceGray >> /Resources << /Shading << /Sh0 {} >> >> /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+                mask_base + i,
+                shading,
+                content.len(),
+                content
+            )
+            .as_bytes(),
+        );
+    }
+
+    for (i, content) in groups.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(
+            format!(
+                "{} 0 obj\n<< /Type /XObject /Subtype /Form /BBox [-10000 -10000 10000 10000] /Group << /S /Transparency >> /Resources {} 0 R /Length {} >>\nstream\n",
+                group_base + i,
+                resources_id,
+                content.len()
+            )
+            .as_bytes(),
+        );
+        pdf.extend(content);
+        pdf.extend(b"\nendstream\nendobj\n");
     }
 
-    let obj_count = image_base + images.len();
+    let obj_count = group_base + groups.len();
 
     let xref_offset = pdf.len();
     pdf.extend(format!("xref\n0 {}\n", obj_count).as_bytes());
@@ -175,120 +488,128 @@ pub fn write_pdf(layout: &LayoutBox) -> Vec<u8> {
         )
         .as_bytes(),
     );
-    pdf
+    (pdf, stats)
 }
 
-fn write_box(
-    b: &LayoutBox,
-    stream: &mut Vec<u8>,
-    links: &mut Vec<LinkInfo>,
-    images: &mut Vec<(usize, String)>,
-    alphas: &mut Vec<(f32, String)>,
-    fonts: &mut HashMap<String, Font>,
-    next_obj_id: &mut usize,
-) {
-    let y_rect = 842.0 - b.y - b.height;
-    if let Some(bg) = &b.style.background {
-        if bg.a < 1.0 {
-            stream.extend(format!("/{} gs\n", ensure_alpha(bg.a, alphas)).as_bytes());
-        }
-        stream.extend(
-            format!(
-                "{} {} {} rg\n",
-                bg.r as f32 / 255.0,
-                bg.g as f32 / 255.0,
-                bg.b as f32 / 255.0
-            )
-            .as_bytes(),
-        );
-        stream.extend(format!("{} {} {} {} re f\n", b.x, y_rect, b.width, b.height).as_bytes());
+// Texte source de chaque glyphe, pour la copie et la recherche dans le document
+fn to_unicode(font: &Font) -> String {
+    let entries: Vec<String> = font
+        .glyphs
+        .iter()
+        .filter(|(_, (_, text))| !text.is_empty())
+        .map(|(id, (_, text))| {
+            let utf16: String = text.encode_utf16().map(|u| format!("{:04X}", u)).collect();
+            format!("<{:04X}> <{}>", id, utf16)
+        })
+        .collect();
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // Au plus cent correspondances par bloc
+    for chunk in entries.chunks(100) {
+        cmap.push_str(&format!(
+            "{} beginbfchar\n{}\nendbfchar\n",
+            chunk.len(),
+            chunk.join("\n")
+        ));
     }
-    if b.style.border_width.top > 0.0 {
-        let bw = b.style.border_width.top;
-        stream.extend(b"0 0 0 RG\n");
-        stream.extend(
-            format!(
-                "{} w\n{} {} {} {} re S\n",
-                bw, b.x, y_rect, b.width, b.height
-            )
-            .as_bytes(),
-        );
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend");
+    cmap
+}
+
+// Opacité et transformation s'appliquent à toute la sous-arborescence
+fn write_box(b: &LayoutBox, stream: &mut Vec<u8>, res: &mut Resources) {
+    let opacity = b.style.opacity.unwrap_or(1.0);
+    if opacity <= 0.0 {
+        return;
+    }
+    let matrix = box_transform(b, 842.0);
+    if matrix.is_none() && opacity >= 1.0 {
+        paint_box(b, stream, res);
+        return;
+    }
+    stream.extend(b"q\n");
+    if let Some([a, b_, c, d, e, f]) = matrix {
+        stream.extend(format!("{} {} {} {} {} {} cm\n", a, b_, c, d, e, f).as_bytes());
     }
+    if opacity < 1.0 {
+        let mut content = Vec::new();
+        paint_box(b, &mut content, res);
+        let group = res.group(content);
+        let gs = ensure_alpha(opacity, &mut res.alphas);
+        stream.extend(format!("/{} gs\n/{} Do\n", gs, group).as_bytes());
+    } else {
+        paint_box(b, stream, res);
+    }
+    stream.extend(b"Q\n");
+}
+
+fn paint_box(b: &LayoutBox, stream: &mut Vec<u8>, res: &mut Resources) {
+    let y_rect = 842.0 - b.y - b.height;
+    let border_box = RoundedRect::from_corners(
+        b.x,
+        y_rect,
+        b.width,
+        b.height,
+        &b.style.border_radius,
+        b.style.font_size.to_pt(),
+    );
+    write_box_shadows(b, &border_box, false, stream, &mut res.alphas);
+    write_background(b, &border_box, stream, res);
+    write_box_shadows(b, &border_box, true, stream, &mut res.alphas);
+    write_borders(b, &border_box, stream, &mut res.alphas);
     match &b.content {
-        BoxContent::Text(text) => {
-            let y = 842.0 - b.y - b.style.font_size;
-            let Color { r, g, b: b_, a } = b.style.color;
-            let font_name = b
-                .style
-                .font_family
-                .as_deref()
-                .unwrap_or("Helvetica")
-                .to_string();
-            let _font = if font_name != "Helvetica"
-                && font_name != "Times-Roman"
-                && font_name != "Times New Roman"
-                && font_name != "Courier"
-            {
-                fonts
-                    .entry(font_name.clone())
-                    .or_insert_with(|| {
-                        load_font(&format!("fonts/{}.ttf", font_name), b.style.font_size)
-                            .unwrap_or_else(|_| default_font())
-                    })
-                    .clone()
-            } else {
-                default_font()
-            };
-            let font_ref = match font_name.as_str() {
-                "Times" | "Times-Roman" | "Times New Roman" => "F2",
-                "Courier" => "F3",
-                _ => {
-                    let font_id = fonts
-                        .keys()
-                        .position(|k| k == &font_name)
-                        .map(|i| i + 1)
-                        .unwrap_or(1);
-                    &format!("F{}", font_id)[..]
-                }
-            };
-            if a < 1.0 {
-                stream.extend(format!("/{} gs\n", ensure_alpha(a, alphas)).as_bytes());
-            }
-            stream.extend(
-                format!(
-                    "BT\n/{} {} Tf\n{} {} Td\n{} {} {} rg\n({}) Tj\nET\n",
-                    font_ref,
-                    b.style.font_size,
-                    b.x,
-                    y,
-                    r as f32 / 255.0,
-                    g as f32 / 255.0,
-                    b_ as f32 / 255.0,
-                    escape_text(text)
-                )
-                .as_bytes(),
-            );
-        }
+        BoxContent::Text(text) => write_text(b, text, stream, res),
         BoxContent::Image(path) => {
-            if let Some(img) = decode_image(path) {
-                let obj_id = *next_obj_id;
-                *next_obj_id += 1;
-                images.push((obj_id, embed_image(&img, obj_id)));
-                let img_index = images.len();
+            let area = (b.x, y_rect, b.width, b.height);
+            if let Some((name, width, height, orientation)) = res.image(path) {
+                let orientation = match b.style.image_orientation {
+                    Some(ImageOrientation::None) => 1,
+                    _ => orientation,
+                };
+                let (width, height) = match orientation {
+                    5..=8 => (height, width),
+                    _ => (width, height),
+                };
+                let natural = (width as f32 * 0.75, height as f32 * 0.75);
+                let rect = object_rect(natural, &b.style, area);
+                let [a, b_, c, d, e, f] = image_matrix(orientation, rect);
+                res.image_drawn(path, (a.hypot(b_), c.hypot(d)));
                 stream.extend(
                     format!(
-                        "q\n{} 0 0 {} {} {} cm\n/Img{} Do\nQ\n",
-                        b.width, b.height, b.x, y_rect, img_index
+                        "q\n{}{} {} {} {} {} {} cm\n/{} Do\nQ\n",
+                        fit_clip(area, rect),
+                        a,
+                        b_,
+                        c,
+                        d,
+                        e,
+                        f,
+                        name
                     )
                     .as_bytes(),
                 );
+            } else if let Ok(data) = res.loader.load(path)
+                && let Some(tree) = parse_svg(&data, path, res.fonts.provider(), res.loader)
+            {
+                let natural = (tree.size().width() * 0.75, tree.size().height() * 0.75);
+                let rect = object_rect(natural, &b.style, area);
+                stream.extend(format!("q\n{}", fit_clip(area, rect)).as_bytes());
+                write_svg(&tree, rect, stream, res);
+                stream.extend(b"Q\n");
+            }
+        }
+        BoxContent::Svg(markup) => {
+            let tree = parse_svg(markup.as_bytes(), "", res.fonts.provider(), res.loader);
+            if let Some(tree) = tree {
+                write_svg(&tree, (b.x, y_rect, b.width, b.height), stream, res);
             }
         }
         BoxContent::Element(_) => {}
     }
 
     if let Some(url) = &b.link {
-        links.push(LinkInfo {
+        res.links.push(LinkInfo {
             x1: b.x,
             y1: y_rect,
             x2: b.x + b.width,
@@ -297,32 +618,45 @@ fn write_box(
         });
     }
 
+    let clip = overflow_clip(b, &border_box);
+    if let Some(clip) = &clip {
+        stream.extend(format!("q\n{}W n\n", clip).as_bytes());
+    }
     for child in &b.children {
-        write_box(child, stream, links, images, alphas, fonts, next_obj_id);
+        write_box(child, stream, res);
+    }
+    if clip.is_some() {
+        stream.extend(b"Q\n");
     }
 }
 
-fn escape_text(text: &str) -> String {
-    let mut out = String::new();
-    for ch in text.chars() {
-        let c = match ch {
-            '(' => "\\(".to_string(),
-            ')' => "\\)".to_string(),
-            '\\' => "\\\\".to_string(),
-            _ => {
-                if ch as u32 <= 0xFF {
-                    (ch as u8 as char).to_string()
-                } else {
-                    "?".to_string()
-                }
-            }
-        };
-        out.push_str(&c);
+// Le contenu déborde de la boîte de remplissage, sur un axe ou les deux
+fn overflow_clip(b: &LayoutBox, border_box: &RoundedRect) -> Option<String> {
+    let clip_x = b.style.overflow_x != Overflow::Visible;
+    let clip_y = b.style.overflow_y != Overflow::Visible;
+    let border = &b.style.border_width;
+    let padding_box = border_box.inset(
+        border.top.to_pt(),
+        border.right.to_pt(),
+        border.bottom.to_pt(),
+        border.left.to_pt(),
+    );
+    let RoundedRect {
+        x,
+        y,
+        width,
+        height,
+        ..
+    } = padding_box;
+    match (clip_x, clip_y) {
+        (true, true) => Some(padding_box.path()),
+        (true, false) => Some(format!("{} -10000 {} 20000 re\n", x, width)),
+        (false, true) => Some(format!("-10000 {} 20000 {} re\n", y, height)),
+        (false, false) => None,
     }
-    out
 }
 
-fn ensure_alpha(value: f32, map: &mut Vec<(f32, String)>) -> String {
+pub(crate) fn ensure_alpha(value: f32, map: &mut Vec<(f32, String)>) -> String {
     if let Some((_, name)) = map.iter().find(|(v, _)| (*v - value).abs() < f32::EPSILON) {
         return name.clone();
     }
@@ -331,10 +665,11 @@ fn ensure_alpha(value: f32, map: &mut Vec<(f32, String)>) -> String {
     name
 }
 
-fn default_font() -> Font {
-    Font {
-        name: "Helvetica".to_string(),
-        data: None,
-        size: 12.0,
-    }
-}
\ No newline at end of file
+pub(crate) fn rgb(color: &Color) -> String {
+    format!(
+        "{} {} {}",
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0
+    )
+}
@@ -0,0 +1,63 @@
+use super::loader::{ResourceError, ResourceLoader};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+// Les URL `data:` sont décodées ici, une fois pour tous les chargeurs, ceux de l'application
+// compris ; les autres sont confiées au chargeur enveloppé
+pub struct DataUrls<'a>(pub &'a dyn ResourceLoader);
+
+impl ResourceLoader for DataUrls<'_> {
+    fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError> {
+        match is_data_url(url) {
+            true => decode_data_url(url),
+            false => self.0.load(url),
+        }
+    }
+}
+
+pub fn is_data_url(url: &str) -> bool {
+    url.get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+}
+
+// `data:[<type>][;base64],<données>` : base64, sinon texte encodé en pourcentages
+pub fn decode_data_url(url: &str) -> Result<Vec<u8>, ResourceError> {
+    let invalid = || ResourceError::InvalidDataUrl(url.chars().take(64).collect());
+    let (media, payload) = Some(url)
+        .filter(|url| is_data_url(url))
+        .and_then(|url| url[5..].split_once(','))
+        .ok_or_else(invalid)?;
+    let base64 = media
+        .rsplit(';')
+        .next()
+        .is_some_and(|p| p.trim().eq_ignore_ascii_case("base64"));
+    if base64 {
+        // Les espaces et l'encodage en pourcentages sont tolérés dans le base64
+        let payload = percent_decode(payload).ok_or_else(invalid)?;
+        let payload: Vec<u8> = payload
+            .into_iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        BASE64.decode(payload).map_err(|_| invalid())
+    } else {
+        percent_decode(payload).ok_or_else(invalid)
+    }
+}
+
+// `%XX` remplacé par l'octet ; tout autre caractère reste tel quel
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(out)
+}
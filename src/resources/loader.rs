@@ -0,0 +1,134 @@
+use super::data_url::is_data_url;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Accès aux contenus externes du document : feuilles liées, images et polices `@font-face`.
+// `url` est la valeur telle qu'écrite dans le document (relative ou absolue) ; les URL
+// `data:` sont décodées avant d'arriver au chargeur.
+pub trait ResourceLoader {
+    fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError>;
+}
+
+#[derive(Debug)]
+pub enum ResourceError {
+    IoError(std::io::Error),
+    NotFound(String),
+    NotAllowed(String),
+    TooLarge(String),
+    InvalidDataUrl(String),
+}
+
+// `url` écrite dans la ressource `base` (feuille liée, fichier SVG), ramenée au document :
+// relative au dossier de `base`, sauf URL absolue, chemin absolu, fragment ou `data:`
+pub fn resolve_url(base: &str, url: &str) -> String {
+    if is_data_url(base) || url.starts_with(['/', '#']) || url.contains(':') {
+        return url.to_string();
+    }
+    let dir = Path::new(base).parent().unwrap_or(Path::new(""));
+    dir.join(url).to_string_lossy().into_owned()
+}
+
+// Premier chargeur qui fournit la ressource ; sinon l'erreur du dernier
+impl ResourceLoader for Vec<Box<dyn ResourceLoader>> {
+    fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError> {
+        let mut error = ResourceError::NotFound(url.to_string());
+        for loader in self {
+            match loader.load(url) {
+                Ok(data) => return Ok(data),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+}
+
+// Fichiers relatifs au dossier courant, sans limite
+pub fn default_loader() -> FileLoader {
+    FileLoader::new(".")
+}
+
+// Fichiers résolus depuis un dossier de base ; taille maximale et dossiers autorisés
+// facultatifs (tous si la liste est vide)
+pub struct FileLoader {
+    base: PathBuf,
+    max_size: Option<u64>,
+    allowed: Vec<PathBuf>,
+}
+
+impl FileLoader {
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        FileLoader {
+            base: base.into(),
+            max_size: None,
+            allowed: Vec::new(),
+        }
+    }
+
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    // Dossier dont les fichiers, sous-dossiers compris, peuvent être lus
+    pub fn allow(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.allowed.push(dir.into());
+        self
+    }
+
+    fn resolve(&self, url: &str) -> Result<PathBuf, ResourceError> {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        if path.contains("://") {
+            return Err(ResourceError::NotAllowed(url.to_string()));
+        }
+        let path = self.base.join(path);
+        if self.allowed.is_empty() {
+            return Ok(path);
+        }
+        // Chemin canonique : ni `..` ni lien symbolique ne sortent des dossiers autorisés
+        let canonical = path.canonicalize().map_err(ResourceError::IoError)?;
+        let inside = |dir: &Path| {
+            self.base
+                .join(dir)
+                .canonicalize()
+                .is_ok_and(|dir| canonical.starts_with(dir))
+        };
+        if self.allowed.iter().any(|dir| inside(dir)) {
+            Ok(canonical)
+        } else {
+            Err(ResourceError::NotAllowed(url.to_string()))
+        }
+    }
+}
+
+impl ResourceLoader for FileLoader {
+    fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError> {
+        let path = self.resolve(url)?;
+        let size = fs::metadata(&path).map_err(ResourceError::IoError)?.len();
+        if self.max_size.is_some_and(|max| size > max) {
+            return Err(ResourceError::TooLarge(url.to_string()));
+        }
+        fs::read(&path).map_err(ResourceError::IoError)
+    }
+}
+
+// Contenus fournis par l'application, par URL exacte
+#[derive(Default)]
+pub struct MemoryLoader {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryLoader {
+    pub fn insert(&mut self, url: &str, data: Vec<u8>) {
+        self.files.insert(url.to_string(), data);
+    }
+}
+
+impl ResourceLoader for MemoryLoader {
+    fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError> {
+        self.files
+            .get(url)
+            .cloned()
+            .ok_or_else(|| ResourceError::NotFound(url.to_string()))
+    }
+}
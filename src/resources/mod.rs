@@ -0,0 +1,2 @@
+pub mod data_url;
+pub mod loader;
@@ -0,0 +1,146 @@
+use crate::html::dom::{Node, NodeType};
+use crate::pdf::font_provider::FontProvider;
+use crate::pdf::woff;
+use crate::resources::loader::{ResourceLoader, resolve_url};
+use std::sync::{Arc, Mutex};
+use usvg::fontdb::{Database, Source};
+use usvg::{Font, FontFamily, FontStyle, ImageHrefResolver, Options, Tree};
+
+// Arbre SVG normalisé par usvg (formes en chemins, styles résolus, texte en contours).
+// Polices et images externes ne sont connues qu'à l'analyse : un premier passage relève
+// les demandes, le second les sert depuis le fournisseur de polices et le chargeur.
+// `base` est l'URL du fichier, pour résoudre les `href` relatifs.
+pub fn parse_svg(
+    data: &[u8],
+    base: &str,
+    fonts: &dyn FontProvider,
+    loader: &dyn ResourceLoader,
+) -> Option<Tree> {
+    let requested_fonts: Arc<Mutex<Vec<Font>>> = Arc::default();
+    let requested_hrefs: Arc<Mutex<Vec<String>>> = Arc::default();
+    let mut options = Options::default();
+    let record = Arc::clone(&requested_fonts);
+    options.font_resolver.select_font = Box::new(move |font, _| {
+        record.lock().unwrap().push(font.clone());
+        None
+    });
+    let record = Arc::clone(&requested_hrefs);
+    options.image_href_resolver.resolve_string = Box::new(move |href, _| {
+        record.lock().unwrap().push(href.to_string());
+        None
+    });
+    let tree = Tree::from_data(data, &options).ok()?;
+    let requested_fonts = std::mem::take(&mut *requested_fonts.lock().unwrap());
+    let requested_hrefs = std::mem::take(&mut *requested_hrefs.lock().unwrap());
+    if requested_fonts.is_empty() && requested_hrefs.is_empty() {
+        return Some(tree);
+    }
+
+    let mut database = Database::new();
+    let mut selected = Vec::new();
+    for font in requested_fonts {
+        if selected.iter().any(|(f, _)| *f == font) {
+            continue;
+        }
+        let italic = font.style() != FontStyle::Normal;
+        // Comme usvg, la famille serif sert en dernier recours
+        let id = font
+            .families()
+            .iter()
+            .chain([&FontFamily::Serif])
+            .filter_map(|family| fonts.font(&family_name(family), font.weight(), italic))
+            .filter_map(woff::decode)
+            .find_map(|data| {
+                database
+                    .load_font_source(Source::Binary(Arc::new(data)))
+                    .first()
+                    .copied()
+            });
+        if let Some(id) = id {
+            selected.push((font, id));
+        }
+    }
+
+    let mut images = Vec::new();
+    for href in requested_hrefs {
+        if let Ok(data) = loader.load(&resolve_url(base, &href)) {
+            images.push((href, Arc::new(data)));
+        }
+    }
+
+    options.fontdb = Arc::new(database);
+    options.font_resolver.select_font =
+        Box::new(move |font, _| selected.iter().find(|(f, _)| f == font).map(|(_, id)| *id));
+    // Le type est reconnu aux premiers octets, comme pour une URL `data:` sans type
+    let sniff = ImageHrefResolver::default_data_resolver();
+    options.image_href_resolver.resolve_string = Box::new(move |href, options| {
+        let (_, data) = images.iter().find(|(h, _)| h == href)?;
+        sniff("text/plain", Arc::clone(data), options)
+    });
+    Tree::from_data(data, &options).ok()
+}
+
+fn family_name(family: &FontFamily) -> String {
+    match family {
+        FontFamily::Serif => "serif".to_string(),
+        FontFamily::SansSerif => "sans-serif".to_string(),
+        FontFamily::Cursive => "cursive".to_string(),
+        FontFamily::Fantasy => "fantasy".to_string(),
+        FontFamily::Monospace => "monospace".to_string(),
+        FontFamily::Named(name) => name.clone(),
+    }
+}
+
+// Taille intrinsèque en pixels CSS : attributs width et height, sinon viewBox
+pub fn svg_size(data: &[u8]) -> Option<(f32, f32)> {
+    let options = Options {
+        image_href_resolver: ImageHrefResolver {
+            resolve_data: Box::new(|_, _, _| None),
+            resolve_string: Box::new(|_, _| None),
+        },
+        ..Options::default()
+    };
+    let size = Tree::from_data(data, &options).ok()?.size();
+    Some((size.width(), size.height()))
+}
+
+// Élément `<svg>` du document resérialisé en fichier autonome
+pub fn serialize_svg(node: &Node) -> String {
+    let mut out = String::new();
+    serialize(node, &mut out);
+    out
+}
+
+fn serialize(node: &Node, out: &mut String) {
+    match &node.node_type {
+        NodeType::Element(tag) => {
+            out.push('<');
+            out.push_str(tag);
+            // Espaces de noms implicites en HTML
+            if tag == "svg" && node.get_attribute("xmlns").is_none() {
+                out.push_str(" xmlns=\"http://www.w3.org/2000/svg\"");
+            }
+            if tag == "svg" && node.get_attribute("xmlns:xlink").is_none() {
+                out.push_str(" xmlns:xlink=\"http://www.w3.org/1999/xlink\"");
+            }
+            for (name, value) in &node.attributes {
+                out.push_str(&format!(
+                    " {}=\"{}\"",
+                    name,
+                    escape(value).replace('"', "&quot;")
+                ));
+            }
+            out.push('>');
+            for child in &node.children {
+                serialize(&child.borrow(), out);
+            }
+            out.push_str(&format!("</{}>", tag));
+        }
+        NodeType::Text(text) => out.push_str(&escape(text)),
+        NodeType::Comment(_) => {}
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;")
+}
@@ -0,0 +1,377 @@
+use crate::pdf::background::function;
+use crate::pdf::writer::{Resources, ensure_alpha};
+use usvg::tiny_skia_path::{self, PathSegment, Point};
+use usvg::{
+    BaseGradient, ClipPath, FillRule, Group, Image, ImageKind, LineCap, LineJoin, Node, Paint,
+    PaintOrder, Path, Transform, Tree,
+};
+
+// Dessin vectoriel de l'arbre dans le rectangle (x, y, largeur, hauteur) de la page, y vers
+// le haut. Masques et filtres sont ignorés ; les motifs (`pattern`) ne peignent rien.
+pub(crate) fn write_svg(
+    tree: &Tree,
+    (x, y, width, height): (f32, f32, f32, f32),
+    stream: &mut Vec<u8>,
+    res: &mut Resources,
+) {
+    let size = tree.size();
+    // Repère SVG : origine en haut à gauche, y vers le bas
+    stream.extend(
+        format!(
+            "q\n{} {} {} {} re W n\n{} 0 0 {} {} {} cm\n",
+            x,
+            y,
+            width,
+            height,
+            width / size.width(),
+            -height / size.height(),
+            x,
+            y + height
+        )
+        .as_bytes(),
+    );
+    write_group(tree.root(), stream, res);
+    stream.extend(b"Q\n");
+}
+
+fn write_node(node: &Node, stream: &mut Vec<u8>, res: &mut Resources) {
+    match node {
+        Node::Group(group) => write_group(group, stream, res),
+        Node::Path(path) => write_path(path, stream, res),
+        Node::Image(image) => write_image(image, stream, res),
+        Node::Text(text) => write_group(text.flattened(), stream, res),
+    }
+}
+
+fn write_group(group: &Group, stream: &mut Vec<u8>, res: &mut Resources) {
+    let opacity = group.opacity().get();
+    if opacity <= 0.0 {
+        return;
+    }
+    stream.extend(b"q\n");
+    if !group.transform().is_identity() {
+        stream.extend(format!("{} cm\n", matrix(group.transform())).as_bytes());
+    }
+    if let Some(clip) = group.clip_path() {
+        write_clip(clip, stream);
+    }
+    if opacity < 1.0 {
+        let mut content = Vec::new();
+        for child in group.children() {
+            write_node(child, &mut content, res);
+        }
+        let form = res.group(content);
+        let gs = ensure_alpha(opacity, &mut res.alphas);
+        stream.extend(format!("/{} gs\n/{} Do\n", gs, form).as_bytes());
+    } else {
+        for child in group.children() {
+            write_node(child, stream, res);
+        }
+    }
+    stream.extend(b"Q\n");
+}
+
+// Union des chemins du `clipPath`, intersectée avec son propre `clip-path`
+fn write_clip(clip: &ClipPath, stream: &mut Vec<u8>) {
+    if let Some(inner) = clip.clip_path() {
+        write_clip(inner, stream);
+    }
+    let mut ops = String::new();
+    let mut even_odd = false;
+    clip_outlines(clip.root(), clip.transform(), &mut ops, &mut even_odd);
+    if ops.is_empty() {
+        // Rien ne reste visible
+        ops.push_str("0 0 0 0 re\n");
+    }
+    let operator = if even_odd { "W*" } else { "W" };
+    stream.extend(format!("{}{} n\n", ops, operator).as_bytes());
+}
+
+fn clip_outlines(group: &Group, ts: Transform, ops: &mut String, even_odd: &mut bool) {
+    let ts = ts.pre_concat(group.transform());
+    for child in group.children() {
+        match child {
+            Node::Group(group) => clip_outlines(group, ts, ops, even_odd),
+            Node::Text(text) => clip_outlines(text.flattened(), ts, ops, even_odd),
+            Node::Path(path) => {
+                if let Some(data) = path.data().clone().transform(ts) {
+                    ops.push_str(&path_ops(&data));
+                    *even_odd |= path.fill().is_some_and(|f| f.rule() == FillRule::EvenOdd);
+                }
+            }
+            Node::Image(_) => {}
+        }
+    }
+}
+
+fn write_path(path: &Path, stream: &mut Vec<u8>, res: &mut Resources) {
+    if !path.is_visible() {
+        return;
+    }
+    let ops = path_ops(path.data());
+    let fill = |stream: &mut Vec<u8>, res: &mut Resources| {
+        if let Some(fill) = path.fill() {
+            let even_odd = fill.rule() == FillRule::EvenOdd;
+            write_paint(
+                fill.paint(),
+                fill.opacity().get(),
+                &ops,
+                even_odd,
+                stream,
+                res,
+            );
+        }
+    };
+    let stroke = |stream: &mut Vec<u8>, res: &mut Resources| {
+        if let Some(stroke) = path.stroke() {
+            write_stroke(path, stroke, &ops, stream, res);
+        }
+    };
+    match path.paint_order() {
+        PaintOrder::FillAndStroke => {
+            fill(stream, res);
+            stroke(stream, res);
+        }
+        PaintOrder::StrokeAndFill => {
+            stroke(stream, res);
+            fill(stream, res);
+        }
+    }
+}
+
+fn write_stroke(
+    path: &Path,
+    stroke: &usvg::Stroke,
+    ops: &str,
+    stream: &mut Vec<u8>,
+    res: &mut Resources,
+) {
+    let Paint::Color(color) = stroke.paint() else {
+        // Un dégradé remplit le contour du trait
+        if let Some(outline) = path.data().stroke(&stroke.to_tiny_skia(), 1.0) {
+            let ops = path_ops(&outline);
+            write_paint(
+                stroke.paint(),
+                stroke.opacity().get(),
+                &ops,
+                false,
+                stream,
+                res,
+            );
+        }
+        return;
+    };
+    stream.extend(b"q\n");
+    set_alpha(stroke.opacity().get(), stream, res);
+    let cap = match stroke.linecap() {
+        LineCap::Butt => 0,
+        LineCap::Round => 1,
+        LineCap::Square => 2,
+    };
+    let join = match stroke.linejoin() {
+        LineJoin::Miter | LineJoin::MiterClip => 0,
+        LineJoin::Round => 1,
+        LineJoin::Bevel => 2,
+    };
+    stream.extend(
+        format!(
+            "{} w {} J {} j {} M\n",
+            stroke.width().get(),
+            cap,
+            join,
+            stroke.miterlimit().get()
+        )
+        .as_bytes(),
+    );
+    if let Some(dashes) = stroke.dasharray() {
+        let dashes: Vec<String> = dashes.iter().map(|d| d.to_string()).collect();
+        stream.extend(format!("[{}] {} d\n", dashes.join(" "), stroke.dashoffset()).as_bytes());
+    }
+    stream.extend(
+        format!(
+            "{} {} {} RG\n{}S\nQ\n",
+            color.red as f32 / 255.0,
+            color.green as f32 / 255.0,
+            color.blue as f32 / 255.0,
+            ops
+        )
+        .as_bytes(),
+    );
+}
+
+// Remplissage de `ops` par une couleur unie ou un dégradé
+fn write_paint(
+    paint: &Paint,
+    opacity: f32,
+    ops: &str,
+    even_odd: bool,
+    stream: &mut Vec<u8>,
+    res: &mut Resources,
+) {
+    let star = if even_odd { "*" } else { "" };
+    let (shading_type, coords, base): (u8, Vec<f32>, &BaseGradient) = match paint {
+        Paint::Color(color) => {
+            stream.extend(b"q\n");
+            set_alpha(opacity, stream, res);
+            stream.extend(
+                format!(
+                    "{} {} {} rg\n{}f{}\nQ\n",
+                    color.red as f32 / 255.0,
+                    color.green as f32 / 255.0,
+                    color.blue as f32 / 255.0,
+                    ops,
+                    star
+                )
+                .as_bytes(),
+            );
+            return;
+        }
+        Paint::LinearGradient(g) => (2, vec![g.x1(), g.y1(), g.x2(), g.y2()], g),
+        // Foyer de rayon nul vers le cercle extérieur
+        Paint::RadialGradient(g) => (3, vec![g.fx(), g.fy(), 0.0, g.cx(), g.cy(), g.r().get()], g),
+        Paint::Pattern(_) => return,
+    };
+    let mut stops: Vec<(f32, Vec<f32>, f32)> = base
+        .stops()
+        .iter()
+        .map(|stop| {
+            let color = stop.color();
+            (
+                stop.offset().get(),
+                vec![
+                    color.red as f32 / 255.0,
+                    color.green as f32 / 255.0,
+                    color.blue as f32 / 255.0,
+                ],
+                stop.opacity().get() * opacity,
+            )
+        })
+        .collect();
+    let (Some(first), Some(last)) = (stops.first().cloned(), stops.last().cloned()) else {
+        return;
+    };
+    // Les couleurs extrêmes s'étendent jusqu'aux bornes (reflect et repeat comme pad)
+    if first.0 > 0.0 || stops.len() == 1 {
+        stops.insert(0, (0.0, first.1, first.2));
+    }
+    if last.0 < 1.0 || stops.len() == 1 {
+        stops.push((1.0, last.1, last.2));
+    }
+    let coords: Vec<String> = coords.iter().map(|c| c.to_string()).collect();
+    let dict = |color_space: &str, function: String| {
+        format!(
+            "<< /ShadingType {} /ColorSpace /{} /Coords [{}] /Function {} /Extend [true true] >>",
+            shading_type,
+            color_space,
+            coords.join(" "),
+            function
+        )
+    };
+    let colors: Vec<(f32, Vec<f32>)> = stops.iter().map(|(t, c, _)| (*t, c.clone())).collect();
+    let name = res.shading(dict("DeviceRGB", function(&colors)));
+    stream.extend(format!("q\n{}W{} n\n", ops, star).as_bytes());
+    if !base.transform().is_identity() {
+        stream.extend(format!("{} cm\n", matrix(base.transform())).as_bytes());
+    }
+    if stops.iter().any(|(_, _, a)| *a < 1.0) {
+        let alphas: Vec<(f32, Vec<f32>)> = stops.iter().map(|(t, _, a)| (*t, vec![*a])).collect();
+        let mask = res.soft_mask(dict("DeviceGray", function(&alphas)));
+        stream.extend(format!("/{} gs\n", mask).as_bytes());
+    }
+    stream.extend(format!("/{} sh\nQ\n", name).as_bytes());
+}
+
+// Images matricielles et SVG imbriqués, posés sur (0, 0, largeur, hauteur)
+fn write_image(image: &Image, stream: &mut Vec<u8>, res: &mut Resources) {
+    if !image.is_visible() {
+        return;
+    }
+    let size = image.size();
+    match image.kind() {
+        ImageKind::SVG(tree) => {
+            stream.extend(
+                format!(
+                    "q\n{} 0 0 {} 0 0 cm\n",
+                    size.width() / tree.size().width(),
+                    size.height() / tree.size().height()
+                )
+                .as_bytes(),
+            );
+            write_group(tree.root(), stream, res);
+            stream.extend(b"Q\n");
+        }
+        ImageKind::JPEG(data)
+        | ImageKind::PNG(data)
+        | ImageKind::GIF(data)
+        | ImageKind::WEBP(data) => {
+            if let Some((name, ..)) = res.image_data(data) {
+                stream.extend(
+                    format!(
+                        "q\n{} 0 0 {} 0 {} cm\n/{} Do\nQ\n",
+                        size.width(),
+                        -size.height(),
+                        size.height(),
+                        name
+                    )
+                    .as_bytes(),
+                );
+            }
+        }
+    }
+}
+
+fn set_alpha(opacity: f32, stream: &mut Vec<u8>, res: &mut Resources) {
+    if opacity < 1.0 {
+        let gs = ensure_alpha(opacity, &mut res.alphas);
+        stream.extend(format!("/{} gs\n", gs).as_bytes());
+    }
+}
+
+fn matrix(ts: Transform) -> String {
+    format!(
+        "{} {} {} {} {} {}",
+        ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty
+    )
+}
+
+// Opérateurs de construction du chemin ; les quadratiques deviennent des cubiques
+fn path_ops(path: &tiny_skia_path::Path) -> String {
+    let mut ops = String::new();
+    let (mut start, mut last) = (Point::zero(), Point::zero());
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                ops.push_str(&format!("{} {} m\n", p.x, p.y));
+                (start, last) = (p, p);
+            }
+            PathSegment::LineTo(p) => {
+                ops.push_str(&format!("{} {} l\n", p.x, p.y));
+                last = p;
+            }
+            PathSegment::QuadTo(c, p) => {
+                let c1 = (
+                    last.x + 2.0 / 3.0 * (c.x - last.x),
+                    last.y + 2.0 / 3.0 * (c.y - last.y),
+                );
+                let c2 = (p.x + 2.0 / 3.0 * (c.x - p.x), p.y + 2.0 / 3.0 * (c.y - p.y));
+                ops.push_str(&format!(
+                    "{} {} {} {} {} {} c\n",
+                    c1.0, c1.1, c2.0, c2.1, p.x, p.y
+                ));
+                last = p;
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                ops.push_str(&format!(
+                    "{} {} {} {} {} {} c\n",
+                    c1.x, c1.y, c2.x, c2.y, p.x, p.y
+                ));
+                last = p;
+            }
+            PathSegment::Close => {
+                ops.push_str("h\n");
+                last = start;
+            }
+        }
+    }
+    ops
+}
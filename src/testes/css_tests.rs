@@ -1,7 +1,19 @@
 #[cfg(test)]
 mod tests {
-    use crate::css::parser::{parse_css, parse_stylesheet};
-    use crate::css::styles::Position;
+    use crate::css::background::parse_image;
+    use crate::css::color::parse_color;
+    use crate::css::parser::{parse_css, parse_length, parse_stylesheet};
+    use crate::css::rules::{Rule, parse_declarations, parse_rules};
+    use crate::css::shadow::parse_shadows;
+    use crate::css::styles::{
+        BackgroundImage, BackgroundPosition, BackgroundSize, BorderStyle, BoxArea, Color,
+        DecorationLine, DecorationStyle, FeatureSetting, FontFace, FontFamily, FontKerning,
+        FontSource, FontStyle, FontWeight, GradientDirection, Hyphens, ImageOrientation, Length,
+        LengthContext, LineHeight, ObjectFit, Overflow, Position, RadialExtent, RadialSize, Repeat,
+        Sides, TextAlign, TransformFunction, VariantLigatures, VerticalAlign,
+    };
+    use crate::css::transform::parse_transform;
+    use crate::css::variables::substitute_vars;
     use crate::html::dom::{Node, NodeType};
 
     #[test]
@@ -15,10 +27,10 @@ mod tests {
             children: vec![],
         };
         let style = parse_css(&node, None, None, None);
-        assert_eq!(style.font_size, 16.0);
-        assert_eq!(style.color.r, 255);
+        assert_eq!(style.font_size, Length::Px(16.0));
+        assert_eq!(style.color.as_ref().unwrap().r, 255);
         assert_eq!(style.background.unwrap().g, 255);
-        assert_eq!(style.border_width.top, 2.0);
+        assert_eq!(style.border_width.top, Length::Px(2.0));
     }
 
     #[test]
@@ -34,9 +46,9 @@ mod tests {
             children: vec![],
         };
         let style = parse_css(&node, Some(&sheet), None, None);
-        assert_eq!(style.font_size, 20.0);
-        assert_eq!(style.color.r, 0);
-        assert_eq!(style.color.g, 255);
+        assert_eq!(style.font_size, Length::Px(20.0));
+        assert_eq!(style.color.as_ref().unwrap().r, 0);
+        assert_eq!(style.color.as_ref().unwrap().g, 255);
     }
 
     #[test]
@@ -55,8 +67,8 @@ mod tests {
         };
         let parent_style = parse_css(&parent, Some(&sheet), None, None);
         let p_style = parse_css(&child, Some(&sheet), Some(&parent), Some(&parent_style));
-        assert_eq!(p_style.color.r, 255);
-        assert_eq!(p_style.font_size, 14.0);
+        assert_eq!(p_style.color.as_ref().unwrap().r, 255);
+        assert_eq!(p_style.font_size, Length::Px(14.0));
     }
 
     #[test]
@@ -67,7 +79,28 @@ mod tests {
             children: vec![],
         };
         let style = parse_css(&node, None, None, None);
-        assert_eq!(style.font_family.as_deref(), Some("Courier"));
+        assert_eq!(
+            style.font_family,
+            Some(vec![FontFamily::Named("Courier".to_string())])
+        );
+        let families = |value: &str| {
+            let node = Node {
+                node_type: NodeType::Element("p".to_string()),
+                attributes: vec![("style".to_string(), format!("font-family: {}", value))],
+                children: vec![],
+            };
+            parse_css(&node, None, None, None).font_family
+        };
+        assert_eq!(
+            families(r#""Open Sans", Times  New Roman, 'serif', sans-serif"#),
+            Some(vec![
+                FontFamily::Named("Open Sans".to_string()),
+                FontFamily::Named("Times New Roman".to_string()),
+                FontFamily::Named("serif".to_string()),
+                FontFamily::SansSerif,
+            ])
+        );
+        assert_eq!(families("Arial,"), None);
     }
 
     #[test]
@@ -82,7 +115,874 @@ mod tests {
         };
         let style = parse_css(&node, None, None, None);
         assert_eq!(style.position, Position::Absolute);
-        assert_eq!(style.top, Some(10.0));
-        assert_eq!(style.left, Some(20.0));
+        assert_eq!(style.top, Some(Length::Px(10.0)));
+        assert_eq!(style.left, Some(Length::Px(20.0)));
+    }
+
+    #[test]
+    fn test_comments_and_strings() {
+        let decls = parse_declarations(
+            "/* a; b: c */ content: \";\"; background: url(data:image/png;base64,AAA=); color: red",
+        );
+        assert_eq!(decls.len(), 3);
+        assert_eq!(decls[0].name, "content");
+        assert_eq!(decls[0].value, "\";\"");
+        assert_eq!(decls[1].value, "url(data:image/png;base64,AAA=)");
+        assert_eq!(decls[2].value, "red");
+    }
+
+    #[test]
+    fn test_important_and_bad_declaration() {
+        let decls = parse_declarations(
+            "color: #00ff00 !important; width 10px; font-size: 12px; height: 1px);",
+        );
+        assert_eq!(decls.len(), 2);
+        assert!(decls[0].important);
+        assert_eq!(decls[0].value, "#00ff00");
+        assert_eq!(decls[1].name, "font-size");
+    }
+
+    #[test]
+    fn test_important_across_rules_and_inline_style() {
+        let sheet = parse_stylesheet(
+            "span { color: blue !important; width: 5px !important } \
+             .x { color: green; width: 9px } #y { height: 2px !important; height: 1px }",
+        );
+        let node = |style: &str| Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![
+                ("class".to_string(), "x".to_string()),
+                ("id".to_string(), "y".to_string()),
+                ("style".to_string(), style.to_string()),
+            ],
+            children: vec![],
+        };
+        let style = parse_css(&node("color: red; width: 1px"), Some(&sheet), None, None);
+        // Importante dans une règle moins spécifique : plus forte que l'attribut style
+        assert_eq!(style.color, parse_color("blue"));
+        assert_eq!(style.width, Some(Length::Px(5.0)));
+        assert_eq!(style.height, Some(Length::Px(2.0)));
+        let style = parse_css(
+            &node("color: red !important; --h: 7px; height: var(--h)"),
+            Some(&sheet),
+            None,
+            None,
+        );
+        assert_eq!(style.color, parse_color("red"));
+        assert_eq!(style.height, Some(Length::Px(2.0)));
+    }
+
+    #[test]
+    fn test_stylesheet_recovery_and_media() {
+        let css = "
+            p { color: #ff0000; font-size: 14px }
+            @media screen { p { font-size: 40px } }
+            @media print { @supports (display: block) { h1 { font-size: 30px } } }
+            a:hover, h2 > span { color: #0000ff }
+            } bogus { font-size: 99px }
+            div { font-size: 18px }
+        ";
+        let sheet = parse_stylesheet(css);
+        assert_eq!(sheet.rules["p"].font_size, Length::Px(14.0));
+        assert_eq!(sheet.rules["h1"].font_size, Length::Px(30.0));
+        assert_eq!(sheet.rules["h2 > span"].color.as_ref().unwrap().b, 255);
+        assert!(sheet.rules.contains_key("a:hover"));
+        assert_eq!(sheet.rules["div"].font_size, Length::Px(18.0));
+        assert!(
+            !sheet
+                .rules
+                .values()
+                .any(|s| s.font_size == Length::Px(99.0))
+        );
+    }
+
+    #[test]
+    fn test_nested_at_rules() {
+        let rules = parse_rules("@media print { @page { margin: 1cm } div { color: red } } p { }");
+        assert_eq!(rules.len(), 2);
+        match &rules[0] {
+            Rule::At(at) => {
+                assert_eq!(at.name, "media");
+                assert_eq!(at.prelude, "print");
+                assert_eq!(parse_rules(at.block.as_deref().unwrap()).len(), 2);
+            }
+            other => panic!("at-rule attendue, obtenu {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_length_units() {
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "font-size: 2em; width: 50%; height: 2cm; margin: 1in 12pt 1pc 10mm; padding: 1rem"
+                    .to_string(),
+            )],
+            children: vec![],
+        };
+        let mut style = parse_css(&node, None, None, None);
+        assert_eq!(style.font_size, Length::Em(2.0));
+        assert_eq!(style.width, Some(Length::Percent(50.0)));
+        style.resolve_lengths(&LengthContext {
+            font_size: 10.0,
+            root_font_size: 12.0,
+            containing_width: 400.0,
+            containing_height: None,
+            page_width: 595.0,
+            page_height: 842.0,
+        });
+        assert_eq!(style.font_size, Length::Pt(20.0));
+        assert_eq!(style.width, Some(Length::Pt(200.0)));
+        assert!((style.height.unwrap().to_pt() - 56.693).abs() < 0.01);
+        assert_eq!(style.margin.top, Length::Pt(72.0));
+        assert_eq!(style.margin.right, Length::Pt(12.0));
+        assert_eq!(style.margin.bottom, Length::Pt(12.0));
+        assert!((style.margin.left.to_pt() - 28.346).abs() < 0.01);
+        assert_eq!(style.padding.top, Length::Pt(12.0));
+    }
+
+    #[test]
+    fn test_px_and_viewport_units() {
+        let ctx = LengthContext {
+            font_size: 12.0,
+            root_font_size: 12.0,
+            containing_width: 100.0,
+            containing_height: None,
+            page_width: 600.0,
+            page_height: 800.0,
+        };
+        assert_eq!(Length::Px(16.0).resolve(&ctx), 12.0);
+        assert_eq!(Length::Vw(10.0).resolve(&ctx), 60.0);
+        assert_eq!(Length::Vh(10.0).resolve(&ctx), 80.0);
+        assert_eq!(Length::Vmin(50.0).resolve(&ctx), 300.0);
+        assert_eq!(Length::Q(4.0).resolve(&ctx), Length::Mm(1.0).resolve(&ctx));
+    }
+
+    #[test]
+    fn test_calc_min_max_clamp() {
+        let ctx = LengthContext {
+            font_size: 10.0,
+            root_font_size: 12.0,
+            containing_width: 400.0,
+            containing_height: None,
+            page_width: 595.0,
+            page_height: 842.0,
+        };
+        let calc = parse_length("calc(100% - 2in)").unwrap();
+        assert_eq!(calc.resolve(&ctx), 256.0);
+        let nested = parse_length("calc((50% + 2em) * 2 / 4)").unwrap();
+        assert_eq!(nested.resolve(&ctx), 110.0);
+        assert_eq!(
+            parse_length("min(50%, 100pt)").unwrap().resolve(&ctx),
+            100.0
+        );
+        assert_eq!(
+            parse_length("max(10pt, 1em, 2pt)").unwrap().resolve(&ctx),
+            10.0
+        );
+        assert_eq!(
+            parse_length("clamp(1rem, 10%, 30pt)")
+                .unwrap()
+                .resolve(&ctx),
+            30.0
+        );
+        assert_eq!(
+            parse_length("calc(2 * calc(5pt + 1pt))")
+                .unwrap()
+                .resolve(&ctx),
+            12.0
+        );
+    }
+
+    #[test]
+    fn test_invalid_calc() {
+        assert!(parse_length("calc(100% -2cm)").is_none());
+        assert!(parse_length("calc(100% + )").is_none());
+        assert!(parse_length("clamp(1px, 2px)").is_none());
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "margin: calc(1cm + 2px) 0; width: calc(100% - 2cm)".to_string(),
+            )],
+            children: vec![],
+        };
+        let mut style = parse_css(&node, None, None, None);
+        assert!(style.width.as_ref().unwrap().has_percent());
+        style.resolve_lengths(&LengthContext {
+            font_size: 12.0,
+            root_font_size: 12.0,
+            containing_width: 200.0,
+            containing_height: None,
+            page_width: 595.0,
+            page_height: 842.0,
+        });
+        assert!((style.margin.top.to_pt() - 29.846).abs() < 0.01);
+        assert!((style.width.unwrap().to_pt() - 143.307).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_custom_properties_inherit_and_substitute() {
+        let css = ":root { --brand-primary: #ff0000; --gap: 12px } \
+                   .card { --gap: 2em; margin: var(--gap) var(--missing, 4px); color: var(--brand-primary) }";
+        let sheet = parse_stylesheet(css);
+        let root = Node {
+            node_type: NodeType::Element("html".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let card = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![("class".to_string(), "card".to_string())],
+            children: vec![],
+        };
+        let root_style = parse_css(&root, Some(&sheet), None, None);
+        assert_eq!(root_style.custom_properties["--gap"], "12px");
+        let style = parse_css(&card, Some(&sheet), Some(&root), Some(&root_style));
+        assert_eq!(style.custom_properties["--gap"], "2em");
+        assert_eq!(style.custom_properties["--brand-primary"], "#ff0000");
+        assert_eq!(style.margin.top, Length::Em(2.0));
+        assert_eq!(style.margin.right, Length::Px(4.0));
+        assert_eq!(style.color.as_ref().unwrap().r, 255);
+    }
+
+    #[test]
+    fn test_later_rule_overrides_var() {
+        let sheet = parse_stylesheet(
+            "span { --a: #0f0; --m: 7px; color: var(--a); margin: var(--m); border-color: currentColor } \
+             .x { color: #f00; margin: 3px; border-top-color: blue } \
+             .y { margin: 1px; padding: var(--m); padding: 2px }",
+        );
+        let node = |class: &str| Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![("class".to_string(), class.to_string())],
+            children: vec![],
+        };
+        let style = parse_css(&node("x"), Some(&sheet), None, None);
+        assert_eq!(style.color, parse_color("#f00"));
+        assert_eq!(style.margin.bottom, Length::Px(3.0));
+        // Le raccourci différé garde ses autres côtés, la composante plus tardive l'emporte ;
+        // currentColor est la couleur de l'élément
+        assert_eq!(style.border_color.top, parse_color("blue"));
+        assert_eq!(style.border_color.left, parse_color("#f00"));
+        let style = parse_css(&node("y"), Some(&sheet), None, None);
+        assert_eq!(style.margin.top, Length::Px(1.0));
+        assert_eq!(style.padding.left, Length::Px(2.0));
+        assert_eq!(style.padding.top, Length::Px(2.0));
+    }
+
+    #[test]
+    fn test_var_invalid_and_cycles() {
+        let node = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "--a: var(--b); --b: var(--a); --size: 20px; font-size: var(--a, 9px); \
+                 width: var(--undefined); height: var(--size)"
+                    .to_string(),
+            )],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        assert_eq!(style.font_size, Length::Px(9.0));
+        assert_eq!(style.width, None);
+        assert_eq!(style.height, Some(Length::Px(20.0)));
+    }
+
+    #[test]
+    fn test_var_expansion_budget() {
+        // Chaque niveau double la valeur : 2^15 copies sont refusées, comme une variable invalide
+        let mut properties =
+            std::collections::HashMap::from([("--v0".to_string(), "1px".to_string())]);
+        for i in 1..=15 {
+            let double = format!("var(--v{}) var(--v{})", i - 1, i - 1);
+            properties.insert(format!("--v{}", i), double);
+        }
+        assert_eq!(substitute_vars("var(--v15)", &properties), None);
+        assert_eq!(
+            substitute_vars("var(--v15, 2px)", &properties).as_deref(),
+            Some("2px")
+        );
+        assert_eq!(
+            substitute_vars("var(--v2)", &properties).as_deref(),
+            Some("1px 1px 1px 1px")
+        );
+    }
+
+    fn rgba(r: u8, g: u8, b: u8, a: f32) -> Option<Color> {
+        Some(Color { r, g, b, a })
+    }
+
+    #[test]
+    fn test_color_syntax() {
+        assert_eq!(parse_color("red"), rgba(255, 0, 0, 1.0));
+        assert_eq!(parse_color("RebeccaPurple"), rgba(102, 51, 153, 1.0));
+        assert_eq!(parse_color("transparent"), rgba(0, 0, 0, 0.0));
+        assert_eq!(parse_color("#f00"), rgba(255, 0, 0, 1.0));
+        assert_eq!(parse_color("#0f08"), rgba(0, 255, 0, 136.0 / 255.0));
+        assert_eq!(parse_color("#102030"), rgba(16, 32, 48, 1.0));
+        assert_eq!(parse_color("rgb(0 0 0 / 50%)"), rgba(0, 0, 0, 0.5));
+        assert_eq!(parse_color("rgba(255, 0, 0, 0.25)"), rgba(255, 0, 0, 0.25));
+        assert_eq!(parse_color("rgb(100%, 50%, 0%)"), rgba(255, 128, 0, 1.0));
+        assert_eq!(parse_color("hsl(120deg 100% 50%)"), rgba(0, 255, 0, 1.0));
+        assert_eq!(
+            parse_color("hsla(240, 100%, 50%, .5)"),
+            rgba(0, 0, 255, 0.5)
+        );
+        assert_eq!(parse_color("hwb(0 0% 0%)"), rgba(255, 0, 0, 1.0));
+        assert_eq!(
+            parse_color("oklch(62.8% 0.2577 29.23)"),
+            rgba(255, 0, 0, 1.0)
+        );
+        assert_eq!(parse_color("lab(100 0 0)"), rgba(255, 255, 255, 1.0));
+    }
+
+    #[test]
+    fn test_invalid_color_leaves_unset() {
+        for bad in [
+            "#ggg",
+            "rgb(1, 2)",
+            "rgb(1 2, 3)",
+            "notacolor",
+            "#12345",
+            "hsl(1px 2% 3%)",
+        ] {
+            assert_eq!(parse_color(bad), None, "{}", bad);
+        }
+        let parent = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![("style".to_string(), "color: blue".to_string())],
+            children: vec![],
+        };
+        let child = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "color: bogus; background-color: currentColor".to_string(),
+            )],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, None, None, None);
+        let style = parse_css(&child, None, Some(&parent), Some(&parent_style));
+        assert_eq!(style.color, rgba(0, 0, 255, 1.0));
+        assert_eq!(style.background, rgba(0, 0, 255, 1.0));
+    }
+
+    #[test]
+    fn test_explicit_black_overrides_parent() {
+        let parent = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![("style".to_string(), "color: red".to_string())],
+            children: vec![],
+        };
+        let child = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "color: black; background: url(x.png) #eee".to_string(),
+            )],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, None, None, None);
+        let style = parse_css(&child, None, Some(&parent), Some(&parent_style));
+        assert_eq!(style.color, rgba(0, 0, 0, 1.0));
+        assert_eq!(style.background, rgba(238, 238, 238, 1.0));
+    }
+
+    #[test]
+    fn test_border_shorthands() {
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "color: green; border: 1px solid #ccc; border-top: thick double; \
+                 border-right-color: red; border-style: solid dashed dotted groove"
+                    .to_string(),
+            )],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        assert_eq!(style.border_width.left, Length::Px(1.0));
+        assert_eq!(style.border_width.top, Length::Px(5.0));
+        assert_eq!(style.border_color.left, rgba(204, 204, 204, 1.0));
+        assert_eq!(style.border_color.top, None);
+        assert_eq!(style.border_color.right, rgba(255, 0, 0, 1.0));
+        assert_eq!(
+            style.border_style,
+            Sides {
+                top: BorderStyle::Solid,
+                right: BorderStyle::Dashed,
+                bottom: BorderStyle::Dotted,
+                left: BorderStyle::Groove,
+            }
+        );
+        let invalid = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "border: 1px solid red blue".to_string(),
+            )],
+            children: vec![],
+        };
+        assert_eq!(
+            parse_css(&invalid, None, None, None).border_style,
+            Sides::default()
+        );
+    }
+
+    #[test]
+    fn test_border_radius_parse() {
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "border-radius: 10px 5% / 20px; border-bottom-left-radius: 1em 2em".to_string(),
+            )],
+            children: vec![],
+        };
+        let radius = parse_css(&node, None, None, None).border_radius;
+        assert_eq!(radius.top_left.horizontal, Length::Px(10.0));
+        assert_eq!(radius.top_left.vertical, Length::Px(20.0));
+        assert_eq!(radius.top_right.horizontal, Length::Percent(5.0));
+        assert_eq!(radius.bottom_right.horizontal, Length::Px(10.0));
+        assert_eq!(radius.bottom_left.horizontal, Length::Em(1.0));
+        assert_eq!(radius.bottom_left.vertical, Length::Em(2.0));
+    }
+
+    #[test]
+    fn test_current_color_border() {
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "border: 1px solid currentColor; color: blue".to_string(),
+            )],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        assert_eq!(style.border_color.bottom, rgba(0, 0, 255, 1.0));
+    }
+
+    #[test]
+    fn test_background_shorthand_layers() {
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "background: url('logo.png') right 10px top / 50% auto no-repeat content-box, linear-gradient(red, blue) navy".to_string(),
+            )],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        assert_eq!(style.background, rgba(0, 0, 128, 1.0));
+        assert_eq!(
+            style.background_image[0],
+            BackgroundImage::Url("logo.png".to_string())
+        );
+        assert!(matches!(
+            style.background_image[1],
+            BackgroundImage::LinearGradient(_)
+        ));
+        let ctx = LengthContext {
+            font_size: 12.0,
+            root_font_size: 12.0,
+            containing_width: 200.0,
+            containing_height: None,
+            page_width: 595.0,
+            page_height: 842.0,
+        };
+        assert_eq!(style.background_position[0].x.resolve(&ctx), 192.5);
+        assert_eq!(style.background_position[0].y, Length::Percent(0.0));
+        assert_eq!(
+            style.background_size[0],
+            BackgroundSize::Explicit(Some(Length::Percent(50.0)), None)
+        );
+        assert_eq!(style.background_repeat[0].x, Repeat::NoRepeat);
+        assert_eq!(style.background_origin[0], BoxArea::ContentBox);
+        assert_eq!(style.background_clip[0], BoxArea::ContentBox);
+        assert_eq!(style.background_origin[1], BoxArea::PaddingBox);
+        assert_eq!(style.background_clip[1], BoxArea::BorderBox);
+    }
+
+    #[test]
+    fn test_gradient_parse() {
+        let Some(BackgroundImage::LinearGradient(linear)) = parse_image(
+            "linear-gradient(to top right, red 10%, rgb(0 0 255 / 50%) 20% 40%, green)",
+        ) else {
+            panic!("dégradé linéaire attendu");
+        };
+        assert_eq!(
+            linear.direction,
+            GradientDirection::Corner {
+                right: true,
+                bottom: false
+            }
+        );
+        assert_eq!(linear.stops.len(), 4);
+        assert_eq!(linear.stops[2].position, Some(Length::Percent(40.0)));
+        assert_eq!(linear.stops[3].position, None);
+
+        let Some(BackgroundImage::RadialGradient(radial)) =
+            parse_image("radial-gradient(circle closest-side at left 20px bottom, #fff, #000)")
+        else {
+            panic!("dégradé radial attendu");
+        };
+        assert!(radial.circle);
+        assert_eq!(radial.size, RadialSize::Extent(RadialExtent::ClosestSide));
+        assert_eq!(radial.position.x, Length::Px(20.0));
+        assert_eq!(radial.position.y, Length::Percent(100.0));
+
+        assert!(parse_image("linear-gradient(red)").is_none());
+        assert!(parse_image("radial-gradient(circle 10%, red, blue)").is_none());
+    }
+
+    #[test]
+    fn test_shadow_parse() {
+        let shadows = parse_shadows("inset 0 2px 4px rgba(0,0,0,.5), red 1em 1em", false).unwrap();
+        assert_eq!(shadows.len(), 2);
+        assert!(shadows[0].inset);
+        assert_eq!(shadows[0].blur, Length::Px(4.0));
+        assert_eq!(shadows[0].spread, Length::Px(0.0));
+        assert_eq!(shadows[1].color, rgba(255, 0, 0, 1.0));
+        assert_eq!(shadows[1].offset_y, Length::Em(1.0));
+        assert_eq!(parse_shadows("none", false), Some(vec![]));
+        assert!(parse_shadows("1px 1px -2px black", false).is_none());
+        assert!(parse_shadows("1px 1px 2px 3px black", true).is_none());
+        assert!(parse_shadows("inset 1px 1px", true).is_none());
+        assert!(parse_shadows("1px red 1px", false).is_none());
+    }
+
+    #[test]
+    fn test_text_shadow_is_inherited() {
+        let parent = Node {
+            node_type: NodeType::Element("h1".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "text-shadow: 1px 1px; box-shadow: 0 0 2px".to_string(),
+            )],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, None, None, None);
+        let child = Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let style = parse_css(&child, None, Some(&parent), Some(&parent_style));
+        assert_eq!(style.text_shadow.map(|s| s.len()), Some(1));
+        assert!(style.box_shadow.is_none());
+    }
+
+    #[test]
+    fn test_transform_opacity_overflow_parse() {
+        let functions =
+            parse_transform("translate(10px, 50%) rotate(0.25turn) scale(150%) skewX(10deg)")
+                .unwrap();
+        assert_eq!(
+            functions[0],
+            TransformFunction::Translate(Length::Px(10.0), Length::Percent(50.0))
+        );
+        assert_eq!(functions[1], TransformFunction::Rotate(90.0));
+        assert_eq!(functions[2], TransformFunction::Scale(1.5, 1.5));
+        assert_eq!(functions[3], TransformFunction::Skew(10.0, 0.0));
+        assert_eq!(parse_transform("none"), Some(vec![]));
+        assert!(parse_transform("rotate(10px)").is_none());
+        assert!(parse_transform("translate3d(1px, 1px, 1px)").is_none());
+
+        let node = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "opacity: 150%; overflow: hidden visible; overflow-y: clip; transform-origin: left top"
+                    .to_string(),
+            )],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        assert_eq!(style.opacity, Some(1.0));
+        assert_eq!(style.overflow_x, Overflow::Hidden);
+        assert_eq!(style.overflow_y, Overflow::Clip);
+        assert_eq!(style.transform_origin.unwrap().y, Length::Percent(0.0));
+    }
+
+    #[test]
+    fn test_font_weight_and_style() {
+        let sheet = parse_stylesheet("p { font-weight: 300 } .x { font-style: oblique 10deg }");
+        let parent = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![("class".to_string(), "x".to_string())],
+            children: vec![],
+        };
+        let child = Node {
+            node_type: NodeType::Element("strong".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, Some(&sheet), None, None);
+        assert_eq!(parent_style.font_weight, Some(FontWeight::Weight(300)));
+        // `bolder` part du poids du parent
+        let style = parse_css(&child, Some(&sheet), Some(&parent), Some(&parent_style));
+        assert_eq!(style.font_weight, Some(FontWeight::Weight(400)));
+        assert_eq!(style.font_style, Some(FontStyle::Oblique));
+
+        let em = Node {
+            node_type: NodeType::Element("em".to_string()),
+            attributes: vec![("style".to_string(), "font-style: normal".to_string())],
+            children: vec![],
+        };
+        assert_eq!(
+            parse_css(&em, None, None, None).font_style,
+            Some(FontStyle::Normal)
+        );
+    }
+
+    #[test]
+    fn test_text_decoration_propagates() {
+        let parent = Node {
+            node_type: NodeType::Element("u".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "color: red; text-decoration-style: dotted".to_string(),
+            )],
+            children: vec![],
+        };
+        let child = Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "color: blue; text-decoration: line-through overline wavy 2px".to_string(),
+            )],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, None, None, None);
+        let style = parse_css(&child, None, Some(&parent), Some(&parent_style));
+        let [underline, own] = style.text_decorations.as_slice() else {
+            panic!("deux décorations attendues");
+        };
+        assert_eq!(underline.line.map(|l| l.underline), Some(true));
+        assert_eq!(underline.style, Some(DecorationStyle::Dotted));
+        assert_eq!(underline.color, rgba(255, 0, 0, 1.0));
+        let line = DecorationLine {
+            underline: false,
+            overline: true,
+            line_through: true,
+        };
+        assert_eq!(own.line, Some(line));
+        assert_eq!(own.style, Some(DecorationStyle::Wavy));
+        assert_eq!(own.thickness, Some(Length::Px(2.0)));
+        assert_eq!(own.color, rgba(0, 0, 255, 1.0));
+
+        let invalid = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![(
+                "style".to_string(),
+                "text-decoration: underline underline".to_string(),
+            )],
+            children: vec![],
+        };
+        assert!(
+            parse_css(&invalid, None, None, None)
+                .text_decorations
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_text_align_and_spacing_inherit() {
+        let sheet = parse_stylesheet(
+            "div { text-align: end; letter-spacing: 0.1em; word-spacing: normal; text-indent: 5% }",
+        );
+        let parent = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let child = Node {
+            node_type: NodeType::Text("x".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let parent_style = parse_css(&parent, Some(&sheet), None, None);
+        let style = parse_css(&child, Some(&sheet), Some(&parent), Some(&parent_style));
+        assert_eq!(style.text_align, Some(TextAlign::End));
+        assert_eq!(style.letter_spacing, Some(Length::Em(0.1)));
+        assert_eq!(style.word_spacing, Some(Length::Px(0.0)));
+        assert_eq!(style.text_indent, Some(Length::Percent(5.0)));
+    }
+
+    #[test]
+    fn test_line_height_and_vertical_align() {
+        let sheet = parse_stylesheet(
+            "p { line-height: 1.5 } .l { line-height: 120%; vertical-align: 10% }",
+        );
+        let p = Node {
+            node_type: NodeType::Element("p".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let sup = Node {
+            node_type: NodeType::Element("sup".to_string()),
+            attributes: vec![("class".to_string(), "l".to_string())],
+            children: vec![],
+        };
+        let parent_style = parse_css(&p, Some(&sheet), None, None);
+        assert_eq!(parent_style.line_height, Some(LineHeight::Number(1.5)));
+        let style = parse_css(&sup, Some(&sheet), Some(&p), Some(&parent_style));
+        assert_eq!(style.line_height, Some(LineHeight::Length(Length::Em(1.2))));
+        assert_eq!(
+            style.vertical_align,
+            Some(VerticalAlign::Length(Length::Percent(10.0)))
+        );
+        let sup_style = parse_css(&sup, None, Some(&p), Some(&parent_style));
+        assert_eq!(sup_style.vertical_align, Some(VerticalAlign::Super));
+        assert_eq!(sup_style.line_height, Some(LineHeight::Number(1.5)));
+    }
+
+    #[test]
+    fn test_hyphens_and_lang_inherit() {
+        let sheet = parse_stylesheet("div { -webkit-hyphens: auto }");
+        let div = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![("lang".to_string(), "de-AT".to_string())],
+            children: vec![],
+        };
+        let span = Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let div_style = parse_css(&div, Some(&sheet), None, None);
+        let style = parse_css(&span, Some(&sheet), Some(&div), Some(&div_style));
+        assert_eq!(style.hyphens, Some(Hyphens::Auto));
+        assert_eq!(style.lang.as_deref(), Some("de-AT"));
+        let unknown = Node {
+            attributes: vec![("lang".to_string(), "".to_string())],
+            ..span
+        };
+        let style = parse_css(&unknown, Some(&sheet), Some(&div), Some(&div_style));
+        assert_eq!(style.lang.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_font_features() {
+        let sheet = parse_stylesheet(
+            r#"div { font-kerning: none; font-variant-ligatures: no-common-ligatures discretionary-ligatures; font-feature-settings: "smcp", 'ss01' on, "liga" 0 }
+            p { font-variant-ligatures: common-ligatures no-common-ligatures; font-feature-settings: "toolong" }"#,
+        );
+        let div = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let span = Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let div_style = parse_css(&div, Some(&sheet), None, None);
+        let style = parse_css(&span, Some(&sheet), Some(&div), Some(&div_style));
+        assert_eq!(style.font_kerning, Some(FontKerning::None));
+        assert_eq!(
+            style.font_variant_ligatures,
+            Some(VariantLigatures {
+                common: false,
+                discretionary: true,
+                historical: false,
+                contextual: true,
+            })
+        );
+        let setting = |tag: &[u8; 4], value| FeatureSetting { tag: *tag, value };
+        assert_eq!(
+            style.font_feature_settings,
+            Some(vec![
+                setting(b"smcp", 1),
+                setting(b"ss01", 1),
+                setting(b"liga", 0)
+            ])
+        );
+        // Mots-clés répétés ou étiquette invalide : déclaration ignorée
+        let p = Node {
+            node_type: NodeType::Element("p".to_string()),
+            ..span
+        };
+        let style = parse_css(&p, Some(&sheet), None, None);
+        assert_eq!(style.font_variant_ligatures, None);
+        assert_eq!(style.font_feature_settings, None);
+    }
+
+    #[test]
+    fn test_font_face_rules() {
+        let sheet = parse_stylesheet(
+            r#"
+            @font-face {
+                font-family: "Brand";
+                src: local(Brand Sans), url(fonts/brand.eot) format("embedded-opentype"),
+                    url('fonts/brand.woff2') format("woff2"), url(data:font/ttf;base64,AAEA);
+                font-weight: 300 700;
+                font-style: italic;
+                unicode-range: U+0-7F, U+0131, U+4??;
+            }
+            @font-face { font-family: Brand; src: local(x); unicode-range: U+110000 }
+            @font-face { font-family: serif, Brand; src: url(a.ttf) }
+            @font-face { font-family: NoSource; src: url(a.svg) format("svg") }
+            p { font-family: Brand }
+            "#,
+        );
+        assert_eq!(
+            sheet.font_faces,
+            [
+                FontFace {
+                    family: "Brand".to_string(),
+                    sources: vec![
+                        FontSource::Local("Brand Sans".to_string()),
+                        FontSource::Url("fonts/brand.woff2".to_string()),
+                        FontSource::Url("data:font/ttf;base64,AAEA".to_string()),
+                    ],
+                    weight: (300, 700),
+                    italic: true,
+                    unicode_range: vec![(0, 0x7F), (0x131, 0x131), (0x400, 0x4FF)],
+                },
+                // Plage invalide : le descripteur seul est ignoré
+                FontFace {
+                    family: "Brand".to_string(),
+                    sources: vec![FontSource::Local("x".to_string())],
+                    weight: (400, 400),
+                    italic: false,
+                    unicode_range: vec![],
+                },
+            ]
+        );
+        assert!(sheet.rules.contains_key("p"));
+    }
+
+    #[test]
+    fn test_object_fit_and_image_orientation() {
+        let sheet = parse_stylesheet(
+            "div { image-orientation: none } img { object-fit: scale-down; object-position: right 10px } \
+             img.bad { object-fit: stretch; image-orientation: flip }",
+        );
+        let div = Node {
+            node_type: NodeType::Element("div".to_string()),
+            attributes: vec![],
+            children: vec![],
+        };
+        let img = Node {
+            node_type: NodeType::Element("img".to_string()),
+            attributes: vec![("class".to_string(), "bad".to_string())],
+            children: vec![],
+        };
+        let div_style = parse_css(&div, Some(&sheet), None, None);
+        let style = parse_css(&img, Some(&sheet), Some(&div), Some(&div_style));
+        // Valeurs inconnues ignorées ; l'orientation est héritée
+        assert_eq!(style.object_fit, Some(ObjectFit::ScaleDown));
+        assert_eq!(
+            style.object_position,
+            Some(BackgroundPosition {
+                x: Length::Percent(100.0),
+                y: Length::Px(10.0),
+            })
+        );
+        assert_eq!(style.image_orientation, Some(ImageOrientation::None));
     }
-}
\ No newline at end of file
+}
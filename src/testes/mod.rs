@@ -1,4 +1,6 @@
 #[cfg(test)]
 mod html_tests;
 #[cfg(test)]
-mod css_tests;
\ No newline at end of file
+mod css_tests;
+#[cfg(test)]
+mod pdf_tests;
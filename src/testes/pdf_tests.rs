@@ -0,0 +1,1513 @@
+#[cfg(test)]
+mod tests {
+    use crate::css::parser::{collect_stylesheets, parse_css};
+    use crate::css::styles::{Direction, Style};
+    use crate::html::dom::{Node, NodeType};
+    use crate::html::parser::parse_html;
+    use crate::layout::bidi::reverse_run;
+    use crate::layout::box_model::{BoxContent, LayoutBox};
+    use crate::layout::engine::compute_layout;
+    use crate::layout::hyphenation::{Lang, hyphenate, load_patterns};
+    use crate::pdf::font_provider::{
+        FileFontProvider, FontProvider, MemoryFontProvider, SystemFontProvider, default_provider,
+    };
+    use crate::pdf::fonts::{Font, FontCache, font_from_data};
+    use crate::pdf::options::{ImageOptions, PdfOptions};
+    use crate::pdf::path::RoundedRect;
+    use crate::pdf::shaping::{Glyph, features, shape};
+    use crate::pdf::transform::box_transform;
+    use crate::pdf::woff;
+    use crate::pdf::writer::{write_pdf, write_pdf_with_options};
+    use crate::resources::data_url::decode_data_url;
+    use crate::resources::loader::{
+        FileLoader, MemoryLoader, ResourceError, ResourceLoader, default_loader,
+    };
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use std::io::{Read, Write};
+
+    fn render(html: &str) -> String {
+        let dom = parse_html(html);
+        let loader = default_loader();
+        let sheet = collect_stylesheets(&dom.borrow(), &loader);
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, Some(&sheet), &fonts, &loader);
+        String::from_utf8_lossy(&write_pdf(&layout, Some(&sheet), &fonts, &loader)).into_owned()
+    }
+
+    #[test]
+    fn test_per_side_borders() {
+        let pdf = render(
+            r#"<div style="border: 2px solid red; border-left: 4px dashed #00f; border-bottom-style: none"></div>"#,
+        );
+        assert!(pdf.contains("1 0 0 rg"));
+        assert!(pdf.contains("0 0 1 RG"));
+        assert!(pdf.contains("[9 9] 0 d"));
+        assert_eq!(pdf.matches("f*").count(), 2);
+    }
+
+    #[test]
+    fn test_border_radius_uses_bezier_curves() {
+        let pdf = render(
+            r#"<div style="background: #eee; border: 1px solid black; border-radius: 8px / 50%"></div>"#,
+        );
+        assert!(pdf.matches(" c\n").count() >= 8);
+        assert!(!pdf.contains("re S"));
+    }
+
+    #[test]
+    fn test_radius_overlap_is_scaled() {
+        let rect = RoundedRect::new(0.0, 0.0, 100.0, 20.0, [(50.0, 50.0); 4]);
+        assert_eq!(rect.radii[0], (10.0, 10.0));
+        let inner = rect.inset(2.0, 2.0, 2.0, 2.0);
+        assert_eq!(inner.radii[0], (8.0, 8.0));
+    }
+
+    #[test]
+    fn test_gradients_use_shadings() {
+        let pdf = render(
+            r#"<div style="height: 100px; background: radial-gradient(red, blue), linear-gradient(90deg, #fff, transparent 50%, #000)"></div>"#,
+        );
+        assert!(pdf.contains("/Shading << /Sh1 << /ShadingType 2"));
+        assert!(pdf.contains("/Sh2 << /ShadingType 3"));
+        assert!(pdf.contains("/FunctionType 3"));
+        // L'arrêt transparent passe par un masque de luminosité
+        assert!(pdf.contains("/S /Luminosity"));
+        assert!(pdf.contains("/SM1 gs\n/Sh1 sh"));
+        assert!(pdf.find("/Sh1 sh").unwrap() < pdf.find("/Sh2 sh").unwrap());
+    }
+
+    #[test]
+    fn test_background_image_tiles() {
+        let path = std::env::temp_dir().join("zyou_htmltopdf_tile.png");
+        image::RgbImage::from_pixel(20, 20, image::Rgb([255, 0, 0]))
+            .save(&path)
+            .unwrap();
+        let html = format!(
+            r#"<div style="width: 80px; height: 40px; background: url('{}') repeat-x"></div>"#,
+            path.display()
+        );
+        let pdf = render(&html);
+        assert_eq!(pdf.matches("/Img1 Do").count(), 4);
+        assert!(pdf.contains("/Width 20 /Height 20"));
+
+        let html = format!(
+            r#"<div style="width: 80px; height: 40px; background: url('{}') center / cover no-repeat"></div>"#,
+            path.display()
+        );
+        let pdf = render(&html);
+        assert_eq!(pdf.matches("/Img1 Do").count(), 1);
+        assert!(pdf.contains("q\n60 0 0 60 10 "));
+    }
+
+    #[test]
+    fn test_box_shadow_bands() {
+        let pdf = render(
+            r#"<div style="background: white; box-shadow: 4px 4px 8px rgba(0,0,0,0.5), inset 0 0 0 2px red"></div>"#,
+        );
+        // Ombre extérieure découpée hors de la boîte, puis fond, puis ombre intérieure
+        let outer = pdf.find("W* n").unwrap();
+        let background = pdf.find("1 1 1 rg").unwrap();
+        let inner = pdf.find("1 0 0 rg").unwrap();
+        assert!(outer < background && background < inner);
+        assert!(pdf.matches("\nf\n").count() > 10);
+        assert!(pdf.contains("f*\n"));
+    }
+
+    #[test]
+    fn test_text_shadow_drawn_under_text() {
+        let pdf = render(r#"<p style="text-shadow: 2px 3px 1px blue">Titre</p>"#);
+        let shadow = pdf.find("0 0 1 rg").unwrap();
+        let text = pdf.rfind("(Titre) Tj").unwrap();
+        assert!(shadow < text);
+        assert!(pdf.contains("2 Tr"));
+        assert!(pdf.matches("(Titre) Tj").count() >= 3);
+    }
+
+    #[test]
+    fn test_group_opacity_uses_transparency_group() {
+        let pdf = render(
+            r#"<div style="opacity: 0.5; background: red"><p style="background: blue">x</p></div>"#,
+        );
+        assert!(pdf.contains("/Resources 5 0 R /Contents"));
+        assert!(pdf.contains("/Group << /S /Transparency >> /Resources 5 0 R"));
+        assert!(pdf.contains("/ca 0.5"));
+        // Le fond du parent et celui de l'enfant sont dans le même groupe
+        let group = pdf.find("/Subtype /Form /BBox").unwrap();
+        assert!(pdf[group..].contains("1 0 0 rg"));
+        assert!(pdf[group..].contains("0 0 1 rg"));
+        assert!(pdf.contains("/Fm1 Do"));
+    }
+
+    #[test]
+    fn test_transform_matrix() {
+        let dom = parse_html(
+            r#"<div style="width: 40px; height: 20px; transform: rotate(90deg)"></div>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let b = &layout.children[0];
+        let [a, m_b, c, d, e, f] = box_transform(b, 842.0).unwrap();
+        // Rotation horaire à l'écran : l'axe x de la page part vers le bas
+        assert!(a.abs() < 1e-5 && d.abs() < 1e-5);
+        assert_eq!((m_b, c), (-1.0, 1.0));
+        // Le centre de la boîte est fixe
+        let (cx, cy) = (b.x + b.width / 2.0, 842.0 - b.y - b.height / 2.0);
+        assert!((a * cx + c * cy + e - cx).abs() < 1e-3);
+        assert!((m_b * cx + d * cy + f - cy).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_overflow_clips_children() {
+        let pdf = render(r#"<div style="overflow: hidden; height: 10px"><p>x</p></div>"#);
+        let clip = pdf.find("re\nW n").unwrap();
+        assert!(clip < pdf.find("(x) Tj").unwrap());
+    }
+
+    #[test]
+    fn test_bold_italic_standard_faces() {
+        let pdf = render(
+            r#"<p><b>a</b><i>b</i><b><i>c</i></b><code style="font-family: monospace; font-weight: 900">d</code></p>"#,
+        );
+        for face in [
+            "/Helvetica-Bold ",
+            "/Helvetica-Oblique ",
+            "/Helvetica-BoldOblique ",
+            "/Courier-Bold ",
+        ] {
+            assert!(pdf.contains(face), "{}", face);
+        }
+        assert!(pdf.contains("/Encoding /WinAnsiEncoding"));
+    }
+
+    #[test]
+    fn test_underline_uses_font_metrics() {
+        let pdf = render(r#"<p style="font-size: 10pt"><u>AV</u></p>"#);
+        // Helvetica : trait de 50/1000 em, centré 100/1000 em sous la ligne de base, A + V = 1334
+        assert!(pdf.contains("0.5 w\n"));
+        let text = pdf.find("(AV) Tj").unwrap();
+        let line = pdf.find(" l\nS").unwrap();
+        assert!(line < text);
+        let start = pdf[..line].rfind(" m\n").unwrap();
+        let x1: f32 = pdf[..start]
+            .rsplit(['\n', ' '])
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let x2: f32 = pdf[start + 3..line]
+            .split(' ')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((x2 - x1 - 13.34).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_line_through_drawn_over_text() {
+        let pdf = render(r#"<p><s style="text-decoration-color: red">x</s></p>"#);
+        let text = pdf.find("(x) Tj").unwrap();
+        assert!(pdf[text..].contains("1 0 0 RG"));
+    }
+
+    #[test]
+    fn test_justified_lines_fill_the_width() {
+        let dom = parse_html(
+            r#"<p style="width: 200px; text-align: justify; text-indent: 20px">Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore.</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let p = &layout.children[0];
+        assert!(p.children.len() > 2);
+        // Toutes les lignes sauf la dernière vont jusqu'au bord droit
+        let (last, lines) = p.children.split_last().unwrap();
+        for line in lines {
+            assert!((line.x + line.width - (p.x + p.width)).abs() < 0.01);
+            assert!(line.style.word_spacing.is_some());
+        }
+        assert!(last.x + last.width < p.x + p.width);
+        assert!(last.style.word_spacing.is_none());
+        assert_eq!(p.children[0].x, p.x + 15.0);
+        assert_eq!(p.children[1].x, p.x);
+        // Le bloc englobe ses lignes, de 1.2 fois la taille de police chacune
+        assert!((p.height - p.children.len() as f32 * 14.4).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_alignment_and_spacing_operators() {
+        let dom = parse_html(
+            r#"<p style="text-align: right">ab</p><p style="text-align: center">ab</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let right = &layout.children[0].children[0];
+        let center = &layout.children[1].children[0];
+        assert!((right.x + right.width - 585.0).abs() < 0.01);
+        assert!((center.x + center.width / 2.0 - 297.5).abs() < 0.01);
+
+        let pdf = render(r#"<p style="letter-spacing: 2pt; word-spacing: 4pt">un deux</p>"#);
+        assert!(pdf.contains("2 Tc"));
+        assert!(pdf.contains("[(un ) -333.33334 (deux)] TJ"));
+    }
+
+    #[test]
+    fn test_text_wraps_at_spaces() {
+        let dom = parse_html(r#"<div style="width: 60pt">aaa bbb ccc</div>"#);
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let lines: Vec<_> = layout.children[0]
+            .children
+            .iter()
+            .map(|b| match &b.content {
+                BoxContent::Text(t) => t.as_str(),
+                _ => "",
+            })
+            .collect();
+        assert_eq!(lines, ["aaa bbb", "ccc"]);
+    }
+
+    // Ligne de base d'un fragment de texte en Helvetica
+    fn baseline(b: &LayoutBox) -> f32 {
+        b.y + 0.718 * b.style.font_size.to_pt()
+    }
+
+    #[test]
+    fn test_mixed_sizes_share_a_baseline() {
+        let dom = parse_html(
+            r#"<p>a<span style="font-size: 24pt">B</span>c<sup>2</sup><sub>i</sub></p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let p = &layout.children[0];
+        let a = &p.children[0];
+        let span = &p.children[1].children[0];
+        let c = &p.children[2];
+        let sup = &p.children[3].children[0];
+        let sub = &p.children[4].children[0];
+        assert!((baseline(a) - baseline(span)).abs() < 0.01);
+        assert!((baseline(a) - baseline(c)).abs() < 0.01);
+        // Exposant et indice : un tiers et un cinquième de la taille du parent
+        assert!((baseline(a) - baseline(sup) - 4.0).abs() < 0.01);
+        assert!((baseline(sub) - baseline(a) - 2.4).abs() < 0.01);
+        assert!((sup.style.font_size.to_pt() - 9.996).abs() < 0.01);
+        // La ligne s'agrandit pour le grand texte
+        assert!(p.height > 24.0 * 1.2);
+    }
+
+    #[test]
+    fn test_line_height_values() {
+        let dom = parse_html(
+            r#"<p style="line-height: 2">a<br>b</p>
+            <p style="line-height: 30px">a<br>b</p>
+            <p style="line-height: 50%; font-size: 20pt">a</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        assert!((layout.children[0].height - 48.0).abs() < 0.01);
+        assert!((layout.children[1].height - 45.0).abs() < 0.01);
+        // Le strut du bloc impose sa hauteur de ligne
+        assert!((layout.children[2].height - 10.0).abs() < 0.01);
+        // Le texte est centré dans sa ligne par le demi-interlignage
+        let a = &layout.children[0].children[0];
+        let line_top = layout.children[0].y;
+        assert!((a.y - line_top - (12.0 - a.height / 2.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_vertical_align_top_bottom_middle() {
+        let dom = parse_html(
+            r#"<p>a<span style="display: inline-block; height: 40pt; width: 5pt"></span><span style="vertical-align: top">t</span><span style="vertical-align: bottom">b</span><span style="vertical-align: middle; display: inline-block; width: 5pt; height: 10pt"></span></p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let p = &layout.children[0];
+        let a = &p.children[0];
+        let block = &p.children[1];
+        let top = &p.children[2];
+        let bottom = &p.children[3];
+        let middle = &p.children[4];
+        // La boîte vide repose sur la ligne de base et fixe la hauteur de ligne
+        assert!((block.y + block.height - baseline(a)).abs() < 0.01);
+        assert!((top.y - p.y).abs() < 0.01);
+        assert!((bottom.y + bottom.height - (p.y + p.height)).abs() < 0.01);
+        assert!((middle.y + 5.0 - (baseline(a) - 0.523 * 6.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_nested_inline_blocks() {
+        // Chaque niveau mesure son contenu une fois : sans cela, 2^24 mises en page
+        let depth = 24;
+        let open = r#"<span style="display: inline-block; padding: 1pt">"#.repeat(depth);
+        let html = format!("<p>{}ab{}</p>", open, "</span>".repeat(depth));
+        let dom = parse_html(&html);
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let mut b = &layout.children[0].children[0];
+        let mut width = b.width;
+        for _ in 1..depth {
+            b = &b.children[0];
+            assert!((width - b.width - 2.0).abs() < 0.05);
+            width = b.width;
+        }
+    }
+
+    // Textes des fragments dans l'ordre d'affichage
+    fn line_texts(b: &LayoutBox) -> Vec<String> {
+        b.children
+            .iter()
+            .filter_map(|b| match &b.content {
+                BoxContent::Text(t) if b.style.direction == Some(Direction::Rtl) => {
+                    Some(reverse_run(t))
+                }
+                BoxContent::Text(t) => Some(t.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Motifs réduits écrits pour les tests, au format TeX
+    fn test_patterns() {
+        load_patterns(
+            Lang::English,
+            "\\patterns{\nx1t n1s b1a a1b l1i c1o % commentaire\n}",
+        );
+        load_patterns(Lang::French, "\\patterns{ é1v e1l p1p e1m }");
+        load_patterns(Lang::German, "\\patterns{ n1g a1b e1a f1f r1d e1r s1l }");
+    }
+
+    #[test]
+    fn test_hyphenation_patterns() {
+        test_patterns();
+        let split = |word: &str, lang| {
+            let breaks = hyphenate(word, lang);
+            let chars: Vec<char> = word.chars().collect();
+            let mut out = String::new();
+            for (i, c) in chars.iter().enumerate() {
+                if breaks.contains(&i) {
+                    out.push('-');
+                }
+                out.push(*c);
+            }
+            out
+        };
+        assert_eq!(split("extensive", Lang::English), "ex-ten-sive");
+        assert_eq!(split("Probability", Lang::English), "Prob-a-bil-ity");
+        assert_eq!(split("développement", Lang::French), "dé-ve-lop-pe-ment");
+        assert_eq!(
+            split("Eingabeaufforderung", Lang::German),
+            "Ein-ga-be-auf-for-de-rung"
+        );
+        assert_eq!(split("hässlich", Lang::German), "häss-lich");
+        assert_eq!(Lang::from_tag("fr-CA"), Some(Lang::French));
+        assert_eq!(Lang::from_tag("en_GB"), Some(Lang::English));
+        assert_eq!(Lang::from_tag("it"), None);
+    }
+
+    #[test]
+    fn test_hyphens_auto_follows_lang() {
+        test_patterns();
+        let html = r#"<div lang="en"><p style="width: 90pt; hyphens: auto">Hyphenation is extensively recognized.</p>
+            <p lang="xx" style="width: 90pt; hyphens: auto">Hyphenation is extensively recognized.</p>
+            <p style="width: 90pt">Hyphenation is extensively recognized.</p></div>"#;
+        let dom = parse_html(html);
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let div = &layout.children[0];
+        assert_eq!(
+            line_texts(&div.children[0]),
+            ["Hyphenation is", "extensively rec-", "ognized."]
+        );
+        // Langue inconnue ou césure manuelle : aucun mot n'est coupé
+        for p in &div.children[1..] {
+            assert!(line_texts(p).iter().all(|t| !t.ends_with('-')));
+        }
+    }
+
+    #[test]
+    fn test_soft_hyphens() {
+        let dom = parse_html(
+            r#"<p style="width: 40pt">super&shy;cali&shy;fragilistic</p>
+            <p style="width: 40pt; hyphens: none">super&shy;cali&shy;fragilistic</p>
+            <p>super&shy;cali</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        assert_eq!(
+            line_texts(&layout.children[0]),
+            ["super-", "cali-", "fragilistic"]
+        );
+        assert_eq!(line_texts(&layout.children[1]), ["supercalifragilistic"]);
+        // Sans coupure, le trait conditionnel reste invisible
+        let p = &layout.children[2];
+        assert_eq!(line_texts(p), ["supercali"]);
+        let font = Font::standard("Helvetica");
+        assert!((p.children[0].width - font.text_width("supercali", 12.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_unicode_line_breaks() {
+        let dom = parse_html(
+            r#"<p style="width: 100pt">http://example.com/some/long/path/to/resource</p>
+            <p style="width: 30pt">日本語の文章です</p>
+            <p style="width: 50pt">aaaa&nbsp;bbbb cc</p>
+            <p style="width: 50pt">bien-aimé lecteur</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        let url = line_texts(&layout.children[0]);
+        assert!(url.len() > 1);
+        assert!(url[0].ends_with('/'));
+        assert_eq!(
+            url.concat(),
+            "http://example.com/some/long/path/to/resource"
+        );
+        let cjk = line_texts(&layout.children[1]);
+        assert!(cjk.len() > 1);
+        assert_eq!(cjk.concat(), "日本語の文章です");
+        // L'espace insécable ne se coupe pas, même si la ligne déborde
+        assert_eq!(line_texts(&layout.children[2]), ["aaaa\u{a0}bbbb", "cc"]);
+        assert_eq!(
+            line_texts(&layout.children[3]),
+            ["bien-", "aimé", "lecteur"]
+        );
+    }
+
+    #[test]
+    fn test_bidi_reordering() {
+        let dom = parse_html(
+            r#"<p>a אבג דהו b</p>
+            <p dir="rtl">abc אבג (דהו)</p>
+            <p style="direction: rtl; unicode-bidi: bidi-override">abc</p>
+            <p dir="auto">אבג abc</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        assert_eq!(line_texts(&layout.children[0]), ["a ", "והד גבא", " b"]);
+        // Paragraphe de droite à gauche : aligné à droite, parenthèses en miroir
+        let rtl = &layout.children[1];
+        assert_eq!(line_texts(rtl), ["(והד) גבא ", "abc"]);
+        let end = rtl.children.last().unwrap();
+        assert!((end.x + end.width - 585.0).abs() < 0.01);
+        assert_eq!(line_texts(&layout.children[2]), ["cba"]);
+        let auto = &layout.children[3];
+        assert_eq!(line_texts(auto), ["abc", " גבא"]);
+        let end = auto.children.last().unwrap();
+        assert!((end.x + end.width - 585.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bidi_isolates_in_ltr_text() {
+        let dom = parse_html(
+            r#"<p>x <span style="display: inline; unicode-bidi: isolate; direction: rtl">א b</span> y</p>"#,
+        );
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            None,
+            &default_provider(),
+            &default_loader(),
+        );
+        // Le latin reste dans son ordre, la suite isolée se lit de droite à gauche
+        assert_eq!(line_texts(&layout.children[0]), ["x ", "b", " א", " y"]);
+    }
+
+    // Polices DejaVu réduites aux contours latins, voir fonts/LICENSE
+    const SANS: &[u8] = include_bytes!("fonts/DejaVuSans.ttf");
+    const SANS_BOLD: &[u8] = include_bytes!("fonts/DejaVuSans-Bold.ttf");
+    const SANS_LIGHT: &[u8] = include_bytes!("fonts/DejaVuSans-ExtraLight.ttf");
+    const SERIF: &[u8] = include_bytes!("fonts/DejaVuSerif.ttf");
+
+    fn dejavu() -> Font {
+        font_from_data(SANS.to_vec(), "DejaVuSans").unwrap()
+    }
+
+    fn shaped(font: &Font, text: &str, css: &str) -> Vec<Glyph> {
+        let node = Node {
+            node_type: NodeType::Element("span".to_string()),
+            attributes: vec![("style".to_string(), css.to_string())],
+            children: vec![],
+        };
+        let style = parse_css(&node, None, None, None);
+        shape(font, text, None, &features(&style)).unwrap()
+    }
+
+    #[test]
+    fn test_shaping_ligatures_and_kerning() {
+        let font = dejavu();
+        let office = shaped(&font, "office", "");
+        assert_eq!(office.len(), 4);
+        assert_eq!(office[1].text, "ffi");
+        assert_eq!(
+            shaped(&font, "office", "font-variant-ligatures: none").len(),
+            6
+        );
+        assert_eq!(
+            shaped(&font, "office", r#"font-feature-settings: "liga" 0"#).len(),
+            6
+        );
+        assert_eq!(shaped(&font, "office", "letter-spacing: 1pt").len(), 6);
+        // Crénage de la paire AV
+        let av = shaped(&font, "AV", "");
+        assert!(av[0].advance < av[0].width);
+        let av = shaped(&font, "AV", "font-kerning: none");
+        assert_eq!(av[0].advance, av[0].width);
+        assert!(shaped(&font, "x", "").iter().all(|g| g.id != 0));
+        assert!(Font::standard("Helvetica").data.is_none());
+    }
+
+    #[test]
+    fn test_glyph_coverage() {
+        let font = dejavu();
+        // Les plages lues au premier besoin donnent la même réponse que le cmap
+        let face = rustybuzz::ttf_parser::Face::parse(font.data.as_ref().unwrap(), 0).unwrap();
+        for ch in ('\0'..'\u{3000}').chain(['😀', '中']) {
+            assert_eq!(font.has_glyph(ch), face.glyph_index(ch).is_some());
+        }
+        assert!(font.has_glyph('é') && !font.has_glyph('中'));
+    }
+
+    #[test]
+    fn test_shaping_arabic_joining() {
+        let font = dejavu();
+        // Lettres liées : formes contextuelles, glyphes dans l'ordre d'affichage
+        let isolated = shaped(&font, "ب", "")[0].id;
+        let word = shaped(&font, "مرحبا", "");
+        assert_eq!(word.len(), 5);
+        assert_eq!(word[0].text, "ا");
+        assert_eq!(word[4].text, "م");
+        assert_ne!(word[1].id, isolated);
+        assert_eq!(word[1].text, "ب");
+    }
+
+    #[test]
+    fn test_font_family_list() {
+        let pdf = render(r#"<p style="font-family: 'No Such Font', Courier, serif">x</p>"#);
+        assert!(pdf.contains("/BaseFont /Courier "));
+        assert!(!pdf.contains("/Times-Roman"));
+        let pdf = render(r#"<p style="font-family: serif">x</p>"#);
+        assert!(pdf.contains("/BaseFont /Times-Roman "));
+        assert!(!pdf.contains("/Helvetica"));
+    }
+
+    #[test]
+    fn test_system_fonts_and_fallback() {
+        // Latin n'a pas l'hébreu : les familles du fournisseur sont essayées dans l'ordre
+        let mut provider = MemoryFontProvider::default();
+        provider.add("Latin", 400, false, SERIF.to_vec());
+        provider.add("DejaVu Sans", 400, false, SANS.to_vec());
+        provider.add("DejaVu Sans", 700, false, SANS_BOLD.to_vec());
+        provider.add("Other", 400, false, SANS_BOLD.to_vec());
+        let loader = default_loader();
+        let render = |html: &str| {
+            let dom = parse_html(html);
+            let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &provider, &loader);
+            String::from_utf8_lossy(&write_pdf(&layout, None, &provider, &loader)).into_owned()
+        };
+        let pdf = render(r#"<p style="font-family: 'DejaVu Sans'"><b>x</b></p>"#);
+        assert!(pdf.contains("/BaseFont /DejaVuSans-Bold "));
+        // Le latin reste en Helvetica, l'hébreu passe à la première famille qui l'a
+        let pdf = render("<p>abc אבג</p>");
+        assert!(pdf.contains("/BaseFont /Helvetica "));
+        assert!(pdf.contains("/BaseFont /DejaVuSans "));
+        assert!(!pdf.contains("/DejaVuSerif") && !pdf.contains("/DejaVuSans-Bold"));
+        assert!(pdf.contains("(abc ) Tj"));
+        assert!(pdf.contains("0 0 0 rg\n/F2 12 Tf\n[<"));
+        // La graisse demandée choisit la face de la famille de secours
+        let pdf = render(r#"<p style="font-family: Latin"><b>abc אבג</b></p>"#);
+        assert!(pdf.contains("/BaseFont /DejaVuSerif "));
+        assert!(pdf.contains("/BaseFont /DejaVuSans-Bold "));
+        assert!(!pdf.contains("/BaseFont /DejaVuSans "));
+
+        let mut fonts = FontCache::new(&[], &provider, &loader);
+        let style = Style::default();
+        // Le point-voyelle suit sa lettre
+        let runs = fonts.runs(&style, "ea א\u{5B8}b");
+        let ranges: Vec<_> = runs.iter().map(|(_, range)| range.clone()).collect();
+        assert_eq!(ranges, [0..3, 3..7, 7..8]);
+        assert_eq!(runs[0].0, runs[2].0);
+        assert_eq!(fonts.font(runs[1].0).name, "DejaVuSans");
+        // Sans famille qui couvre le caractère, il reste dans la police demandée
+        let runs = fonts.runs(&style, "a中");
+        assert_eq!(runs.len(), 1);
+    }
+
+    // Tables d'un fichier sfnt, dans l'ordre du répertoire
+    fn sfnt_tables(data: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        let u32_at = |at: usize| u32::from_be_bytes(data[at..at + 4].try_into().unwrap()) as usize;
+        let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+        (0..count)
+            .map(|k| {
+                let record = 12 + 16 * k;
+                let offset = u32_at(record + 8);
+                let tag = data[record..record + 4].try_into().unwrap();
+                (tag, data[offset..offset + u32_at(record + 12)].to_vec())
+            })
+            .collect()
+    }
+
+    fn woff(sfnt: &[u8]) -> Vec<u8> {
+        let tables = sfnt_tables(sfnt);
+        let mut directory = Vec::new();
+        let mut body = Vec::new();
+        let start = 44 + 20 * tables.len();
+        for (tag, table) in &tables {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(table).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let stored = if compressed.len() < table.len() {
+                &compressed
+            } else {
+                table
+            };
+            directory.extend(tag);
+            for value in [start + body.len(), stored.len(), table.len(), 0] {
+                directory.extend((value as u32).to_be_bytes());
+            }
+            body.extend(stored);
+            body.resize(body.len().div_ceil(4) * 4, 0);
+        }
+        let mut out = b"wOFF".to_vec();
+        out.extend(&sfnt[..4]);
+        out.extend(((start + body.len()) as u32).to_be_bytes());
+        out.extend((tables.len() as u16).to_be_bytes());
+        out.extend([0; 2]);
+        out.extend((sfnt.len() as u32).to_be_bytes());
+        out.extend([0; 24]);
+        out.extend(directory);
+        out.extend(body);
+        out
+    }
+
+    // WOFF2 sans transformation des tables : glyf et loca en version 3
+    fn woff2(sfnt: &[u8]) -> Vec<u8> {
+        let tables = sfnt_tables(sfnt);
+        let mut directory = Vec::new();
+        let mut stream = Vec::new();
+        for (tag, table) in &tables {
+            let version = if tag == b"glyf" || tag == b"loca" {
+                0xC0
+            } else {
+                0
+            };
+            directory.push(version | 63);
+            directory.extend(tag);
+            let mut length = table.len();
+            let mut digits = vec![(length & 0x7F) as u8];
+            while length > 0x7F {
+                length >>= 7;
+                digits.push((length & 0x7F) as u8 | 0x80);
+            }
+            directory.extend(digits.iter().rev());
+            stream.extend(table);
+        }
+        let mut compressed = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+            writer.write_all(&stream).unwrap();
+        }
+        let mut out = b"wOF2".to_vec();
+        out.extend(&sfnt[..4]);
+        out.extend(((48 + directory.len() + compressed.len()) as u32).to_be_bytes());
+        out.extend((tables.len() as u16).to_be_bytes());
+        out.extend([0; 2]);
+        out.extend((sfnt.len() as u32).to_be_bytes());
+        out.extend((compressed.len() as u32).to_be_bytes());
+        out.extend([0; 24]);
+        out.extend(directory);
+        out.extend(compressed);
+        out
+    }
+
+    // Fichier sfnt minimal : répertoire puis tables, sans alignement ni sommes de contrôle
+    fn sfnt(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut out = vec![0, 1, 0, 0];
+        out.extend((tables.len() as u16).to_be_bytes());
+        out.extend([0; 6]);
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in tables {
+            out.extend(tag);
+            for value in [0, offset, table.len()] {
+                out.extend((value as u32).to_be_bytes());
+            }
+            offset += table.len();
+        }
+        for (_, table) in tables {
+            out.extend(table);
+        }
+        out
+    }
+
+    #[test]
+    fn test_hostile_woff_lengths() {
+        let u32_at =
+            |data: &[u8], at: usize| u32::from_be_bytes(data[at..at + 4].try_into().unwrap());
+        // Table zlib qui se décompresse bien au-delà de la longueur annoncée
+        let big = sfnt(&[(*b"zero", vec![0; 1 << 20])]);
+        let mut data = woff(&big);
+        assert_eq!(woff::decode(data.clone()).map(|d| d.len()), Some(big.len()));
+        let compressed = u32_at(&data, 44 + 8);
+        data[44 + 12..44 + 16].copy_from_slice(&(compressed + 1).to_be_bytes());
+        assert!(woff::decode(data).is_none());
+
+        // Flux Brotli plus long que la somme des tables
+        let small = sfnt(&[(*b"zero", vec![0; 16])]);
+        let data = woff2(&small);
+        assert!(woff::decode(data.clone()).is_some());
+        let end = data.len() - u32_at(&data, 20) as usize;
+        let mut bomb = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut bomb, 4096, 5, 22);
+            writer.write_all(&vec![0; 1 << 20]).unwrap();
+        }
+        let mut data = data[..end].to_vec();
+        data[20..24].copy_from_slice(&(bomb.len() as u32).to_be_bytes());
+        data.extend(bomb);
+        assert!(woff::decode(data).is_none());
+
+        // Plus de 4095 tables : les champs de recherche du répertoire débordent
+        let tables: Vec<_> = (0..4096u32).map(|i| (i.to_be_bytes(), vec![1])).collect();
+        assert!(woff::decode(woff(&sfnt(&tables))).is_none());
+    }
+
+    #[test]
+    fn test_font_face_sources() {
+        // Source locale absente, puis URL data: en TrueType, WOFF ou WOFF2
+        for data in [SANS.to_vec(), woff(SANS), woff2(SANS)] {
+            let html = format!(
+                "<style>@font-face {{ font-family: Brand; src: local(NoSuchFont), \
+                 url(data:font/woff;base64,{}) }}</style><p style=\"font-family: Brand\">ab</p>",
+                BASE64.encode(&data)
+            );
+            let pdf = render(&html);
+            assert!(pdf.contains("/BaseFont /DejaVuSans "));
+            assert!(pdf.contains("/FontFile2"));
+            assert!(!pdf.contains("/Helvetica"));
+        }
+        // Une face par plage Unicode ; la graisse choisit parmi les faces d'une même plage
+        let mut loader = MemoryLoader::default();
+        for (url, data) in [
+            ("sans.ttf", SANS),
+            ("serif.ttf", SERIF),
+            ("bold.ttf", SANS_BOLD),
+            ("light.ttf", SANS_LIGHT),
+        ] {
+            loader.insert(url, data.to_vec());
+        }
+        let served = |html: &str| {
+            let dom = parse_html(html);
+            let sheet = collect_stylesheets(&dom.borrow(), &loader);
+            let fonts = default_provider();
+            let layout = compute_layout(&dom.borrow(), 595.0, 842.0, Some(&sheet), &fonts, &loader);
+            String::from_utf8_lossy(&write_pdf(&layout, Some(&sheet), &fonts, &loader)).into_owned()
+        };
+        let pdf = served(
+            "<style>
+            @font-face { font-family: Brand; src: url(sans.ttf); unicode-range: U+0-7F }
+            @font-face { font-family: Brand; src: url(serif.ttf); unicode-range: U+80-FF }
+            @font-face {
+                font-family: Brand; src: url(bold.ttf); font-weight: 600 900; unicode-range: U+0-7F
+            }
+            </style><p style=\"font-family: Brand\">a é <b>b</b></p>",
+        );
+        assert!(pdf.contains("/BaseFont /DejaVuSans "));
+        assert!(pdf.contains("/BaseFont /DejaVuSerif "));
+        assert!(pdf.contains("/BaseFont /DejaVuSans-Bold "));
+        // `font-weight: 300` prend la face déclarée à 300, pas celle à 400
+        let pdf = served(
+            "<style>
+            @font-face { font-family: Brand; src: url(sans.ttf); font-weight: 400 }
+            @font-face { font-family: Brand; src: url(light.ttf); font-weight: 300 }
+            </style><p style=\"font-family: Brand; font-weight: 300\">a</p>",
+        );
+        assert!(pdf.contains("/BaseFont /DejaVuSans-ExtraLight "));
+        assert!(!pdf.contains("/BaseFont /DejaVuSans "));
+    }
+
+    #[test]
+    fn test_font_providers() {
+        // Polices en mémoire seulement : ni `fonts/` ni polices du système
+        let mut memory = MemoryFontProvider::default();
+        memory.add("Brand", 400, false, SANS.to_vec());
+        memory.add("Brand", 700, false, SANS_BOLD.to_vec());
+        let fonts: Vec<Box<dyn FontProvider>> = vec![Box::new(memory)];
+        let dom = parse_html(r#"<p style="font-family: Brand, Unknown">a <b>b</b></p>"#);
+        let loader = default_loader();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &loader);
+        let pdf = String::from_utf8_lossy(&write_pdf(&layout, None, &fonts, &loader)).into_owned();
+        assert!(pdf.contains("/BaseFont /DejaVuSans "));
+        assert!(pdf.contains("/BaseFont /DejaVuSans-Bold "));
+        assert!(fonts.font("Unknown", 400, false).is_none());
+        assert_eq!(fonts.families(), ["Brand"]);
+
+        // Dossier de polices : la graisse manquante retombe sur le fichier sans suffixe
+        let folder = std::env::temp_dir().join("zyou_htmltopdf_fonts");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("Brand.ttf"), SANS).unwrap();
+        let files = FileFontProvider::new(&folder);
+        assert_eq!(files.font("Brand", 700, true).as_deref(), Some(SANS));
+        assert_eq!(files.font("Other", 400, false), None);
+    }
+
+    #[test]
+    #[ignore = "lit les polices installées sur la machine (DejaVu)"]
+    fn test_system_font_provider() {
+        let system = SystemFontProvider;
+        let data = system.font("DejaVu Sans", 700, false).unwrap();
+        let face = rustybuzz::ttf_parser::Face::parse(&data, 0).unwrap();
+        assert!(face.is_bold());
+        assert!(system.font("No Such Family", 400, false).is_none());
+        assert!(system.families().iter().any(|f| f == "DejaVu Sans"));
+    }
+
+    #[test]
+    fn test_numeric_font_weights() {
+        // 200 et 900 ne se confondent plus avec 400 et 700
+        let mut memory = MemoryFontProvider::default();
+        memory.add("Brand", 200, false, SANS_LIGHT.to_vec());
+        memory.add("Brand", 400, false, SANS.to_vec());
+        memory.add("Brand", 700, false, SANS.to_vec());
+        memory.add("Brand", 900, false, SANS_BOLD.to_vec());
+        let fonts: Vec<Box<dyn FontProvider>> = vec![Box::new(memory)];
+        let html =
+            r#"<span style="font-weight: 200">a</span><span style="font-weight: 900">b</span>"#;
+        let dom = parse_html(&format!(r#"<p style="font-family: Brand">{}</p>"#, html));
+        let loader = default_loader();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &loader);
+        let pdf = String::from_utf8_lossy(&write_pdf(&layout, None, &fonts, &loader)).into_owned();
+        assert!(pdf.contains("/BaseFont /DejaVuSans-ExtraLight "));
+        assert!(pdf.contains("/BaseFont /DejaVuSans-Bold "));
+        assert!(!pdf.contains("/BaseFont /DejaVuSans "));
+    }
+
+    #[test]
+    fn test_resource_loaders() {
+        // Document généré en mémoire : feuille liée, image et police servies par l'application
+        let mut png = Vec::new();
+        ::image::RgbImage::from_pixel(4, 2, ::image::Rgb([255, 0, 0]))
+            .write_to(
+                &mut std::io::Cursor::new(&mut png),
+                ::image::ImageFormat::Png,
+            )
+            .unwrap();
+        let mut memory = MemoryLoader::default();
+        memory.insert("logo.png", png.clone());
+        memory.insert(
+            "css/site.css",
+            b"p { color: #00f } @font-face { font-family: Brand; src: url(brand.ttf) } \
+              div { width: 4px; height: 4px; --tile: url('img/tile.png'); background-image: var(--tile) }"
+                .to_vec(),
+        );
+        memory.insert("css/img/tile.png", png);
+        memory.insert("css/brand.ttf", SANS.to_vec());
+        let loader: Vec<Box<dyn ResourceLoader>> =
+            vec![Box::new(memory), Box::new(default_loader())];
+        let dom = parse_html(
+            r#"<link rel="stylesheet" href="css/site.css"><p style="font-family: Brand">a</p><img src="logo.png"><div></div>"#,
+        );
+        let sheet = collect_stylesheets(&dom.borrow(), &loader);
+        // Les url() d'une feuille liée sont relatives à elle
+        assert_eq!(
+            sheet.rules["div"].custom_properties["--tile"],
+            "url(\"css/img/tile.png\")"
+        );
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, Some(&sheet), &fonts, &loader);
+        let image = &layout.children[1];
+        assert_eq!((image.width, image.height), (3.0, 1.5));
+        let pdf = String::from_utf8_lossy(&write_pdf(&layout, Some(&sheet), &fonts, &loader))
+            .into_owned();
+        assert!(pdf.contains("0 0 1 rg"));
+        assert!(pdf.contains("/Subtype /Image /Width 4 /Height 2"));
+        assert!(pdf.contains("/Img2 Do"));
+        assert!(pdf.contains("/BaseFont /DejaVuSans "));
+        assert!(matches!(
+            loader.load("missing.png"),
+            Err(ResourceError::IoError(_))
+        ));
+
+        // Dossier de base, dossiers autorisés et taille maximale
+        let base = std::env::temp_dir().join("zyou_htmltopdf_loader");
+        std::fs::create_dir_all(base.join("assets")).unwrap();
+        std::fs::write(base.join("assets/a.css"), "p {}").unwrap();
+        std::fs::write(base.join("secret.txt"), "secret").unwrap();
+        let files = FileLoader::new(&base).allow("assets").max_size(5);
+        assert_eq!(files.load("assets/a.css").unwrap(), b"p {}");
+        assert!(matches!(
+            files.load("assets/../secret.txt"),
+            Err(ResourceError::NotAllowed(_))
+        ));
+        assert!(matches!(
+            files.load("http://example.com/a.css"),
+            Err(ResourceError::NotAllowed(_))
+        ));
+        std::fs::write(base.join("assets/big.css"), "p { color: red }").unwrap();
+        assert!(matches!(
+            files.load("assets/big.css"),
+            Err(ResourceError::TooLarge(_))
+        ));
+    }
+
+    fn png_data_url(width: u32, height: u32) -> String {
+        let mut png = Vec::new();
+        ::image::RgbImage::from_pixel(width, height, ::image::Rgb([0, 0, 255]))
+            .write_to(
+                &mut std::io::Cursor::new(&mut png),
+                ::image::ImageFormat::Png,
+            )
+            .unwrap();
+        format!("data:image/png;base64,{}", BASE64.encode(png))
+    }
+
+    // Chargeur d'application qui ne connaît aucune URL
+    struct EmptyLoader;
+
+    impl ResourceLoader for EmptyLoader {
+        fn load(&self, url: &str) -> Result<Vec<u8>, ResourceError> {
+            Err(ResourceError::NotFound(url.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_data_urls() {
+        // Décodées avant tout chargeur, même celui de l'application
+        let dom = parse_html(&format!(
+            r#"<link rel="stylesheet" href="data:text/css,p%20%7B%20color%3A%20%2300f%20%7D">
+            <p>a</p><img src="{}">
+            <div style="height: 20px; background-image: url('{}')"></div>"#,
+            png_data_url(4, 2),
+            png_data_url(2, 2)
+        ));
+        let sheet = collect_stylesheets(&dom.borrow(), &EmptyLoader);
+        let fonts = default_provider();
+        let layout = compute_layout(
+            &dom.borrow(),
+            595.0,
+            842.0,
+            Some(&sheet),
+            &fonts,
+            &EmptyLoader,
+        );
+        let pdf = write_pdf(&layout, Some(&sheet), &fonts, &EmptyLoader);
+        let pdf = String::from_utf8_lossy(&pdf);
+        assert!(pdf.contains("0 0 1 rg"));
+        assert!(pdf.contains("/Subtype /Image /Width 4 /Height 2"));
+        assert!(pdf.contains("/Subtype /Image /Width 2 /Height 2"));
+
+        assert_eq!(
+            decode_data_url("data:,caf%C3%A9").unwrap(),
+            "café".as_bytes()
+        );
+        assert_eq!(
+            decode_data_url("DATA:text/plain;charset=utf-8,a b").unwrap(),
+            b"a b"
+        );
+        assert_eq!(decode_data_url("data:;base64,YW\nJj").unwrap(), b"abc");
+        assert_eq!(decode_data_url("data:;base64,YWI%3D").unwrap(), b"ab");
+        assert!(matches!(
+            decode_data_url("data:text/plain,%zz"),
+            Err(ResourceError::InvalidDataUrl(_))
+        ));
+        assert!(decode_data_url("data:text/plain").is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let pdf = render(
+            r##"<p>Graphique <svg width="100" height="50" viewBox="0 0 200 100">
+                <defs><linearGradient id="g"><stop offset="0" stop-color="#f00"/>
+                <stop offset="1" stop-color="#00f" stop-opacity="0.5"/></linearGradient></defs>
+                <rect width="200" height="100" fill="url(#g)"/>
+                <g transform="translate(10 20)" opacity="0.5">
+                <circle cx="50" cy="50" r="40" fill="none" stroke="#0f0" stroke-width="4"
+                    stroke-dasharray="5 3"/></g>
+                <path d="M0 0 Q 50 100 100 0 Z" fill="#ff0" fill-rule="evenodd"/>
+                </svg></p>"##,
+        );
+        // Taille des attributs en points, puis viewBox réduit de moitié
+        assert!(pdf.contains("75 37.5 re W n\n0.75 0 0 -0.75 "));
+        assert!(pdf.contains("0.5 0 0 0.5 0 0 cm"));
+        // Dégradé objectBoundingBox rapporté à la boîte du rectangle
+        assert!(pdf.contains("W n\n200 0 0 100 0 0 cm\n/SM1 gs\n/Sh1 sh"));
+        assert!(pdf.contains("/ShadingType 2 /ColorSpace /DeviceRGB /Coords [0 0 1 0]"));
+        assert!(pdf.contains("/ShadingType 2 /ColorSpace /DeviceGray"));
+        assert!(pdf.contains("1 0 0 1 10 20 cm"));
+        assert!(pdf.contains("4 w 0 J 0 j 4 M\n[5 3] 0 d\n0 1 0 RG"));
+        assert!(pdf.contains("/Fm1 Do"));
+        assert!(
+            pdf.contains("1 1 0 rg\n0 0 m\n33.333336 66.66667 66.666664 66.66667 100 0 c\nh\nf*")
+        );
+        assert!(!pdf.contains("/Subtype /Image"));
+
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="40" height="40">
+            <image href="dot.png" width="10" height="10"/>
+            <text x="5" y="30" font-family="DejaVu Sans" font-size="20">A</text></svg>"##;
+        let mut png = Vec::new();
+        ::image::RgbImage::from_pixel(3, 3, ::image::Rgb([0, 0, 255]))
+            .write_to(
+                &mut std::io::Cursor::new(&mut png),
+                ::image::ImageFormat::Png,
+            )
+            .unwrap();
+        let mut memory = MemoryLoader::default();
+        memory.insert("icons/logo.svg", svg.to_vec());
+        memory.insert("icons/dot.png", png);
+        let mut fonts = MemoryFontProvider::default();
+        fonts.add("DejaVu Sans", 400, false, SANS.to_vec());
+        let dom = parse_html(r#"<img src="icons/logo.svg" style="width: 60px">"#);
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &memory);
+        let image = &layout.children[0];
+        assert_eq!((image.width, image.height), (45.0, 45.0));
+        let pdf = String::from_utf8_lossy(&write_pdf(&layout, None, &fonts, &memory)).into_owned();
+        assert!(pdf.contains("/Subtype /Image /Width 3 /Height 3"));
+        assert!(pdf.contains("3.3333333 0 0 3.3333333 0 0 cm\nq\n3 0 0 -3 0 3 cm\n/Img1 Do"));
+        // Texte en contours : aucune police écrite
+        assert!(pdf.contains("0 0 0 rg\n11.8359375 17.363281 m"));
+        assert!(!pdf.contains("/FontFile2"));
+    }
+
+    fn encode(image: ::image::DynamicImage, format: ::image::ImageFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_image_formats() {
+        use ::image::{DynamicImage, ImageBuffer, ImageFormat, Rgb, Rgba, RgbaImage};
+        let mut memory = MemoryLoader::default();
+        let red = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+        let mut gif = Vec::new();
+        {
+            let mut encoder = ::image::codecs::gif::GifEncoder::new(&mut gif);
+            let blue = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 255]));
+            for frame in [red.clone(), blue] {
+                encoder.encode_frame(::image::Frame::new(frame)).unwrap();
+            }
+        }
+        memory.insert("anim.gif", gif);
+        let translucent = RgbaImage::from_pixel(3, 1, Rgba([0, 255, 0, 128]));
+        memory.insert(
+            "photo.webp",
+            encode(DynamicImage::ImageRgba8(translucent), ImageFormat::WebP),
+        );
+        memory.insert(
+            "icon.bmp",
+            encode(DynamicImage::ImageRgba8(red), ImageFormat::Bmp),
+        );
+        let deep = ImageBuffer::from_pixel(5, 1, Rgb([0xFFFFu16, 0x8000, 0]));
+        memory.insert(
+            "deep.png",
+            encode(DynamicImage::ImageRgb16(deep), ImageFormat::Png),
+        );
+        let gray = DynamicImage::ImageLuma8(ImageBuffer::from_pixel(6, 1, ::image::Luma([9u8])));
+        memory.insert("gray.tif", encode(gray, ImageFormat::Tiff));
+        memory.insert(
+            "photo.jpg",
+            encode(
+                DynamicImage::ImageRgb8(ImageBuffer::from_pixel(7, 1, Rgb([1, 2, 3]))),
+                ImageFormat::Jpeg,
+            ),
+        );
+        // CMYK sur trois bandes d'une ligne
+        let mut tiff = std::io::Cursor::new(Vec::new());
+        {
+            let mut encoder = tiff::encoder::TiffEncoder::new(&mut tiff).unwrap();
+            let mut image = encoder
+                .new_image::<tiff::encoder::colortype::CMYK8>(2, 3)
+                .unwrap();
+            image.rows_per_strip(1).unwrap();
+            image
+                .write_data(&[
+                    10, 20, 30, 40, 10, 20, 30, 40, 1, 2, 3, 4, 1, 2, 3, 4, 5, 6, 7, 8, 5, 6, 7, 8,
+                ])
+                .unwrap();
+        }
+        memory.insert("print.tif", tiff.into_inner());
+        // En-tête seul : marqueur Adobe puis trame de base à quatre composantes
+        let mut cmyk = vec![0xFF, 0xD8, 0xFF, 0xEE, 0x00, 0x0E];
+        cmyk.extend(b"Adobe\x00\x64\x00\x00\x00\x00\x02");
+        cmyk.extend([0xFF, 0xC0, 0x00, 0x14, 8, 0x00, 0x04, 0x00, 0x08, 4]);
+        cmyk.extend([1, 0x11, 0, 2, 0x11, 0, 3, 0x11, 0, 4, 0x11, 0]);
+        cmyk.extend([0xFF, 0xD9]);
+        memory.insert("adobe.jpg", cmyk);
+
+        let dom = parse_html(
+            r#"<img src="anim.gif"><img src="photo.webp"><img src="icon.bmp"><img src="deep.png">
+            <img src="gray.tif"><img src="photo.jpg"><img src="print.tif"><img src="adobe.jpg">"#,
+        );
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &memory);
+        let pdf = write_pdf(&layout, None, &fonts, &memory);
+        let text = String::from_utf8_lossy(&pdf);
+        let find =
+            |data: &[u8], needle: &[u8]| data.windows(needle.len()).position(|w| w == needle);
+        // Échantillons décompressés du flux qui suit `dict`
+        let image = |dict: &str| {
+            let start = find(&pdf, dict.as_bytes()).unwrap_or_else(|| panic!("{}", dict));
+            let data = start + find(&pdf[start..], b"stream\n").unwrap() + 7;
+            let end = data + find(&pdf[data..], b"\nendstream").unwrap();
+            let mut samples = Vec::new();
+            flate2::read::ZlibDecoder::new(&pdf[data..end])
+                .read_to_end(&mut samples)
+                .unwrap();
+            samples
+        };
+        // Première image du GIF
+        assert!(
+            image(
+                "/Width 2 /Height 2 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode"
+            )
+            .starts_with(&[255, 0, 0, 255, 0, 0])
+        );
+        assert!(
+            text.contains("/Width 3 /Height 1 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode /SMask")
+        );
+        assert!(
+            image("/Width 3 /Height 1 /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode")
+                .starts_with(&[128, 128, 128])
+        );
+        assert!(
+            image("/Width 5 /Height 1 /ColorSpace /DeviceRGB /BitsPerComponent 16 /Filter /FlateDecode")
+                .starts_with(&[0xFF, 0xFF, 0x80, 0x00, 0, 0])
+        );
+        assert!(text.contains("/Width 6 /Height 1 /ColorSpace /DeviceGray /BitsPerComponent 8"));
+        assert!(text.contains(
+            "/Width 7 /Height 1 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"
+        ));
+        assert!(
+            image("/Width 2 /Height 3 /ColorSpace /DeviceCMYK /BitsPerComponent 8 /Filter /FlateDecode")
+                .starts_with(&[10, 20, 30, 40, 10, 20, 30, 40, 1, 2, 3, 4, 1, 2, 3, 4, 5])
+        );
+        assert!(text.contains(
+            "/Width 8 /Height 4 /ColorSpace /DeviceCMYK /BitsPerComponent 8 /Filter /DCTDecode /Decode [1 0 1 0 1 0 1 0]"
+        ));
+        // Taille de mise en page lue dans l'en-tête JPEG
+        let adobe = layout
+            .children
+            .iter()
+            .find(|b| matches!(&b.content, BoxContent::Image(src) if src == "adobe.jpg"))
+            .unwrap();
+        assert_eq!((adobe.width, adobe.height), (6.0, 3.0));
+    }
+
+    #[test]
+    fn test_image_orientation_and_object_fit() {
+        use ::image::{DynamicImage, ImageBuffer, ImageFormat, Rgb};
+        let wide = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(4, 2, Rgb([1, 2, 3])));
+        let mut memory = MemoryLoader::default();
+        memory.insert("wide.png", encode(wide.clone(), ImageFormat::Png));
+        // Segment APP1 : orientation 6 (quart de tour horaire)
+        let jpeg = encode(wide, ImageFormat::Jpeg);
+        let mut rotated = jpeg[..2].to_vec();
+        rotated.extend([0xFF, 0xE1, 0x00, 0x22]);
+        rotated.extend(b"Exif\0\0MM\0*\0\0\0\x08\0\x01");
+        rotated.extend([0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0]);
+        rotated.extend(&jpeg[2..]);
+        memory.insert("rotated.jpg", rotated);
+
+        let dom = parse_html(
+            r#"<img src="rotated.jpg"><img src="rotated.jpg" style="image-orientation: none">
+            <img src="wide.png" style="width: 40px; height: 40px; object-fit: contain">
+            <img src="wide.png" style="width: 40px; height: 40px; object-fit: cover; object-position: left">
+            <img src="wide.png" style="width: 40px; height: 40px; object-fit: none; object-position: 0 100%">"#,
+        );
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &memory);
+        let sizes: Vec<_> = layout
+            .children
+            .iter()
+            .filter(|b| matches!(b.content, BoxContent::Image(_)))
+            .map(|b| (b.width, b.height, b.x, 842.0 - b.y - b.height))
+            .collect();
+        // Boîte redressée, sauf avec image-orientation: none
+        assert_eq!((sizes[0].0, sizes[0].1), (1.5, 3.0));
+        assert_eq!((sizes[1].0, sizes[1].1), (3.0, 1.5));
+        let pdf = String::from_utf8_lossy(&write_pdf(&layout, None, &fonts, &memory)).into_owned();
+        let (x, y) = (sizes[0].2, sizes[0].3);
+        assert!(pdf.contains(&format!(
+            "q\n0 -3 1.5 0 {} {} cm\n/Img1 Do\nQ\n",
+            x,
+            y + 3.0
+        )));
+        let (x, y) = (sizes[1].2, sizes[1].3);
+        assert!(pdf.contains(&format!("q\n3 0 0 1.5 {} {} cm\n/Img1 Do\nQ\n", x, y)));
+        // contain : centrée verticalement, sans découpe
+        let (x, y) = (sizes[2].2, sizes[2].3);
+        assert!(pdf.contains(&format!("q\n30 0 0 15 {} {} cm\n/Img2 Do\nQ\n", x, y + 7.5)));
+        // cover : débordement à droite découpé
+        let (x, y) = (sizes[3].2, sizes[3].3);
+        assert!(pdf.contains(&format!(
+            "q\n{x} {y} 30 30 re W n\n60 0 0 30 {x} {y} cm\n/Img2 Do\nQ\n"
+        )));
+        // none : taille naturelle en bas à gauche
+        let (x, y) = (sizes[4].2, sizes[4].3);
+        assert!(pdf.contains(&format!("q\n3 0 0 1.5 {} {} cm\n/Img2 Do\nQ\n", x, y)));
+    }
+
+    #[test]
+    fn test_image_optimization() {
+        use ::image::{DynamicImage, ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+        // Bruit : la compression JPEG a de quoi gagner
+        let mut seed = 7u32;
+        let photo = RgbImage::from_fn(400, 200, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            Rgb([(seed >> 24) as u8, (seed >> 16) as u8, (seed >> 8) as u8])
+        });
+        let mut memory = MemoryLoader::default();
+        memory.insert(
+            "photo.png",
+            encode(DynamicImage::ImageRgb8(photo), ImageFormat::Png),
+        );
+        let logo = RgbaImage::from_fn(4, 4, |x, _| Rgba([255, 0, 0, 60 * x as u8]));
+        memory.insert(
+            "logo.png",
+            encode(DynamicImage::ImageRgba8(logo), ImageFormat::Png),
+        );
+        let dom = parse_html(
+            r#"<img src="photo.png" style="width: 150px; height: 75px">
+            <img src="photo.png" style="width: 100px; height: 50px">
+            <div style="width: 3px; height: 3px; background: url(logo.png); background-size: 3px 3px"></div>"#,
+        );
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &memory);
+        let pdf = |options: &PdfOptions| {
+            let (pdf, stats) = write_pdf_with_options(&layout, None, &fonts, &memory, options);
+            (String::from_utf8_lossy(&pdf).into_owned(), stats)
+        };
+        // Octets des flux d'image écrits, masques compris
+        let written = |text: &str| -> usize {
+            text.match_indices("/Subtype /Image ")
+                .map(|(at, _)| {
+                    let length = &text[at + text[at..].find("/Length ").unwrap() + 8..];
+                    length[..length.find(' ').unwrap()]
+                        .parse::<usize>()
+                        .unwrap()
+                })
+                .sum()
+        };
+
+        // Sans option : rien ne change
+        let (text, stats) = pdf(&PdfOptions::default());
+        assert_eq!(
+            text,
+            String::from_utf8_lossy(&write_pdf(&layout, None, &fonts, &memory))
+        );
+        assert_eq!(
+            (stats.images, stats.optimized, stats.saved_bytes()),
+            (2, 0, 0)
+        );
+        assert_eq!(stats.original_bytes, written(&text));
+
+        // 72 ppp à la plus grande taille de dessin (112,5 × 56,25 pt)
+        let mut options = PdfOptions {
+            images: ImageOptions {
+                max_dpi: Some(72.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (text, stats) = pdf(&options);
+        assert!(text.contains(
+            "/Width 113 /Height 57 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode"
+        ));
+        // Le fond de 2,25 pt garde un pixel par point, masque compris
+        assert!(
+            text.contains("/Width 3 /Height 3 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode /SMask")
+        );
+        assert!(text.contains(
+            "/Width 3 /Height 3 /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode"
+        ));
+        assert_eq!(stats.optimized, 2);
+        assert_eq!(stats.written_bytes, written(&text));
+        assert_eq!(
+            stats.saved_bytes(),
+            stats.original_bytes - stats.written_bytes
+        );
+
+        options.images.grayscale = true;
+        options.images.jpeg_quality = Some(60);
+        let (text, stats) = pdf(&options);
+        assert!(text.contains(
+            "/Width 113 /Height 57 /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /DCTDecode"
+        ));
+        // Trop petit pour gagner à la compression JPEG
+        assert!(
+            text.contains("/Width 3 /Height 3 /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode /SMask")
+        );
+        assert!(stats.written_bytes < 113 * 57);
+        assert_eq!(stats.written_bytes, written(&text));
+    }
+
+    #[test]
+    fn test_icc_profiles() {
+        use ::image::codecs::jpeg::JpegEncoder;
+        use ::image::codecs::png::PngEncoder;
+        use ::image::{ExtendedColorType, ImageEncoder};
+        let srgb = moxcms::ColorProfile::new_srgb().encode().unwrap();
+        let gray = moxcms::ColorProfile::new_gray_with_gamma(2.2)
+            .encode()
+            .unwrap();
+        let tagged =
+            |png: bool, pixels: &[u8], width: u32, color: ExtendedColorType, icc: &[u8]| {
+                let mut data = Vec::new();
+                let height =
+                    (pixels.len() / width as usize / (color.bits_per_pixel() as usize / 8)) as u32;
+                match png {
+                    true => {
+                        let mut encoder = PngEncoder::new(&mut data);
+                        encoder.set_icc_profile(icc.to_vec()).unwrap();
+                        encoder.write_image(pixels, width, height, color)
+                    }
+                    false => {
+                        let mut encoder = JpegEncoder::new(&mut data);
+                        encoder.set_icc_profile(icc.to_vec()).unwrap();
+                        encoder.write_image(pixels, width, height, color)
+                    }
+                }
+                .unwrap();
+                data
+            };
+        let mut memory = MemoryLoader::default();
+        let rgb = ExtendedColorType::Rgb8;
+        memory.insert("rgb.png", tagged(true, &[1, 2, 3, 4, 5, 6], 2, rgb, &srgb));
+        memory.insert(
+            "gray.jpg",
+            tagged(false, &[9; 9], 3, ExtendedColorType::L8, &gray),
+        );
+        // Profil RGB sur une image en gris : ignoré
+        memory.insert(
+            "wrong.png",
+            tagged(true, &[7; 4], 4, ExtendedColorType::L8, &srgb),
+        );
+        let dom = parse_html(r#"<img src="rgb.png"><img src="gray.jpg"><img src="wrong.png">"#);
+        let fonts = default_provider();
+        let layout = compute_layout(&dom.borrow(), 595.0, 842.0, None, &fonts, &memory);
+        let pdf = write_pdf(&layout, None, &fonts, &memory);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(!text.contains("/OutputIntents"));
+        let ids: Vec<_> = text
+            .match_indices("/ColorSpace [/ICCBased ")
+            .map(|(i, _)| text[i + 23..].split(' ').next().unwrap().to_string())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(text.contains(&format!(
+            "{} 0 obj\n<< /N 3 /Alternate /DeviceRGB /Length {} >>",
+            ids[0],
+            srgb.len()
+        )));
+        assert!(text.contains(&format!(
+            "{} 0 obj\n<< /N 1 /Alternate /DeviceGray /Length {} >>",
+            ids[1],
+            gray.len()
+        )));
+        assert!(text.contains("/Width 3 /Height 3 /ColorSpace [/ICCBased"));
+        assert!(text.contains("/Width 4 /Height 1 /ColorSpace /DeviceGray"));
+        assert!(pdf.windows(srgb.len()).any(|w| w == srgb));
+
+        // Le passage en gris abandonne le profil RGB
+        let options = PdfOptions {
+            images: ImageOptions {
+                grayscale: true,
+                ..Default::default()
+            },
+            srgb_output_intent: true,
+        };
+        let (pdf, _) = write_pdf_with_options(&layout, None, &fonts, &memory, &options);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Width 2 /Height 1 /ColorSpace /DeviceGray"));
+        assert!(text.contains("/Catalog /Pages 2 0 R /OutputIntents [<< /Type /OutputIntent /S /GTS_PDFA1 /OutputConditionIdentifier (sRGB IEC61966-2.1) /Info (sRGB IEC61966-2.1) /DestOutputProfile 6 0 R >>]"));
+        // Le profil écrit est complet
+        assert!(text.contains(&format!(
+            "6 0 obj\n<< /N 3 /Alternate /DeviceRGB /Length {} >>",
+            srgb.len()
+        )));
+        // Table de références croisées toujours alignée sur les objets
+        let xref = text.rfind("xref\n").unwrap();
+        let offsets: Vec<usize> = text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+}